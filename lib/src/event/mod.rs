@@ -1,3 +1,5 @@
+use std::cell::Cell;
+
 use sdl2::rect::{FPoint, FRect};
 
 mod _enum;
@@ -5,18 +7,121 @@ mod from;
 
 pub use _enum::Event;
 
+thread_local! {
+    static HANDLED: Cell<bool> = const { Cell::new(false) };
+}
+
+///Marks the event being dispatched as used, so the elements it went through can ignore it.
+pub fn mark_handled() {
+    HANDLED.set(true);
+}
+
+///Whether the event was marked as used since the last call.
+pub fn take_handled() -> bool {
+    HANDLED.replace(false)
+}
+
 impl Event {
     pub fn hover(&self, sub: FRect) -> bool {
-        match self {
-            &Self::MouseMotion { x, y, .. }
-            | &Self::MouseButtonDown { x, y, .. }
-            | &Self::MouseButtonUp { x, y, .. }
-            | &Self::MouseWheel {
+        self.position()
+            .is_none_or(|point| sub.contains_point(point))
+    }
+
+    ///The mouse position of the pointer events.
+    pub fn position(&self) -> Option<FPoint> {
+        match *self {
+            Self::MouseMotion { x, y, .. }
+            | Self::MouseButtonDown { x, y, .. }
+            | Self::MouseButtonUp { x, y, .. }
+            | Self::MouseWheel {
                 mouse_x: x,
                 mouse_y: y,
                 ..
-            } => sub.contains_point(FPoint::new(x, y)),
-            _ => true,
+            } => Some(FPoint::new(x, y)),
+            _ => None,
+        }
+    }
+
+    ///Whether the event comes from the user through the mouse, the keyboard or a touch screen.
+    pub const fn is_input(&self) -> bool {
+        matches!(
+            self,
+            Self::KeyDown { .. }
+                | Self::KeyUp { .. }
+                | Self::TextEditing { .. }
+                | Self::TextInput { .. }
+                | Self::MouseMotion { .. }
+                | Self::MouseButtonDown { .. }
+                | Self::MouseButtonUp { .. }
+                | Self::MouseWheel { .. }
+                | Self::FingerDown { .. }
+                | Self::FingerUp { .. }
+                | Self::FingerMotion { .. }
+                | Self::DollarGesture { .. }
+                | Self::MultiGesture { .. }
+        )
+    }
+
+    ///Moves the mouse position of the event by (dx, dy).
+    pub fn translate(self, dx: f32, dy: f32) -> Self {
+        match self {
+            Self::MouseMotion {
+                which,
+                mousestate,
+                x,
+                y,
+                moved_x,
+                moved_y,
+            } => Self::MouseMotion {
+                which,
+                mousestate,
+                x: x + dx,
+                y: y + dy,
+                moved_x,
+                moved_y,
+            },
+            Self::MouseButtonDown {
+                which,
+                mouse_btn,
+                clicks,
+                x,
+                y,
+            } => Self::MouseButtonDown {
+                which,
+                mouse_btn,
+                clicks,
+                x: x + dx,
+                y: y + dy,
+            },
+            Self::MouseButtonUp {
+                which,
+                mouse_btn,
+                clicks,
+                x,
+                y,
+            } => Self::MouseButtonUp {
+                which,
+                mouse_btn,
+                clicks,
+                x: x + dx,
+                y: y + dy,
+            },
+            Self::MouseWheel {
+                which,
+                scroll_x,
+                scroll_y,
+                direction,
+                mouse_x,
+                mouse_y,
+            } => Self::MouseWheel {
+                which,
+                scroll_x,
+                scroll_y,
+                direction,
+                mouse_x: mouse_x + dx,
+                mouse_y: mouse_y + dy,
+            },
+            event => event,
         }
     }
 }
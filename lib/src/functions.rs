@@ -22,6 +22,11 @@ pub enum StateEnum {
 }
 pub type FnState<Element, Parent, State> =
     Box<dyn Fn(Ref<Element>, Ref<Parent>, Ref<State>) -> StateEnum>;
+pub type FnChanged<Element, Parent, State> =
+    Box<dyn Fn(Ref<Element>, Ref<Parent>, Ref<State>) -> bool>;
+///The progress from 0 to 1, None when it is unknown.
+pub type FnProgress<Element, Parent, State> =
+    Box<dyn Fn(Ref<Element>, Ref<Parent>, Ref<State>) -> Option<f32>>;
 pub type FnColor<Element, Parent, State> =
     Box<dyn Fn(Ref<Element>, Ref<Parent>, Ref<State>) -> Color>;
 pub type FnImage<Element, Parent, State> =
@@ -4,6 +4,7 @@ extern crate sdl2;
 pub mod event;
 pub mod functions;
 pub mod missing;
+pub mod overlay;
 pub mod refs;
 pub mod state_manager;
 pub mod ui_element;
@@ -64,6 +65,7 @@ pub fn run_event<State: 'static, Game: EventWindow<State> + 'static>(
     state_func: impl FnOnce(&mut Canvas<Window>) -> Result<State>,
     func: impl FnOnce(&mut Canvas<Window>, MutRef<State>) -> Result<Game>,
 ) -> Result<()> {
+    let _overlay = overlay::RunGuard::new();
     let (sdl_context, mut canvas) = init(title, width, height, window)?;
 
     let (last_x, last_y) = canvas.window().position();
@@ -103,7 +105,10 @@ pub fn run_event<State: 'static, Game: EventWindow<State> + 'static>(
         let mut a = false;
         loop {
             for event in event_pump.poll_iter() {
-                Game::event(game, &canvas, event.into(), parent, state)?;
+                let event: Event = event.into();
+                if !overlay::event(&canvas, &event, state)? {
+                    Game::event(game, &canvas, event, parent, state)?;
+                }
                 a = true;
             }
 
@@ -140,7 +145,9 @@ pub fn run_event<State: 'static, Game: EventWindow<State> + 'static>(
         }
 
         Game::update(game, &canvas, Duration::ZERO, parent, state)?;
+        overlay::update(&canvas, Duration::ZERO, state)?;
         Game::draw(game.into(), &mut canvas, parent.into(), state.into())?;
+        overlay::draw(&mut canvas, state.into())?;
         canvas.present();
     }
 
@@ -155,6 +162,7 @@ pub fn run_game<State: 'static, Game: GameWindow<State> + 'static>(
     state_func: impl FnOnce(&mut Canvas<Window>) -> Result<State>,
     func: impl FnOnce(&mut Canvas<Window>, MutRef<State>) -> Result<Game>,
 ) -> Result<()> {
+    let _overlay = overlay::RunGuard::new();
     let (sdl_context, mut canvas) = init(title, width, height, window)?;
 
     let (last_x, last_y) = canvas.window().position();
@@ -198,7 +206,10 @@ pub fn run_game<State: 'static, Game: GameWindow<State> + 'static>(
         last_time = current_time;
 
         for event in event_pump.poll_iter() {
-            Game::event(game, &canvas, event.into(), parent, state)?;
+            let event: Event = event.into();
+            if !overlay::event(&canvas, &event, state)? {
+                Game::event(game, &canvas, event, parent, state)?;
+            }
         }
 
         let (x, y) = canvas.window().position();
@@ -224,7 +235,9 @@ pub fn run_game<State: 'static, Game: GameWindow<State> + 'static>(
 
         let ts = Game::time_scale(game.into(), state.into());
         Game::update(game, &canvas, elapsed.mul_f32(ts), parent, state)?;
+        overlay::update(&canvas, elapsed.mul_f32(ts), state)?;
         Game::draw(game.into(), &mut canvas, parent.into(), state.into())?;
+        overlay::draw(&mut canvas, state.into())?;
         canvas.present();
 
         let elapsed = Instant::now() - current_time;
@@ -239,8 +252,41 @@ pub fn run_game<State: 'static, Game: GameWindow<State> + 'static>(
 #[cfg(test)]
 mod tests {
     use crate::{
+        overlay::overlay_test::test_overlay_routing,
         refs::MutRef,
-        ui_element::{grid::grid_test::test_grid_click, panel::panel_test::test_panel_click},
+        ui_element::{
+            cached::cached_test::test_cached_redraw,
+            checkbox::checkbox_test::test_checkbox_tri_state,
+            combo_box::combo_box_test::test_combo_box_search,
+            data_grid::data_grid_test::test_data_grid,
+            dialog::dialog_test::{
+                test_dialog_center, test_dialog_queued, test_message_box, test_modal_blocking,
+            },
+            dock_panel::dock_panel_test::test_dock_panel_layout,
+            flex::flex_test::test_flex_layout,
+            grid::grid_test::{test_grid_click, test_grid_draw_order},
+            list_view::list_view_test::{test_list_view_selection, test_list_view_virtualization},
+            menu::menu_test::test_menu_accelerator,
+            panel::panel_test::{
+                test_panel_anchors, test_panel_children, test_panel_click, test_panel_drag_release,
+                test_panel_z_order,
+            },
+            progress_bar::progress_bar_test::test_progress_bar,
+            radio_group::radio_group_test::test_radio_group_select,
+            scroll_view::scroll_view_test::{
+                test_scroll_view_bars, test_scroll_view_clip, test_scroll_view_kinetic,
+                test_scroll_view_nested, test_scroll_view_scroll_to, test_scroll_view_smooth,
+            },
+            slider::slider_test::{test_range_slider, test_slider_drag},
+            spinner::spinner_test::test_spinner,
+            splitter::splitter_test::test_splitter_drag,
+            stack_panel::stack_panel_test::test_stack_panel_layout,
+            tab_control::tab_control_test::{test_tab_control_close, test_tab_control_switch},
+            toggle_switch::toggle_switch_test::test_toggle_switch,
+            tooltip::tooltip_test::test_tooltip_delay,
+            tree_view::tree_view_test::test_tree_view,
+            wrap_panel::wrap_panel_test::test_wrap_panel_flow,
+        },
     };
 
     #[test]
@@ -262,5 +308,43 @@ mod tests {
 
         test_grid_click(canvas);
         test_panel_click(canvas);
+        test_list_view_virtualization(canvas);
+        test_checkbox_tri_state(canvas);
+        test_slider_drag(canvas);
+        test_overlay_routing(canvas);
+        test_modal_blocking(canvas);
+        test_menu_accelerator(canvas);
+        test_tooltip_delay(canvas);
+        test_tab_control_switch(canvas);
+        test_splitter_drag(canvas);
+        test_stack_panel_layout(canvas);
+        test_wrap_panel_flow(canvas);
+        test_flex_layout(canvas);
+        test_panel_anchors(canvas);
+        test_dock_panel_layout(canvas);
+        test_panel_z_order(canvas);
+        test_panel_drag_release(canvas);
+        test_grid_draw_order(canvas);
+        test_panel_children(canvas);
+        test_combo_box_search(canvas);
+        test_scroll_view_kinetic(canvas);
+        test_cached_redraw(canvas);
+        test_scroll_view_clip(canvas);
+        test_scroll_view_bars(canvas);
+        test_scroll_view_scroll_to(canvas);
+        test_scroll_view_smooth(canvas);
+        test_scroll_view_nested(canvas);
+        test_tree_view(canvas);
+        test_radio_group_select(canvas);
+        test_toggle_switch(canvas);
+        test_range_slider(canvas);
+        test_progress_bar(canvas);
+        test_spinner(canvas);
+        test_message_box(canvas);
+        test_dialog_queued(canvas);
+        test_dialog_center(canvas);
+        test_data_grid(canvas);
+        test_list_view_selection(canvas);
+        test_tab_control_close(canvas);
     }
 }
@@ -1,4 +1,9 @@
-use sdl2::rect::{FRect, Rect};
+use anyhow::Result;
+use sdl2::{
+    rect::{FRect, Rect},
+    render::Canvas,
+    video::Window,
+};
 
 pub fn scale(surface: FRect, scale: FRect) -> FRect {
     FRect::new(
@@ -17,3 +22,23 @@ pub fn as_rect(surface: FRect) -> Rect {
         surface.height() as u32,
     )
 }
+
+///Runs draw with everything outside of surface, or of the current clip rect, clipped.
+pub fn clipped(
+    canvas: &mut Canvas<Window>,
+    surface: FRect,
+    draw: impl FnOnce(&mut Canvas<Window>) -> Result<()>,
+) -> Result<()> {
+    let previous = canvas.clip_rect();
+    let clip = match previous {
+        Some(previous) => previous.intersection(as_rect(surface)),
+        None => Some(as_rect(surface)),
+    };
+    if let Some(clip) = clip {
+        canvas.set_clip_rect(clip);
+        let success = draw(canvas);
+        canvas.set_clip_rect(previous);
+        success?;
+    }
+    Ok(())
+}
@@ -123,6 +123,21 @@ impl UIString {
             .map_err(|e| anyhow!(e))
     }
 
+    ///Draws the text at its own size, vertically centered on the left of to and cut at its right.
+    pub fn draw_left(&self, canvas: &mut Canvas<Window>, to: FRect, color: Color) -> Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let (width, height) = self.size()?;
+        let (width, height) = (width.min(to.width()), height.min(to.height()));
+        self.draw(
+            canvas,
+            Some(FRect::new(0., 0., width, height)),
+            FRect::new(to.x(), to.y() + (to.height() - height) / 2., width, height),
+            color,
+        )
+    }
+
     pub fn as_str(&self) -> &str {
         &self.text
     }
@@ -143,3 +158,19 @@ impl AsRef<String> for UIString {
         &self.text
     }
 }
+
+#[cfg(test)]
+impl UIString {
+    ///Only for the tests that never measure nor draw the text, since there is no font.
+    pub(crate) fn unmeasured(text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            ..Self::default()
+        }
+    }
+
+    ///The font of the unmeasured strings, for the controls the tests never draw.
+    pub(crate) fn no_font() -> &'static Font<'static, 'static> {
+        Self::default().font
+    }
+}
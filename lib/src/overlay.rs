@@ -0,0 +1,322 @@
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+use sdl2::{rect::FRect, render::Canvas, video::Window};
+
+use crate::{
+    event::Event,
+    refs::{MutRef, Ref},
+};
+
+pub type FnOverlay = Box<dyn FnOnce(&mut Canvas<Window>) -> Result<()>>;
+pub type FnLayerSurface = Box<dyn Fn() -> FRect>;
+///The layers get the state of the game from the runners, see `state`.
+pub type FnLayerDraw = Box<dyn Fn(&mut Canvas<Window>, Ref<dyn Any>) -> Result<()>>;
+///Returns if the event was used, so it doesn't reach the layers under it and the tree.
+pub type FnLayerEvent = Box<dyn FnMut(&Canvas<Window>, &Event, MutRef<dyn Any>) -> Result<bool>>;
+pub type FnLayerUpdate = Box<dyn FnMut(&Canvas<Window>, Duration, MutRef<dyn Any>) -> Result<()>>;
+pub type FnDismiss = Box<dyn FnMut() -> Result<()>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayerId(u64);
+
+///Content drawn above the whole tree that gets the events before it.
+pub struct Layer {
+    z: i32,
+    ///The part of the window the layer takes, the pointer events in it never reach under it.
+    surface: FnLayerSurface,
+    draw: FnLayerDraw,
+    event: Option<FnLayerEvent>,
+    update: Option<FnLayerUpdate>,
+    ///It gets called when the user clicks outside of the layer, which then gets removed.
+    dismiss: Option<FnDismiss>,
+    ///Uses every input event that reaches it, so nothing under it gets any input.</br>
+    ///The other events like `Quit` still reach the game.
+    modal: bool,
+}
+
+impl Layer {
+    pub fn new(surface: FnLayerSurface, draw: FnLayerDraw) -> Self {
+        Self {
+            z: 0,
+            surface,
+            draw,
+            event: None,
+            update: None,
+            dismiss: None,
+            modal: false,
+        }
+    }
+
+    ///Layers with a higher z are drawn above and get the events first.
+    pub const fn z(mut self, z: i32) -> Self {
+        self.z = z;
+        self
+    }
+
+    pub fn event(mut self, event: FnLayerEvent) -> Self {
+        self.event = Some(event);
+        self
+    }
+
+    pub fn update(mut self, update: FnLayerUpdate) -> Self {
+        self.update = Some(update);
+        self
+    }
+
+    pub fn dismiss(mut self, dismiss: FnDismiss) -> Self {
+        self.dismiss = Some(dismiss);
+        self
+    }
+
+    pub const fn modal(mut self, modal: bool) -> Self {
+        self.modal = modal;
+        self
+    }
+}
+
+type Layers = Vec<(LayerId, i32, Rc<RefCell<Layer>>)>;
+
+thread_local! {
+    static DRAWS: RefCell<Vec<FnOverlay>> = const { RefCell::new(Vec::new()) };
+    ///Sorted by z, the last one being on top.
+    static LAYERS: RefCell<Layers> = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+///Draws above the whole tree for this frame, after what was pushed before.</br>
+///Meant to be called from `UserControl::draw` for popups that must not be hidden by siblings.
+pub fn draw_above(draw: FnOverlay) {
+    DRAWS.with_borrow_mut(|draws| draws.push(draw));
+}
+
+///Adds the layer above the others with the same z.
+pub fn push(layer: Layer) -> LayerId {
+    let id = LayerId(NEXT_ID.get());
+    NEXT_ID.set(id.0 + 1);
+    LAYERS.with_borrow_mut(|layers| {
+        let at = layers.partition_point(|(_, z, _)| *z <= layer.z);
+        layers.insert(at, (id, layer.z, Rc::new(RefCell::new(layer))));
+    });
+    id
+}
+
+///Returns if the layer was still there.
+pub fn remove(id: LayerId) -> bool {
+    LAYERS.with_borrow_mut(|layers| {
+        let len = layers.len();
+        layers.retain(|(other, _, _)| *other != id);
+        len != layers.len()
+    })
+}
+
+pub fn contains(id: LayerId) -> bool {
+    LAYERS.with_borrow(|layers| layers.iter().any(|(other, _, _)| *other == id))
+}
+
+///Removes the layers and what was left to draw above.
+pub fn clear() {
+    LAYERS.take();
+    DRAWS.take();
+}
+
+///Clears the overlay when a runner starts and when it exits, even through an error.
+pub(crate) struct RunGuard;
+
+impl RunGuard {
+    pub(crate) fn new() -> Self {
+        clear();
+        Self
+    }
+}
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        clear();
+    }
+}
+
+///The state of the game given to the layers, the parent of the game being ().</br>
+///Fails when the game has another state.
+pub fn state<State: 'static>(mut state: MutRef<dyn Any>) -> Result<MutRef<State>> {
+    state
+        .as_mut()
+        .downcast_mut::<State>()
+        .map(MutRef::new)
+        .ok_or(anyhow!(
+            "The layer expects another state than the one of the game"
+        ))
+}
+
+pub fn state_ref<State: 'static>(state: Ref<dyn Any>) -> Result<Ref<State>> {
+    state
+        .as_ref()
+        .downcast_ref::<State>()
+        .map(Ref::new)
+        .ok_or(anyhow!(
+            "The layer expects another state than the one of the game"
+        ))
+}
+
+///Called by the runners before the tree, returns if the event was used by a layer.
+pub(crate) fn event<State: 'static>(
+    canvas: &Canvas<Window>,
+    event: &Event,
+    mut state: MutRef<State>,
+) -> Result<bool> {
+    let state: MutRef<dyn Any> = MutRef::new(state.as_mut());
+    if let Event::ElementMove { .. } | Event::ElementResize { .. } = event {
+        return Ok(false);
+    }
+    //Copied so the layers can push and remove layers while handling the event.
+    let layers: Vec<(LayerId, Rc<RefCell<Layer>>)> = LAYERS.with_borrow(|layers| {
+        layers
+            .iter()
+            .rev()
+            .map(|(id, _, layer)| (*id, layer.clone()))
+            .collect()
+    });
+    let point = event.position();
+    for (id, layer) in layers {
+        if !contains(id) {
+            continue;
+        }
+        let mut layer = layer.borrow_mut();
+        let inside = point.map(|point| (layer.surface)().contains_point(point));
+        if let (Some(false), Event::MouseButtonDown { .. }, Some(dismiss)) =
+            (inside, event, layer.dismiss.as_mut())
+        {
+            remove(id);
+            dismiss()?;
+            return Ok(true);
+        }
+        if let Some(handler) = layer.event.as_mut() {
+            if handler(canvas, event, state)? {
+                return Ok(true);
+            }
+        }
+        if (layer.modal && event.is_input()) || inside == Some(true) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+///Called by the runners once the tree is updated.
+pub(crate) fn update<State: 'static>(
+    canvas: &Canvas<Window>,
+    elapsed: Duration,
+    mut state: MutRef<State>,
+) -> Result<()> {
+    let state: MutRef<dyn Any> = MutRef::new(state.as_mut());
+    let layers: Vec<(LayerId, Rc<RefCell<Layer>>)> = LAYERS.with_borrow(|layers| {
+        layers
+            .iter()
+            .map(|(id, _, layer)| (*id, layer.clone()))
+            .collect()
+    });
+    for (id, layer) in layers {
+        if !contains(id) {
+            continue;
+        }
+        if let Some(update) = layer.borrow_mut().update.as_mut() {
+            update(canvas, elapsed, state)?;
+        }
+    }
+    Ok(())
+}
+
+///Called by the runners once the tree is drawn.
+pub(crate) fn draw<State: 'static>(canvas: &mut Canvas<Window>, state: Ref<State>) -> Result<()> {
+    let state: Ref<dyn Any> = Ref::new(state.as_ref());
+    for draw in DRAWS.take() {
+        draw(canvas)?;
+    }
+    let layers: Vec<Rc<RefCell<Layer>>> =
+        LAYERS.with_borrow(|layers| layers.iter().map(|(_, _, layer)| layer.clone()).collect());
+    for layer in layers {
+        (layer.borrow().draw)(canvas, state)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod overlay_test {
+    use sdl2::mouse::MouseButton;
+
+    use crate::zero;
+
+    use super::*;
+
+    fn click(canvas: &Canvas<Window>, x: f32, y: f32) -> bool {
+        let mut state = ();
+        event(
+            canvas,
+            &Event::MouseButtonDown {
+                which: 0,
+                mouse_btn: MouseButton::Left,
+                clicks: 1,
+                x,
+                y,
+            },
+            MutRef::new(&mut state),
+        )
+        .expect("")
+    }
+
+    pub(crate) fn test_overlay_routing(canvas: &mut Canvas<Window>) {
+        let clicks = Rc::new(Cell::new(0));
+        let dismissed = Rc::new(Cell::new(false));
+        let counter = clicks.clone();
+        let bottom = push(
+            Layer::new(
+                Box::new(|| FRect::new(0., 0., 10., 10.)),
+                Box::new(|_, _| Ok(())),
+            )
+            .event(Box::new(move |_, _, _| {
+                counter.set(counter.get() + 1);
+                Ok(false)
+            })),
+        );
+        let flag = dismissed.clone();
+        let top = push(
+            Layer::new(
+                Box::new(|| FRect::new(5., 5., 10., 10.)),
+                Box::new(|_, _| Ok(())),
+            )
+            .z(1)
+            .dismiss(Box::new(move || {
+                flag.set(true);
+                Ok(())
+            })),
+        );
+
+        assert!(click(canvas, 7., 7.));
+        assert_eq!(clicks.get(), 0);
+        assert!(click(canvas, 1., 1.));
+        assert!(dismissed.get());
+        assert!(!contains(top));
+        assert_eq!(clicks.get(), 0);
+        assert!(click(canvas, 1., 1.));
+        assert_eq!(clicks.get(), 1);
+        assert!(!click(canvas, 30., 30.));
+        assert_eq!(clicks.get(), 2);
+
+        assert!(remove(bottom));
+        assert!(!click(canvas, 1., 1.));
+        draw(canvas, Ref::new(&())).expect("");
+
+        //A runner leaves no layer nor draw behind.
+        let guard = RunGuard::new();
+        let layer = push(Layer::new(Box::new(zero), Box::new(|_, _| Ok(()))));
+        draw_above(Box::new(|_| Ok(())));
+        drop(guard);
+        assert!(!contains(layer));
+        assert!(DRAWS.with_borrow(Vec::is_empty));
+    }
+}
@@ -0,0 +1,330 @@
+use std::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+use sdl2::{
+    pixels::Color,
+    rect::FRect,
+    render::{BlendMode, Canvas, Texture, TextureCreator},
+    video::{Window, WindowContext},
+};
+
+use crate::{
+    event::Event,
+    functions::FnChanged,
+    refs::{MutRef, Ref},
+    user_control::UserControl,
+    zero,
+};
+
+struct Cache {
+    //Declared before the creator so it is destroyed first.
+    texture: Texture<'static>,
+    _creator: Box<TextureCreator<WindowContext>>,
+}
+
+impl Cache {
+    fn new(canvas: &Canvas<Window>, width: u32, height: u32) -> Result<Self> {
+        let creator = Box::new(canvas.texture_creator());
+        let mut texture = creator
+            .create_texture_target(None, width, height)
+            .map_err(|e| anyhow!(e))?;
+        texture.set_blend_mode(BlendMode::Blend);
+        //The creator is boxed and owned by the cache, so it outlives the texture.
+        let texture = unsafe { std::mem::transmute::<Texture<'_>, Texture<'static>>(texture) };
+        Ok(Self {
+            texture,
+            _creator: creator,
+        })
+    }
+
+    fn fits(&self, width: u32, height: u32) -> bool {
+        let query = self.texture.query();
+        query.width == width && query.height == height
+    }
+}
+
+///Renders its child once into a texture and reuses it until the child changes or gets resized.
+pub struct Cached<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static> {
+    parent: PhantomData<Parent>,
+    state: PhantomData<State>,
+    surface: FRect,
+    child: Child,
+    ///It gets called every update, returning true re-renders the child on the next draw.
+    changed: Option<FnChanged<Self, Parent, State>>,
+    cache: RefCell<Option<Cache>>,
+    dirty: Cell<bool>,
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    Cached<Parent, State, Child>
+{
+    pub fn new(child: Child) -> Self {
+        Self {
+            parent: PhantomData,
+            state: PhantomData,
+            surface: zero(),
+            child,
+            changed: None,
+            cache: RefCell::new(None),
+            dirty: Cell::new(true),
+        }
+    }
+
+    pub fn changed(mut self, changed: FnChanged<Self, Parent, State>) -> Self {
+        self.changed = Some(changed);
+        self
+    }
+
+    pub fn changed_mut(&mut self) -> &mut Option<FnChanged<Self, Parent, State>> {
+        &mut self.changed
+    }
+
+    pub const fn child(&self) -> &Child {
+        &self.child
+    }
+
+    ///Gives access to the child and re-renders it on the next draw.
+    pub fn child_mut(&mut self) -> &mut Child {
+        self.dirty.set(true);
+        &mut self.child
+    }
+
+    ///Re-renders the child on the next draw.
+    pub fn invalidate(&self) {
+        self.dirty.set(true);
+    }
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    UserControl<Parent, State> for Cached<Parent, State, Child>
+{
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match event {
+            Event::ElementMove { x, y } => {
+                this.surface.set_x(x);
+                this.surface.set_y(y);
+                Ok(())
+            }
+            Event::ElementResize { width, height } => {
+                this.surface.set_width(width);
+                this.surface.set_height(height);
+                this.dirty.set(true);
+                let surface = Child::surface(Ref::new(&this.child), parent.into(), state.into());
+                if 0. != surface.x() || 0. != surface.y() {
+                    Child::event(
+                        MutRef::new(&mut this.child),
+                        canvas,
+                        Event::ElementMove { x: 0., y: 0. },
+                        parent,
+                        state,
+                    )?;
+                }
+                Child::event(
+                    MutRef::new(&mut this.child),
+                    canvas,
+                    Event::ElementResize { width, height },
+                    parent,
+                    state,
+                )
+            }
+            event => {
+                let (x, y) = (this.surface.x(), this.surface.y());
+                Child::event(
+                    MutRef::new(&mut this.child),
+                    canvas,
+                    event.translate(-x, -y),
+                    parent,
+                    state,
+                )
+            }
+        }
+    }
+
+    fn update(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        elapsed: Duration,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        Child::update(MutRef::new(&mut this.child), canvas, elapsed, parent, state)?;
+        if let Some(changed) = this.changed.as_ref() {
+            if changed(this.into(), parent.into(), state.into()) {
+                this.dirty.set(true);
+            }
+        }
+        Ok(())
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        let (width, height) = (this.surface.width() as u32, this.surface.height() as u32);
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        let mut cache = this.cache.borrow_mut();
+        if !cache
+            .as_ref()
+            .is_some_and(|cache| cache.fits(width, height))
+        {
+            *cache = Some(Cache::new(canvas, width, height)?);
+            this.dirty.set(true);
+        }
+        let cache = cache.as_mut().ok_or(anyhow!("Checked"))?;
+        if this.dirty.get() {
+            let mut success = Ok(());
+            canvas
+                .with_texture_canvas(&mut cache.texture, |sub| {
+                    sub.set_draw_color(Color::RGBA(0, 0, 0, 0));
+                    sub.clear();
+                    success = Child::draw(Ref::new(&this.child), sub, parent, state)
+                })
+                .map_err(|e| anyhow!(e))?;
+            success?;
+            this.dirty.set(false);
+        }
+        canvas
+            .copy_f(&cache.texture, None, this.surface)
+            .map_err(|e| anyhow!(e))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod cached_test {
+    use super::*;
+
+    struct Counter {
+        surface: FRect,
+        draws: Cell<usize>,
+        changed: Cell<bool>,
+    }
+
+    impl UserControl<(), ()> for Counter {
+        fn surface(this: Ref<Self>, _: Ref<()>, _: Ref<()>) -> FRect {
+            this.surface
+        }
+
+        fn event(
+            mut this: MutRef<Self>,
+            _: &Canvas<Window>,
+            event: Event,
+            _: MutRef<()>,
+            _: MutRef<()>,
+        ) -> Result<()> {
+            match event {
+                Event::ElementMove { x, y } => {
+                    this.surface.set_x(x);
+                    this.surface.set_y(y);
+                }
+                Event::ElementResize { width, height } => {
+                    this.surface.set_width(width);
+                    this.surface.set_height(height);
+                }
+                _ => {}
+            }
+            Ok(())
+        }
+
+        fn update(
+            _: MutRef<Self>,
+            _: &Canvas<Window>,
+            _: Duration,
+            _: MutRef<()>,
+            _: MutRef<()>,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        fn draw(this: Ref<Self>, _: &mut Canvas<Window>, _: Ref<()>, _: Ref<()>) -> Result<()> {
+            this.draws.set(this.draws.get() + 1);
+            Ok(())
+        }
+    }
+
+    pub(crate) fn test_cached_redraw(canvas: &mut Canvas<Window>) {
+        let mut cached = Cached::new(Counter {
+            surface: zero(),
+            draws: Cell::new(0),
+            changed: Cell::new(false),
+        })
+        .changed(Box::new(|this, _, _| this.child().changed.get()));
+        let mut cached = MutRef::new(&mut cached);
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        let send = move |canvas: &Canvas<Window>, event| {
+            UserControl::event(cached, canvas, event, unit, unit).expect("");
+        };
+        let update = move |canvas: &Canvas<Window>| {
+            UserControl::update(cached, canvas, Duration::ZERO, unit, unit).expect("");
+        };
+        let draw = move |canvas: &mut Canvas<Window>| {
+            UserControl::draw(cached.into(), canvas, unit.into(), unit.into()).expect("");
+            cached.child().draws.get()
+        };
+
+        //Nothing to render into before the first resize.
+        assert_eq!(draw(canvas), 0);
+        send(canvas, Event::ElementMove { x: 5., y: 5. });
+        send(
+            canvas,
+            Event::ElementResize {
+                width: 20.,
+                height: 10.,
+            },
+        );
+        assert_eq!(cached.child().surface, FRect::new(0., 0., 20., 10.));
+        assert_eq!(draw(canvas), 1);
+        assert_eq!(draw(canvas), 1);
+        update(canvas);
+        assert_eq!(draw(canvas), 1);
+
+        cached.child().changed.set(true);
+        update(canvas);
+        cached.child().changed.set(false);
+        assert_eq!(draw(canvas), 2);
+        update(canvas);
+        assert_eq!(draw(canvas), 2);
+
+        cached.invalidate();
+        assert_eq!(draw(canvas), 3);
+        cached.child_mut();
+        assert_eq!(draw(canvas), 4);
+
+        //Moving reuses the texture, resizing makes a new one.
+        send(canvas, Event::ElementMove { x: 10., y: 10. });
+        assert_eq!(draw(canvas), 4);
+        send(
+            canvas,
+            Event::ElementResize {
+                width: 30.,
+                height: 10.,
+            },
+        );
+        assert_eq!(cached.child().surface, FRect::new(0., 0., 30., 10.));
+        assert_eq!(draw(canvas), 5);
+        assert!(cached
+            .cache
+            .borrow()
+            .as_ref()
+            .is_some_and(|cache| cache.fits(30, 10)));
+        assert_eq!(draw(canvas), 5);
+    }
+}
@@ -0,0 +1,340 @@
+use std::{marker::PhantomData, time::Duration};
+
+use anyhow::{anyhow, Result};
+use sdl2::{
+    gfx::primitives::DrawRenderer, mouse::MouseButton, rect::FRect, render::Canvas, video::Window,
+};
+
+use crate::{
+    event::Event,
+    functions::{FnAction, FnColor, FnState, FnText, StateEnum},
+    refs::{MutRef, Ref},
+    user_control::UserControl,
+    zero,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckState {
+    Unchecked,
+    Checked,
+    ///Neither checked nor unchecked, like a "select all" when only some items are.
+    Indeterminate,
+}
+
+///A box the user checks by clicking on it, with its label on the right.
+pub struct Checkbox<Parent: 'static, State: 'static> {
+    parent: PhantomData<Parent>,
+    statel: PhantomData<State>,
+    surface: FRect,
+    check: CheckState,
+    ///If clicking goes through the indeterminate state.
+    tri_state: bool,
+    ///It gets called when the user changes the check state.
+    changed: Option<FnAction<Self, Parent, State>>,
+    text: Option<FnText<Self, Parent, State>>,
+    state: FnState<Self, Parent, State>,
+    back_color: FnColor<Self, Parent, State>,
+    check_color: FnColor<Self, Parent, State>,
+    hover: bool,
+}
+
+impl<Parent: 'static, State: 'static> Checkbox<Parent, State> {
+    pub fn new(
+        state: FnState<Self, Parent, State>,
+        back_color: FnColor<Self, Parent, State>,
+        check_color: FnColor<Self, Parent, State>,
+    ) -> Self {
+        Self {
+            parent: PhantomData,
+            statel: PhantomData,
+            surface: zero(),
+            check: CheckState::Unchecked,
+            tri_state: false,
+            changed: None,
+            text: None,
+            state,
+            back_color,
+            check_color,
+            hover: false,
+        }
+    }
+
+    pub const fn surface(&self) -> FRect {
+        self.surface
+    }
+
+    pub const fn checked(mut self, check: CheckState) -> Self {
+        self.check = check;
+        self
+    }
+
+    pub const fn tri_state(mut self, tri_state: bool) -> Self {
+        self.tri_state = tri_state;
+        self
+    }
+
+    pub fn changed(mut self, changed: FnAction<Self, Parent, State>) -> Self {
+        self.changed = Some(changed);
+        self
+    }
+
+    pub fn changed_mut(&mut self) -> &mut Option<FnAction<Self, Parent, State>> {
+        &mut self.changed
+    }
+
+    pub fn text(mut self, text: FnText<Self, Parent, State>) -> Self {
+        self.text = Some(text);
+        self
+    }
+
+    pub fn text_mut(&mut self) -> &mut Option<FnText<Self, Parent, State>> {
+        &mut self.text
+    }
+
+    pub fn state_mut(&mut self) -> &mut FnState<Self, Parent, State> {
+        &mut self.state
+    }
+
+    pub const fn check_state(&self) -> CheckState {
+        self.check
+    }
+
+    pub fn set_check_state(&mut self, check: CheckState) {
+        self.check = check;
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.check == CheckState::Checked
+    }
+
+    pub const fn hover(&self) -> bool {
+        self.hover
+    }
+
+    ///The state a click goes to.
+    const fn next(&self) -> CheckState {
+        match (self.check, self.tri_state) {
+            (CheckState::Unchecked, _) | (CheckState::Indeterminate, false) => CheckState::Checked,
+            (CheckState::Checked, true) => CheckState::Indeterminate,
+            (CheckState::Checked, false) | (CheckState::Indeterminate, true) => {
+                CheckState::Unchecked
+            }
+        }
+    }
+
+    ///The square on the left of the surface.
+    fn check_box(&self) -> FRect {
+        let size = self.surface.height().min(self.surface.width()) * 0.6;
+        FRect::new(
+            self.surface.x() + (self.surface.height() - size) / 2.,
+            self.surface.y() + (self.surface.height() - size) / 2.,
+            size,
+            size,
+        )
+    }
+}
+
+impl<Parent: 'static, State: 'static> UserControl<Parent, State> for Checkbox<Parent, State> {
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        if let Event::ElementMove { x, y } = event {
+            this.surface.set_x(x);
+            this.surface.set_y(y);
+            return Ok(());
+        }
+        if let Event::ElementResize { width, height } = event {
+            this.surface.set_width(width);
+            this.surface.set_height(height);
+            return Ok(());
+        }
+        if (this.state)(this.into(), parent.into(), state.into()) != StateEnum::Enable {
+            return Ok(());
+        }
+        match (event.hover(this.surface), event) {
+            (
+                true,
+                Event::MouseButtonDown {
+                    mouse_btn: MouseButton::Left,
+                    ..
+                },
+            ) => {
+                this.check = this.next();
+                let t = this;
+                if let Some(changed) = this.changed.as_mut() {
+                    changed(t, parent, state, canvas)?;
+                }
+            }
+            (true, Event::MouseMotion { .. }) => {
+                this.hover = true;
+            }
+            (false, _) => this.hover = false,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn update(
+        _: MutRef<Self>,
+        _: &Canvas<Window>,
+        _: Duration,
+        _: MutRef<Parent>,
+        _: MutRef<State>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        if (this.state)(this, parent, state) == StateEnum::Hidden {
+            return Ok(());
+        }
+        canvas.set_draw_color((this.back_color)(this, parent, state));
+        canvas.fill_frect(this.surface).map_err(|e| anyhow!(e))?;
+        let check_box = this.check_box();
+        let check_color = (this.check_color)(this, parent, state);
+        canvas.set_draw_color(check_color);
+        canvas.draw_frect(check_box).map_err(|e| anyhow!(e))?;
+        let (x, y, size) = (check_box.x(), check_box.y(), check_box.width());
+        match this.check {
+            CheckState::Unchecked => {}
+            CheckState::Checked => {
+                let width = (size / 8.).max(1.) as u8;
+                canvas
+                    .thick_line(
+                        (x + size * 0.2) as i16,
+                        (y + size * 0.5) as i16,
+                        (x + size * 0.4) as i16,
+                        (y + size * 0.75) as i16,
+                        width,
+                        check_color,
+                    )
+                    .map_err(|e| anyhow!(e))?;
+                canvas
+                    .thick_line(
+                        (x + size * 0.4) as i16,
+                        (y + size * 0.75) as i16,
+                        (x + size * 0.8) as i16,
+                        (y + size * 0.25) as i16,
+                        width,
+                        check_color,
+                    )
+                    .map_err(|e| anyhow!(e))?;
+            }
+            CheckState::Indeterminate => {
+                canvas
+                    .fill_frect(FRect::new(
+                        x + size * 0.2,
+                        y + size * 0.4,
+                        size * 0.6,
+                        size * 0.2,
+                    ))
+                    .map_err(|e| anyhow!(e))?;
+            }
+        }
+        if let Some(text) = this.text.as_ref() {
+            if let (Some(text), color) = text(this, parent, state)? {
+                let left = this.surface.height();
+                text.draw_left(
+                    canvas,
+                    FRect::new(
+                        this.surface.x() + left,
+                        this.surface.y(),
+                        (this.surface.width() - left).max(0.),
+                        this.surface.height(),
+                    ),
+                    color,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod checkbox_test {
+    use sdl2::pixels::Color;
+
+    use super::*;
+
+    pub(crate) fn test_checkbox_tri_state(canvas: &mut Canvas<Window>) {
+        let mut changes = 0_usize;
+        let changes = MutRef::new(&mut changes);
+        let mut checkbox = Checkbox::new(
+            Box::new(|_, _, _| StateEnum::Enable),
+            Box::new(|_, _, _| Color::BLACK),
+            Box::new(|_, _, _| Color::WHITE),
+        )
+        .tri_state(true)
+        .changed(Box::new(|_, _, mut changes: MutRef<usize>, _| {
+            *changes += 1;
+            Ok(())
+        }));
+        let mut checkbox = MutRef::new(&mut checkbox);
+        let mut parent = ();
+        let parent = MutRef::new(&mut parent);
+        UserControl::event(
+            checkbox,
+            canvas,
+            Event::ElementResize {
+                width: 40.,
+                height: 20.,
+            },
+            parent,
+            changes,
+        )
+        .expect("");
+
+        click(checkbox, parent, changes, canvas, 50., 5.);
+        assert_eq!(checkbox.check_state(), CheckState::Unchecked);
+        assert_eq!(*changes, 0);
+        click(checkbox, parent, changes, canvas, 5., 5.);
+        assert_eq!(checkbox.check_state(), CheckState::Checked);
+        click(checkbox, parent, changes, canvas, 5., 5.);
+        assert_eq!(checkbox.check_state(), CheckState::Indeterminate);
+        click(checkbox, parent, changes, canvas, 5., 5.);
+        assert_eq!(checkbox.check_state(), CheckState::Unchecked);
+        assert_eq!(*changes, 3);
+
+        checkbox.tri_state = false;
+        checkbox.set_check_state(CheckState::Indeterminate);
+        click(checkbox, parent, changes, canvas, 5., 5.);
+        assert!(checkbox.is_checked());
+    }
+
+    fn click(
+        checkbox: MutRef<Checkbox<(), usize>>,
+        parent: MutRef<()>,
+        state: MutRef<usize>,
+        canvas: &Canvas<Window>,
+        x: f32,
+        y: f32,
+    ) {
+        UserControl::event(
+            checkbox,
+            canvas,
+            Event::MouseButtonDown {
+                which: 0,
+                mouse_btn: MouseButton::Left,
+                clicks: 1,
+                x,
+                y,
+            },
+            parent,
+            state,
+        )
+        .expect("");
+    }
+}
@@ -0,0 +1,702 @@
+use std::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use sdl2::{
+    gfx::primitives::DrawRenderer,
+    keyboard::{Keycode, Mod},
+    mouse::MouseButton,
+    pixels::Color,
+    rect::{FPoint, FRect},
+    render::Canvas,
+    video::Window,
+};
+
+use crate::{
+    event::{mark_handled, Event},
+    functions::{FnAction, FnColor, FnState, StateEnum},
+    missing::{rect::clipped, ui_string::UIString},
+    overlay::{self, Layer, LayerId},
+    refs::{MutRef, Ref},
+    user_control::UserControl,
+    zero,
+};
+
+///How long the typed letters are kept for the search.
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(1);
+
+///What the overlay layer of the popup shares with the combo box.</br>
+///The layer only queues the events, the combo box handles them once it gets an event or an update itself
+///since it can move in memory while the popup is open.
+struct Popup {
+    surface: Cell<FRect>,
+    events: RefCell<Vec<Event>>,
+    dismissed: Cell<bool>,
+}
+
+///Shows the selected option and lets the user pick another one in a popup list.
+pub struct ComboBox<Parent: 'static, State: 'static> {
+    parent: PhantomData<Parent>,
+    statel: PhantomData<State>,
+    surface: FRect,
+    options: Vec<UIString>,
+    selected: Option<usize>,
+    ///It gets called when the user selects another option.
+    changed: Option<FnAction<Self, Parent, State>>,
+    state: FnState<Self, Parent, State>,
+    back_color: FnColor<Self, Parent, State>,
+    front_color: FnColor<Self, Parent, State>,
+    select_color: FnColor<Self, Parent, State>,
+    ///How many options the popup shows before scrolling.
+    max_rows: usize,
+    open: bool,
+    ///The option under the mouse or the keyboard in the popup.
+    highlighted: usize,
+    ///The first option shown in the popup.
+    first: usize,
+    search: String,
+    ///When the last letter was typed, the search starts over after SEARCH_TIMEOUT.
+    last_typed: Option<Instant>,
+    window_height: f32,
+    ///The overlay layer of the popup while it is open.
+    layer: Option<LayerId>,
+    popup: Rc<Popup>,
+    focused: bool,
+    hover: bool,
+}
+
+impl<Parent: 'static, State: 'static> ComboBox<Parent, State> {
+    pub fn new(
+        options: Vec<UIString>,
+        state: FnState<Self, Parent, State>,
+        back_color: FnColor<Self, Parent, State>,
+        front_color: FnColor<Self, Parent, State>,
+    ) -> Self {
+        Self {
+            parent: PhantomData,
+            statel: PhantomData,
+            surface: zero(),
+            options,
+            selected: None,
+            changed: None,
+            state,
+            back_color,
+            front_color,
+            select_color: Box::new(|_, _, _| Color::RGBA(70, 120, 200, 160)),
+            max_rows: 8,
+            open: false,
+            highlighted: 0,
+            first: 0,
+            search: String::new(),
+            last_typed: None,
+            window_height: f32::MAX,
+            layer: None,
+            popup: Rc::new(Popup {
+                surface: Cell::new(zero()),
+                events: RefCell::default(),
+                dismissed: Cell::new(false),
+            }),
+            focused: false,
+            hover: false,
+        }
+    }
+
+    pub const fn surface(&self) -> FRect {
+        self.surface
+    }
+
+    pub const fn selected(mut self, selected: usize) -> Self {
+        self.selected = Some(selected);
+        self
+    }
+
+    pub fn changed(mut self, changed: FnAction<Self, Parent, State>) -> Self {
+        self.changed = Some(changed);
+        self
+    }
+
+    pub fn changed_mut(&mut self) -> &mut Option<FnAction<Self, Parent, State>> {
+        &mut self.changed
+    }
+
+    pub fn select_color(mut self, select_color: FnColor<Self, Parent, State>) -> Self {
+        self.select_color = select_color;
+        self
+    }
+
+    pub fn max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = max_rows.max(1);
+        self
+    }
+
+    pub fn state_mut(&mut self) -> &mut FnState<Self, Parent, State> {
+        &mut self.state
+    }
+
+    pub fn options(&self) -> &[UIString] {
+        &self.options
+    }
+
+    pub fn options_mut(&mut self) -> &mut Vec<UIString> {
+        self.close();
+        &mut self.options
+    }
+
+    pub const fn get_selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn set_selected(&mut self, selected: Option<usize>) {
+        self.selected = selected.filter(|index| *index < self.options.len());
+    }
+
+    pub const fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub const fn hover(&self) -> bool {
+        self.hover
+    }
+
+    pub fn open(&mut self) {
+        if self.options.is_empty() {
+            return;
+        }
+        self.open = true;
+        self.highlighted = self.selected.unwrap_or(0);
+        self.show(self.highlighted);
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+        self.search.clear();
+    }
+
+    fn rows(&self) -> usize {
+        self.options.len().min(self.max_rows)
+    }
+
+    ///Under the surface, or over it when there is no room left in the window.
+    fn popup(&self) -> FRect {
+        let height = self.rows() as f32 * self.surface.height();
+        let bottom = self.surface.y() + self.surface.height();
+        let y = if bottom + height > self.window_height && self.surface.y() >= height {
+            self.surface.y() - height
+        } else {
+            bottom
+        };
+        FRect::new(self.surface.x(), y, self.surface.width(), height)
+    }
+
+    fn option_at(&self, x: f32, y: f32) -> Option<usize> {
+        let popup = self.popup();
+        if !popup.contains_point(FPoint::new(x, y)) {
+            return None;
+        }
+        Some(self.first + ((y - popup.y()) / self.surface.height()) as usize)
+            .filter(|index| *index < self.options.len())
+    }
+
+    ///Scrolls the popup as little as possible to show the option.
+    fn show(&mut self, index: usize) {
+        let rows = self.rows();
+        if index < self.first {
+            self.first = index;
+        } else if index >= self.first + rows {
+            self.first = index + 1 - rows;
+        }
+        self.first = self.first.min(self.options.len() - rows);
+    }
+
+    ///Finds the next option starting with the typed letters.
+    fn search(&mut self, letter: &str) -> Option<usize> {
+        if self
+            .last_typed
+            .is_none_or(|last_typed| last_typed.elapsed() > SEARCH_TIMEOUT)
+        {
+            self.search.clear();
+        }
+        self.last_typed = Some(Instant::now());
+        self.search.push_str(&letter.to_lowercase());
+        let len = self.options.len();
+        let start = if self.open {
+            self.highlighted
+        } else {
+            self.selected.unwrap_or(0)
+        };
+        //A single letter goes to the next match so pressing it again cycles.
+        let start = if self.search.chars().count() == 1 {
+            start + 1
+        } else {
+            start
+        };
+        (0..len).map(|i| (start + i) % len).find(|index| {
+            self.options[*index]
+                .as_str()
+                .to_lowercase()
+                .starts_with(&self.search)
+        })
+    }
+
+    fn select(
+        mut this: MutRef<Self>,
+        index: usize,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        if this.selected != Some(index) {
+            this.selected = Some(index);
+            let t = this;
+            if let Some(changed) = this.changed.as_mut() {
+                changed(t, parent, state, canvas)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///Moves the highlight when open and the selection when closed.
+    fn key_move(
+        mut this: MutRef<Self>,
+        index: usize,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        if this.open {
+            this.highlighted = index;
+            this.show(index);
+            Ok(())
+        } else {
+            Self::select(this, index, canvas, parent, state)
+        }
+    }
+
+    fn key_event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: &Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<bool> {
+        let Event::KeyDown {
+            keycode: Some(keycode),
+            keymod,
+            ..
+        } = event
+        else {
+            return Ok(false);
+        };
+        let len = this.options.len();
+        if len == 0 {
+            return Ok(false);
+        }
+        let current = if this.open {
+            this.highlighted
+        } else {
+            this.selected.unwrap_or(0)
+        };
+        match *keycode {
+            Keycode::Escape if this.open => this.close(),
+            Keycode::Return | Keycode::KpEnter if this.open => {
+                let highlighted = this.highlighted;
+                this.close();
+                Self::select(this, highlighted, canvas, parent, state)?;
+            }
+            Keycode::Down if keymod.intersects(Mod::LALTMOD | Mod::RALTMOD) => this.open(),
+            Keycode::Return | Keycode::Space | Keycode::F4 => this.open(),
+            Keycode::Up => Self::key_move(this, current.saturating_sub(1), canvas, parent, state)?,
+            Keycode::Down => {
+                Self::key_move(this, (current + 1).min(len - 1), canvas, parent, state)?
+            }
+            Keycode::Home => Self::key_move(this, 0, canvas, parent, state)?,
+            Keycode::End => Self::key_move(this, len - 1, canvas, parent, state)?,
+            _ => return Ok(false),
+        }
+        mark_handled();
+        Ok(true)
+    }
+
+    ///Goes to the next option starting with the typed text, which follows the layout of the keyboard.
+    fn text_event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        text: &str,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        if let Some(index) = this.search(text) {
+            Self::key_move(this, index, canvas, parent, state)?;
+        }
+        mark_handled();
+        Ok(())
+    }
+
+    ///Handles the events the popup got from its overlay layer since the last call.
+    fn popup_events(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let events = this.popup.events.take();
+        for event in events {
+            Self::popup_event(this, canvas, &event, parent, state)?;
+        }
+        if this.popup.dismissed.take() {
+            this.layer = None;
+            this.close();
+        }
+        Ok(())
+    }
+
+    fn popup_event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: &Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match *event {
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => {
+                if let Some(index) = this.option_at(x, y) {
+                    this.close();
+                    Self::select(this, index, canvas, parent, state)?;
+                }
+            }
+            Event::MouseMotion { x, y, .. } => {
+                if let Some(index) = this.option_at(x, y) {
+                    this.highlighted = index;
+                }
+            }
+            Event::MouseWheel {
+                scroll_y,
+                mouse_x,
+                mouse_y,
+                ..
+            } if this.popup().contains_point(FPoint::new(mouse_x, mouse_y)) => {
+                let max = this.options.len() - this.rows();
+                this.first = if scroll_y > 0. {
+                    this.first.saturating_sub(1)
+                } else {
+                    (this.first + 1).min(max)
+                };
+            }
+            Event::KeyDown { .. } => {
+                Self::key_event(this, canvas, event, parent, state)?;
+            }
+            Event::TextInput { ref text } => Self::text_event(this, canvas, text, parent, state)?,
+            _ => {}
+        }
+        this.sync_layer();
+        Ok(())
+    }
+
+    ///Opens or closes the overlay layer of the popup to match if it is open.
+    fn sync_layer(&mut self) {
+        self.popup.surface.set(self.popup());
+        match (self.open, self.layer) {
+            (true, None) => {
+                let (surface, events, dismissed) =
+                    (self.popup.clone(), self.popup.clone(), self.popup.clone());
+                //The popup is drawn by the combo box, see draw.
+                let layer = Layer::new(
+                    Box::new(move || surface.surface.get()),
+                    Box::new(|_, _| Ok(())),
+                )
+                .event(Box::new(move |_, event, _| {
+                    let used = match *event {
+                        Event::MouseButtonDown {
+                            mouse_btn: MouseButton::Left,
+                            ..
+                        }
+                        | Event::KeyDown { .. }
+                        | Event::TextInput { .. } => true,
+                        Event::MouseWheel {
+                            mouse_x, mouse_y, ..
+                        } => events
+                            .surface
+                            .get()
+                            .contains_point(FPoint::new(mouse_x, mouse_y)),
+                        Event::MouseMotion { .. } => false,
+                        _ => return Ok(false),
+                    };
+                    events.events.borrow_mut().push(event.clone());
+                    Ok(used)
+                }))
+                .dismiss(Box::new(move || {
+                    dismissed.dismissed.set(true);
+                    Ok(())
+                }));
+                self.layer = Some(overlay::push(layer));
+            }
+            (false, Some(layer)) => {
+                overlay::remove(layer);
+                self.layer = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn draw_popup(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        let popup = this.popup();
+        let front_color = (this.front_color)(this, parent, state);
+        canvas.set_draw_color((this.back_color)(this, parent, state));
+        canvas.fill_frect(popup).map_err(|e| anyhow!(e))?;
+        clipped(canvas, popup, |canvas| {
+            let select_color = (this.select_color)(this, parent, state);
+            let height = this.surface.height();
+            for (row, index) in (this.first..this.first + this.rows()).enumerate() {
+                let rect = FRect::new(
+                    popup.x(),
+                    popup.y() + row as f32 * height,
+                    popup.width(),
+                    height,
+                );
+                if index == this.highlighted {
+                    canvas.set_draw_color(select_color);
+                    canvas.fill_frect(rect).map_err(|e| anyhow!(e))?;
+                }
+                this.options[index].draw_left(
+                    canvas,
+                    FRect::new(rect.x() + 4., rect.y(), rect.width() - 8., height),
+                    front_color,
+                )?;
+            }
+            Ok(())
+        })?;
+        canvas.set_draw_color(front_color);
+        canvas.draw_frect(popup).map_err(|e| anyhow!(e))
+    }
+}
+
+impl<Parent: 'static, State: 'static> UserControl<Parent, State> for ComboBox<Parent, State> {
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match event {
+            Event::ElementMove { x, y } => {
+                this.surface.set_x(x);
+                this.surface.set_y(y);
+                return Ok(());
+            }
+            Event::ElementResize { width, height } => {
+                this.surface.set_width(width);
+                this.surface.set_height(height);
+                return Ok(());
+            }
+            _ => {}
+        }
+        Self::popup_events(this, canvas, parent, state)?;
+        if (this.state)(this.into(), parent.into(), state.into()) != StateEnum::Enable {
+            this.close();
+            this.sync_layer();
+            return Ok(());
+        }
+        match event {
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => {
+                this.focused = this.surface.contains_point(FPoint::new(x, y));
+                if this.focused && !this.open {
+                    this.open();
+                } else {
+                    this.close();
+                }
+            }
+            Event::MouseButtonDown { x, y, .. } => {
+                this.focused = this.surface.contains_point(FPoint::new(x, y));
+                this.close();
+            }
+            Event::MouseMotion { x, y, .. } => {
+                this.hover = this.surface.contains_point(FPoint::new(x, y));
+            }
+            Event::KeyDown { .. } if this.focused => {
+                Self::key_event(this, canvas, &event, parent, state)?;
+            }
+            Event::TextInput { ref text } if this.focused => {
+                Self::text_event(this, canvas, text, parent, state)?;
+            }
+            _ => {}
+        }
+        this.sync_layer();
+        Ok(())
+    }
+
+    fn update(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        _: Duration,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        if let Ok((_, height)) = canvas.output_size() {
+            this.window_height = height as f32;
+        }
+        Self::popup_events(this, canvas, parent, state)?;
+        this.sync_layer();
+        Ok(())
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        if (this.state)(this, parent, state) == StateEnum::Hidden {
+            return Ok(());
+        }
+        let front_color = (this.front_color)(this, parent, state);
+        canvas.set_draw_color((this.back_color)(this, parent, state));
+        canvas.fill_frect(this.surface).map_err(|e| anyhow!(e))?;
+        canvas.set_draw_color(front_color);
+        canvas.draw_frect(this.surface).map_err(|e| anyhow!(e))?;
+        let arrow = this.surface.height();
+        if let Some(selected) = this.selected.and_then(|index| this.options.get(index)) {
+            selected.draw_left(
+                canvas,
+                FRect::new(
+                    this.surface.x() + 4.,
+                    this.surface.y(),
+                    (this.surface.width() - arrow - 4.).max(0.),
+                    this.surface.height(),
+                ),
+                front_color,
+            )?;
+        }
+        let (cx, cy) = (
+            this.surface.x() + this.surface.width() - arrow / 2.,
+            this.surface.y() + this.surface.height() / 2.,
+        );
+        let size = arrow / 6.;
+        if this.open {
+            //Only kept until the overlay is drawn at the end of this frame.
+            overlay::draw_above(Box::new(move |canvas| {
+                Self::draw_popup(this, canvas, parent, state)
+            }));
+        }
+        canvas
+            .filled_trigon(
+                (cx - size) as i16,
+                (cy - size / 2.) as i16,
+                (cx + size) as i16,
+                (cy - size / 2.) as i16,
+                cx as i16,
+                (cy + size) as i16,
+                front_color,
+            )
+            .map_err(|e| anyhow!(e))
+    }
+}
+
+impl<Parent: 'static, State: 'static> Drop for ComboBox<Parent, State> {
+    fn drop(&mut self) {
+        if let Some(layer) = self.layer {
+            overlay::remove(layer);
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod combo_box_test {
+    use sdl2::{keyboard::Scancode, mouse::MouseButton};
+
+    use super::*;
+
+    pub(crate) fn test_combo_box_search(canvas: &mut Canvas<Window>) {
+        let mut combo = ComboBox::new(
+            ["Apple", "Banana", "Blueberry", "Cherry"]
+                .into_iter()
+                .map(UIString::unmeasured)
+                .collect(),
+            Box::new(|_, _, _| StateEnum::Enable),
+            Box::new(|_, _, _| Color::WHITE),
+            Box::new(|_, _, _| Color::BLACK),
+        );
+        let combo = MutRef::new(&mut combo);
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        let send = |event| UserControl::event(combo, canvas, event, unit, unit).expect("");
+        let wait = |duration| {
+            UserControl::update(combo, canvas, duration, unit, unit).expect("");
+        };
+        let time_out = move || {
+            let mut combo = combo;
+            combo.last_typed = Instant::now().checked_sub(2 * SEARCH_TIMEOUT);
+        };
+        let text = |text: &str| Event::TextInput {
+            text: text.to_owned(),
+        };
+        let key = |keycode, scancode| Event::KeyDown {
+            keycode: Some(keycode),
+            scancode: Some(scancode),
+            keymod: Mod::NOMOD,
+            repeat: false,
+        };
+        send(Event::ElementResize {
+            width: 100.,
+            height: 20.,
+        });
+        //Focused without opening.
+        send(Event::MouseButtonDown {
+            which: 0,
+            mouse_btn: MouseButton::Right,
+            clicks: 1,
+            x: 5.,
+            y: 5.,
+        });
+
+        send(text("b"));
+        assert_eq!(combo.get_selected(), Some(1));
+        //The same letter again cycles once the search timed out.
+        time_out();
+        send(text("b"));
+        assert_eq!(combo.get_selected(), Some(2));
+        send(text("l"));
+        assert_eq!(combo.get_selected(), Some(2));
+        time_out();
+        send(text("C"));
+        assert_eq!(combo.get_selected(), Some(3));
+        //The key of an AZERTY A is the Q of a QWERTY, only the text searches.
+        send(key(Keycode::A, Scancode::Q));
+        assert_eq!(combo.get_selected(), Some(3));
+
+        //While open, the popup layer gets the text before the tree.
+        send(key(Keycode::Return, Scancode::Return));
+        assert!(combo.is_open());
+        wait(Duration::ZERO);
+        time_out();
+        assert!(overlay::event(canvas, &text("a"), unit).expect(""));
+        assert!(overlay::event(canvas, &key(Keycode::Return, Scancode::Return), unit).expect(""));
+        wait(Duration::ZERO);
+        assert!(!combo.is_open());
+        assert_eq!(combo.get_selected(), Some(0));
+        assert!(!overlay::event(canvas, &text("a"), unit).expect(""));
+    }
+}
@@ -0,0 +1,1025 @@
+use std::{cmp::Ordering, marker::PhantomData, time::Duration};
+
+use anyhow::{anyhow, Result};
+use sdl2::{
+    keyboard::Keycode,
+    mouse::MouseButton,
+    pixels::Color,
+    rect::{FPoint, FRect},
+    render::Canvas,
+    ttf::Font,
+    video::Window,
+};
+
+use crate::{
+    event::{mark_handled, Event},
+    functions::{FnAction, FnColor},
+    missing::{rect::clipped, ui_string::UIString},
+    refs::{MutRef, Ref},
+    ui_element::{
+        grid::ColType,
+        place,
+        scroll_view::{ScrollBarPlacement, ScrollBarStyle},
+        text_box::TextBox,
+    },
+    user_control::UserControl,
+    zero,
+};
+
+pub type FnCellText<Item> = Box<dyn Fn(&Item) -> String>;
+pub type FnCompare<Item> = Box<dyn Fn(&Item, &Item) -> Ordering>;
+///Writes the edited text of a cell back into its item.
+pub type FnEdit<Item> = Box<dyn Fn(&mut Item, &str) -> Result<()>>;
+///Creates the TextBox used to edit a cell from the cell's text.
+pub type FnEditor<Parent, State> = Box<dyn Fn(UIString) -> TextBox<Parent, State>>;
+
+///How close to a header border the mouse has to be to resize the column.
+const BORDER_GRAB: f32 = 4.;
+const MIN_COLUMN_WIDTH: f32 = 20.;
+const CELL_PADDING: f32 = 4.;
+
+pub struct Column<Item: 'static> {
+    title: String,
+    width: ColType,
+    text: FnCellText<Item>,
+    compare: Option<FnCompare<Item>>,
+    edit: Option<FnEdit<Item>>,
+}
+
+impl<Item: 'static> Column<Item> {
+    pub fn new(title: &str, width: ColType, text: FnCellText<Item>) -> Self {
+        Self {
+            title: title.to_owned(),
+            width,
+            text,
+            compare: None,
+            edit: None,
+        }
+    }
+
+    ///Lets the user sort the rows by clicking on the header of this column.
+    pub fn sortable(mut self, compare: FnCompare<Item>) -> Self {
+        self.compare = Some(compare);
+        self
+    }
+
+    ///Lets the user edit the cells of this column by double clicking them.
+    pub fn editable(mut self, edit: FnEdit<Item>) -> Self {
+        self.edit = Some(edit);
+        self
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub const fn width(&self) -> &ColType {
+        &self.width
+    }
+
+    pub fn width_mut(&mut self) -> &mut ColType {
+        &mut self.width
+    }
+}
+
+///Shows items as rows of cells under a header that stays in place while scrolling.</br>
+///Only the visible rows are drawn.
+pub struct DataGrid<Parent: 'static, State: 'static, Item: 'static> {
+    parent: PhantomData<Parent>,
+    state: PhantomData<State>,
+    surface: FRect,
+    font: &'static Font<'static, 'static>,
+    columns: Vec<Column<Item>>,
+    items: Vec<Item>,
+    ///The item indexes in the order they are shown.
+    order: Vec<usize>,
+    ///The sorted column and if it is ascending.
+    sort: Option<(usize, bool)>,
+    dirty: bool,
+    header_height: f32,
+    row_height: f32,
+    scroll: f32,
+    wheel_step: f32,
+    style: ScrollBarStyle,
+    front_color: FnColor<Self, Parent, State>,
+    back_color: FnColor<Self, Parent, State>,
+    header_color: FnColor<Self, Parent, State>,
+    select_color: FnColor<Self, Parent, State>,
+    scroll_color: FnColor<Self, Parent, State>,
+    selected: Option<usize>,
+    ///It gets called when the user selects another row.
+    selection_changed: Option<FnAction<Self, Parent, State>>,
+    editor: Option<FnEditor<Parent, State>>,
+    ///The edited item, column and its TextBox.
+    editing: Option<(usize, usize, TextBox<Parent, State>)>,
+    ///The resized column, where the drag started and the width it had.
+    resizing: Option<(usize, f32, f32)>,
+    thumb_selected: bool,
+    focused: bool,
+}
+
+impl<Parent: 'static, State: 'static, Item: 'static> DataGrid<Parent, State, Item> {
+    pub fn new(
+        font: &'static Font<'static, 'static>,
+        columns: Vec<Column<Item>>,
+        items: Vec<Item>,
+        front_color: FnColor<Self, Parent, State>,
+        back_color: FnColor<Self, Parent, State>,
+    ) -> Self {
+        Self {
+            parent: PhantomData,
+            state: PhantomData,
+            surface: zero(),
+            font,
+            columns,
+            order: (0..items.len()).collect(),
+            items,
+            sort: None,
+            dirty: false,
+            header_height: 30.,
+            row_height: 25.,
+            scroll: 0.,
+            wheel_step: 40.,
+            style: ScrollBarStyle::default(),
+            front_color,
+            back_color,
+            header_color: Box::new(|_, _, _| Color::RGB(60, 60, 60)),
+            select_color: Box::new(|_, _, _| Color::RGBA(70, 120, 200, 160)),
+            scroll_color: Box::new(|_, _, _| Color::RGB(120, 120, 120)),
+            selected: None,
+            selection_changed: None,
+            editor: None,
+            editing: None,
+            resizing: None,
+            thumb_selected: false,
+            focused: false,
+        }
+    }
+
+    pub fn header_color(mut self, header_color: FnColor<Self, Parent, State>) -> Self {
+        self.header_color = header_color;
+        self
+    }
+
+    pub fn select_color(mut self, select_color: FnColor<Self, Parent, State>) -> Self {
+        self.select_color = select_color;
+        self
+    }
+
+    pub fn scroll_color(mut self, scroll_color: FnColor<Self, Parent, State>) -> Self {
+        self.scroll_color = scroll_color;
+        self
+    }
+
+    pub const fn style(mut self, style: ScrollBarStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub const fn header_height(mut self, header_height: f32) -> Self {
+        self.header_height = header_height;
+        self
+    }
+
+    pub const fn row_height(mut self, row_height: f32) -> Self {
+        self.row_height = row_height;
+        self
+    }
+
+    ///Lets the user edit the editable columns with the TextBox it creates.
+    pub fn editor(mut self, editor: FnEditor<Parent, State>) -> Self {
+        self.editor = Some(editor);
+        self
+    }
+
+    pub fn selection_changed(mut self, selection_changed: FnAction<Self, Parent, State>) -> Self {
+        self.selection_changed = Some(selection_changed);
+        self
+    }
+
+    pub fn columns(&self) -> &[Column<Item>] {
+        &self.columns
+    }
+
+    pub fn columns_mut(&mut self) -> &mut Vec<Column<Item>> {
+        self.dirty = true;
+        &mut self.columns
+    }
+
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    ///Gives access to the items, the rows get sorted again on the next update.
+    pub fn items_mut(&mut self) -> &mut Vec<Item> {
+        self.dirty = true;
+        &mut self.items
+    }
+
+    ///The item indexes in the order they are shown.
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    ///The index of the selected item.
+    pub const fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn set_selected(&mut self, selected: Option<usize>) {
+        self.selected = selected.filter(|item| *item < self.items.len());
+    }
+
+    pub const fn sorted_by(&self) -> Option<(usize, bool)> {
+        self.sort
+    }
+
+    pub fn sort_by(&mut self, column: usize, ascending: bool) {
+        self.sort = Some((column, ascending));
+        self.refresh_order();
+    }
+
+    pub const fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    fn refresh_order(&mut self) {
+        self.order = (0..self.items.len()).collect();
+        if let Some((column, ascending)) = self.sort {
+            if let Some(compare) = self.columns.get(column).and_then(|c| c.compare.as_ref()) {
+                let items = &self.items;
+                self.order.sort_by(|a, b| {
+                    let ordering = compare(&items[*a], &items[*b]);
+                    if ascending {
+                        ordering
+                    } else {
+                        ordering.reverse()
+                    }
+                });
+            }
+        }
+        self.selected = self.selected.filter(|item| *item < self.items.len());
+        self.dirty = false;
+    }
+
+    ///The width of each column, the Ratio columns share what the Px columns leave.
+    pub fn widths(&self) -> Vec<f32> {
+        let mut static_x = 0.;
+        let mut dyn_x = 0.;
+        for column in &self.columns {
+            match column.width {
+                ColType::Px(x) => static_x += x,
+                ColType::Ratio(x) => dyn_x += x,
+            }
+        }
+        let remain = (self.body().width() - static_x).max(0.);
+        self.columns
+            .iter()
+            .map(|column| column.width.scale_ration(dyn_x).to_px(remain))
+            .collect()
+    }
+
+    fn bar(&self) -> bool {
+        self.order.len() as f32 * self.row_height > self.surface.height() - self.header_height
+    }
+
+    ///Where the rows are shown, under the header.
+    fn body(&self) -> FRect {
+        let gutter = if self.style.placement == ScrollBarPlacement::Gutter && self.bar() {
+            self.style.thickness
+        } else {
+            0.
+        };
+        FRect::new(
+            self.surface.x(),
+            self.surface.y() + self.header_height,
+            (self.surface.width() - gutter).max(0.),
+            (self.surface.height() - self.header_height).max(0.),
+        )
+    }
+
+    fn header(&self) -> FRect {
+        FRect::new(
+            self.surface.x(),
+            self.surface.y(),
+            self.body().width(),
+            self.header_height.min(self.surface.height()),
+        )
+    }
+
+    fn max_scroll(&self) -> f32 {
+        (self.order.len() as f32 * self.row_height - self.body().height()).max(0.)
+    }
+
+    pub fn set_scroll(&mut self, scroll: f32) {
+        self.scroll = scroll.clamp(0., self.max_scroll());
+    }
+
+    ///Scrolls as little as possible to make the shown row visible.
+    pub fn scroll_to(&mut self, row: usize) {
+        let top = row as f32 * self.row_height;
+        let height = self.body().height();
+        if top < self.scroll {
+            self.set_scroll(top);
+        } else if top + self.row_height > self.scroll + height {
+            self.set_scroll(top + self.row_height - height);
+        }
+    }
+
+    fn track(&self) -> FRect {
+        let body = self.body();
+        FRect::new(
+            self.surface.x() + self.surface.width() - self.style.thickness,
+            body.y(),
+            self.style.thickness,
+            body.height(),
+        )
+    }
+
+    fn thumb(&self) -> FRect {
+        let track = self.track();
+        let total = self.order.len() as f32 * self.row_height;
+        let h = (track.height() * track.height() / total)
+            .max(self.style.min_thumb_size)
+            .min(track.height());
+        let max_scroll = self.max_scroll();
+        let ratio = if max_scroll > 0. {
+            self.scroll / max_scroll
+        } else {
+            0.
+        };
+        FRect::new(
+            track.x(),
+            track.y() + ratio * (track.height() - h),
+            track.width(),
+            h,
+        )
+    }
+
+    ///The shown row at the window position y.
+    fn row_at(&self, y: f32) -> Option<usize> {
+        let body = self.body();
+        if y < body.y() || y >= body.y() + body.height() {
+            return None;
+        }
+        let row = ((y - body.y() + self.scroll) / self.row_height) as usize;
+        (row < self.order.len()).then_some(row)
+    }
+
+    fn column_at(&self, x: f32) -> Option<usize> {
+        let mut left = self.surface.x();
+        for (column, width) in self.widths().into_iter().enumerate() {
+            if x >= left && x < left + width {
+                return Some(column);
+            }
+            left += width;
+        }
+        None
+    }
+
+    ///The column whose right border is at the window position x.
+    fn border_at(&self, x: f32) -> Option<usize> {
+        let mut right = self.surface.x();
+        for (column, width) in self.widths().into_iter().enumerate() {
+            right += width;
+            if (x - right).abs() <= BORDER_GRAB {
+                return Some(column);
+            }
+        }
+        None
+    }
+
+    fn cell(&self, row: usize, column: usize) -> FRect {
+        let widths = self.widths();
+        let left: f32 = widths[..column].iter().sum();
+        let body = self.body();
+        FRect::new(
+            body.x() + left,
+            body.y() + row as f32 * self.row_height - self.scroll,
+            widths[column],
+            self.row_height,
+        )
+    }
+
+    fn start_edit(&mut self, item: usize, column: usize) -> Result<bool> {
+        let (Some(editor), Some(column_data)) = (self.editor.as_ref(), self.columns.get(column))
+        else {
+            return Ok(false);
+        };
+        //The order can still show an item removed through items_mut until the next update.
+        let (Some(_), Some(item_data)) = (column_data.edit.as_ref(), self.items.get(item)) else {
+            return Ok(false);
+        };
+        let text = UIString::new(self.font, (column_data.text)(item_data))?
+            .unwrap_or_else(|| UIString::new_const(self.font, ""));
+        let mut text_box = editor(text);
+        text_box.focus();
+        self.editing = Some((item, column, text_box));
+        Ok(true)
+    }
+
+    ///Writes the edited text into the item.
+    pub fn commit_edit(&mut self) -> Result<()> {
+        if let Some((item, column, text_box)) = self.editing.take() {
+            //The edit is dropped when its item was removed meanwhile.
+            if let (Some(edit), Some(item)) = (
+                self.columns.get(column).and_then(|c| c.edit.as_ref()),
+                self.items.get_mut(item),
+            ) {
+                edit(item, text_box.text().as_str())?;
+            }
+            self.refresh_order();
+        }
+        Ok(())
+    }
+
+    pub fn cancel_edit(&mut self) {
+        self.editing = None;
+    }
+
+    fn place_editor(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let Some((item, column, _)) = this.editing.as_ref() else {
+            return Ok(());
+        };
+        let row = this.order.iter().position(|i| i == item);
+        let (Some(row), true) = (row, *column < this.columns.len()) else {
+            this.editing = None;
+            return Ok(());
+        };
+        let target = this.cell(row, *column);
+        let Some((_, _, text_box)) = this.editing.as_mut() else {
+            return Ok(());
+        };
+        place(MutRef::new(text_box), target, canvas, parent, state)
+    }
+
+    fn select_row(
+        mut this: MutRef<Self>,
+        row: usize,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let item = this.order.get(row).copied();
+        this.scroll_to(row);
+        if item != this.selected {
+            this.selected = item;
+            let t = this;
+            if let Some(selection_changed) = this.selection_changed.as_mut() {
+                selection_changed(t, parent, state, canvas)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///Handles the events going to the TextBox while a cell is edited, returns if it was used.
+    fn editor_event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: &Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<bool> {
+        let Some((_, _, text_box)) = this.editing.as_mut() else {
+            return Ok(false);
+        };
+        let text_box = MutRef::new(text_box);
+        match *event {
+            Event::KeyDown {
+                keycode: Some(Keycode::Return | Keycode::KpEnter),
+                ..
+            } => {
+                this.commit_edit()?;
+                mark_handled();
+                return Ok(true);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Escape),
+                ..
+            } => {
+                this.cancel_edit();
+                mark_handled();
+                return Ok(true);
+            }
+            Event::MouseButtonDown { .. }
+                if !event.hover(UserControl::surface(
+                    text_box.into(),
+                    parent.into(),
+                    state.into(),
+                )) =>
+            {
+                this.commit_edit()?;
+                return Ok(false);
+            }
+            _ => {}
+        }
+        UserControl::event(text_box, canvas, event.clone(), parent, state)?;
+        Ok(matches!(
+            event,
+            Event::KeyDown { .. } | Event::KeyUp { .. } | Event::MouseButtonDown { .. }
+        ))
+    }
+
+    fn mouse_event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: &Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match *event {
+            Event::MouseMotion {
+                mousestate, x, y, ..
+            } if mousestate.left() => {
+                if let Some((column, start, width)) = this.resizing {
+                    let width = (width + x - start).max(MIN_COLUMN_WIDTH);
+                    this.columns[column].width = ColType::Px(width);
+                } else if this.thumb_selected {
+                    let track = this.track();
+                    let thumb = this.thumb();
+                    let ratio = ((y - track.y() - thumb.height() / 2.)
+                        / (track.height() - thumb.height()))
+                    .clamp(0., 1.);
+                    let max_scroll = this.max_scroll();
+                    this.set_scroll(ratio * max_scroll);
+                }
+            }
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Left,
+                clicks,
+                x,
+                y,
+                ..
+            } => {
+                let point = FPoint::new(x, y);
+                this.focused = this.surface.contains_point(point);
+                if this.bar() && this.thumb().contains_point(point) {
+                    this.thumb_selected = true;
+                } else if this.header().contains_point(point) {
+                    if let Some(column) = this.border_at(x) {
+                        let width = this.widths()[column];
+                        this.resizing = Some((column, x, width));
+                    } else if let Some(column) = this.column_at(x) {
+                        if this.columns[column].compare.is_some() {
+                            let ascending = this.sort != Some((column, true));
+                            this.sort_by(column, ascending);
+                        }
+                    }
+                } else if let Some(row) = this.row_at(y) {
+                    Self::select_row(this, row, canvas, parent, state)?;
+                    if let (2, Some(column)) = (clicks, this.column_at(x)) {
+                        let item = this.order[row];
+                        if this.start_edit(item, column)? {
+                            Self::place_editor(this, canvas, parent, state)?;
+                        }
+                    }
+                }
+            }
+            Event::MouseButtonDown { x, y, .. } => {
+                this.focused = this.surface.contains_point(FPoint::new(x, y));
+            }
+            Event::MouseButtonUp { .. } => {
+                this.resizing = None;
+                this.thumb_selected = false;
+            }
+            Event::MouseWheel {
+                scroll_y,
+                mouse_x,
+                mouse_y,
+                ..
+            } if this.surface.contains_point(FPoint::new(mouse_x, mouse_y)) => {
+                let scroll = this.scroll - scroll_y * this.wheel_step;
+                this.set_scroll(scroll);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn key_event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        keycode: Keycode,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let len = this.order.len();
+        if len == 0 {
+            return Ok(());
+        }
+        let row = this
+            .selected
+            .and_then(|item| this.order.iter().position(|i| *i == item));
+        let page = (this.body().height() / this.row_height).max(1.) as usize;
+        let row = match (keycode, row) {
+            (Keycode::Up, Some(row)) => row.saturating_sub(1),
+            (Keycode::Down, Some(row)) => (row + 1).min(len - 1),
+            (Keycode::PageUp, Some(row)) => row.saturating_sub(page),
+            (Keycode::PageDown, Some(row)) => (row + page).min(len - 1),
+            (Keycode::Up | Keycode::Down | Keycode::PageUp | Keycode::PageDown, None)
+            | (Keycode::Home, _) => 0,
+            (Keycode::End, _) => len - 1,
+            (Keycode::F2 | Keycode::Return, Some(row)) => {
+                let item = this.order[row];
+                if let Some(column) = this.columns.iter().position(|c| c.edit.is_some()) {
+                    if this.start_edit(item, column)? {
+                        Self::place_editor(this, canvas, parent, state)?;
+                    }
+                }
+                mark_handled();
+                return Ok(());
+            }
+            _ => return Ok(()),
+        };
+        mark_handled();
+        Self::select_row(this, row, canvas, parent, state)
+    }
+
+    fn draw_rows(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        let front_color = (this.front_color)(this, parent, state);
+        let select_color = (this.select_color)(this, parent, state);
+        let body = this.body();
+        let widths = this.widths();
+        let first = (this.scroll / this.row_height) as usize;
+        let last = (((this.scroll + body.height()) / this.row_height).ceil() as usize)
+            .min(this.order.len());
+        for row in first..last {
+            let item = this.order[row];
+            let Some(item_data) = this.items.get(item) else {
+                continue;
+            };
+            let y = body.y() + row as f32 * this.row_height - this.scroll;
+            if this.selected == Some(item) {
+                canvas.set_draw_color(select_color);
+                canvas
+                    .fill_frect(FRect::new(body.x(), y, body.width(), this.row_height))
+                    .map_err(|e| anyhow!(e))?;
+            }
+            let mut x = body.x();
+            for (column, width) in this.columns.iter().zip(widths.iter()) {
+                if let Some(text) = UIString::new(this.font, (column.text)(item_data))? {
+                    text.draw_left(
+                        canvas,
+                        FRect::new(
+                            x + CELL_PADDING,
+                            y,
+                            (width - 2. * CELL_PADDING).max(0.),
+                            this.row_height,
+                        ),
+                        front_color,
+                    )?;
+                }
+                x += width;
+            }
+        }
+        if let Some((_, _, text_box)) = this.editing.as_ref() {
+            UserControl::draw(text_box.into(), canvas, parent, state)?;
+        }
+        Ok(())
+    }
+
+    fn draw_header(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        let header = this.header();
+        let front_color = (this.front_color)(this, parent, state);
+        canvas.set_draw_color((this.header_color)(this, parent, state));
+        canvas.fill_frect(header).map_err(|e| anyhow!(e))?;
+        let mut x = header.x();
+        for (index, (column, width)) in this.columns.iter().zip(this.widths()).enumerate() {
+            let title = match this.sort {
+                Some((sorted, true)) if sorted == index => format!("{} ^", column.title),
+                Some((sorted, false)) if sorted == index => format!("{} v", column.title),
+                _ => column.title.clone(),
+            };
+            if let Some(title) = UIString::new(this.font, title)? {
+                title.draw_left(
+                    canvas,
+                    FRect::new(
+                        x + CELL_PADDING,
+                        header.y(),
+                        (width - 2. * CELL_PADDING).max(0.),
+                        header.height(),
+                    ),
+                    front_color,
+                )?;
+            }
+            x += width;
+            canvas.set_draw_color(front_color);
+            canvas
+                .draw_fline(
+                    FPoint::new(x, header.y()),
+                    FPoint::new(x, header.y() + header.height()),
+                )
+                .map_err(|e| anyhow!(e))?;
+        }
+        canvas
+            .draw_fline(
+                FPoint::new(header.x(), header.y() + header.height()),
+                FPoint::new(header.x() + header.width(), header.y() + header.height()),
+            )
+            .map_err(|e| anyhow!(e))
+    }
+}
+
+impl<Parent: 'static, State: 'static, Item: 'static> UserControl<Parent, State>
+    for DataGrid<Parent, State, Item>
+{
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match event {
+            Event::ElementMove { x, y } => {
+                this.surface.set_x(x);
+                this.surface.set_y(y);
+                return Self::place_editor(this, canvas, parent, state);
+            }
+            Event::ElementResize { width, height } => {
+                this.surface.set_width(width);
+                this.surface.set_height(height);
+                let scroll = this.scroll;
+                this.set_scroll(scroll);
+                return Self::place_editor(this, canvas, parent, state);
+            }
+            _ => {}
+        }
+        if Self::editor_event(this, canvas, &event, parent, state)? {
+            return Ok(());
+        }
+        match event {
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } if this.focused => Self::key_event(this, canvas, keycode, parent, state),
+            event => Self::mouse_event(this, canvas, &event, parent, state),
+        }
+    }
+
+    fn update(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        elapsed: Duration,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        if this.dirty {
+            this.refresh_order();
+            let scroll = this.scroll;
+            this.set_scroll(scroll);
+        }
+        Self::place_editor(this, canvas, parent, state)?;
+        if let Some((_, _, text_box)) = this.editing.as_mut() {
+            UserControl::update(text_box.into(), canvas, elapsed, parent, state)?;
+        }
+        Ok(())
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        canvas.set_draw_color((this.back_color)(this, parent, state));
+        canvas.fill_frect(this.surface).map_err(|e| anyhow!(e))?;
+        clipped(canvas, this.body(), |canvas| {
+            Self::draw_rows(this, canvas, parent, state)
+        })?;
+        clipped(canvas, this.header(), |canvas| {
+            Self::draw_header(this, canvas, parent, state)
+        })?;
+        if !this.bar() || (this.style.auto_hide && !this.thumb_selected) {
+            return Ok(());
+        }
+        if let Some(track_color) = this.style.track_color {
+            canvas.set_draw_color(track_color);
+            canvas.fill_frect(this.track()).map_err(|e| anyhow!(e))?;
+        }
+        let color = (this.scroll_color)(this, parent, state);
+        canvas.set_draw_color(if this.thumb_selected {
+            this.style.thumb_pressed_color.unwrap_or(color)
+        } else {
+            color
+        });
+        canvas.fill_frect(this.thumb()).map_err(|e| anyhow!(e))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod data_grid_test {
+    use sdl2::{keyboard::Mod, mouse::MouseState};
+
+    use crate::functions::StateEnum;
+
+    use super::*;
+
+    type Row = (String, u32);
+
+    fn data_grid() -> DataGrid<(), usize, Row> {
+        DataGrid::new(
+            UIString::no_font(),
+            vec![
+                Column::new(
+                    "name",
+                    ColType::Ratio(1.),
+                    Box::new(|row: &Row| row.0.clone()),
+                )
+                .sortable(Box::new(|a, b| a.0.cmp(&b.0)))
+                .editable(Box::new(|row, text| {
+                    text.clone_into(&mut row.0);
+                    Ok(())
+                })),
+                Column::new(
+                    "size",
+                    ColType::Px(20.),
+                    Box::new(|row: &Row| row.1.to_string()),
+                )
+                .sortable(Box::new(|a, b| a.1.cmp(&b.1)))
+                .editable(Box::new(|row, text| {
+                    row.1 = text.parse()?;
+                    Ok(())
+                })),
+            ],
+            vec![
+                ("b".to_owned(), 3),
+                ("a".to_owned(), 1),
+                ("c".to_owned(), 2),
+            ],
+            Box::new(|_, _, _| Color::WHITE),
+            Box::new(|_, _, _| Color::BLACK),
+        )
+        .header_height(10.)
+        .row_height(10.)
+        .editor(Box::new(|text| {
+            TextBox::new(
+                UIString::no_font(),
+                text,
+                Box::new(|_, _, _| StateEnum::Enable),
+                Box::new(|_, _, _| Color::BLUE),
+                Box::new(|_, _, _| Color::BLUE),
+                Box::new(|_, _, _| Color::WHITE),
+                Box::new(|_, _, _| Color::BLACK),
+            )
+        }))
+        .selection_changed(Box::new(|_, _, mut changes: MutRef<usize>, _| {
+            *changes += 1;
+            Ok(())
+        }))
+    }
+
+    const fn click(clicks: u8, x: f32, y: f32) -> Event {
+        Event::MouseButtonDown {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks,
+            x,
+            y,
+        }
+    }
+
+    const fn key(keycode: Keycode) -> Event {
+        Event::KeyDown {
+            keycode: Some(keycode),
+            scancode: None,
+            keymod: Mod::NOMOD,
+            repeat: false,
+        }
+    }
+
+    ///Without a font the cell text can't be measured, so the edit starts as a double click starts it.
+    fn edit(mut grid: MutRef<DataGrid<(), usize, Row>>, item: usize, column: usize, text: &str) {
+        let text_box = grid.editor.as_ref().expect("")(UIString::unmeasured(text));
+        grid.editing = Some((item, column, text_box));
+    }
+
+    #[allow(clippy::too_many_lines)]
+    pub(crate) fn test_data_grid(canvas: &mut Canvas<Window>) {
+        let mut changes = 0_usize;
+        let changes = MutRef::new(&mut changes);
+        let mut grid = data_grid();
+        let mut grid = MutRef::new(&mut grid);
+        let mut parent = ();
+        let parent = MutRef::new(&mut parent);
+        let canvas = &*canvas;
+        let send = move |event| UserControl::event(grid, canvas, event, parent, changes).expect("");
+        let update = move || {
+            UserControl::update(grid, canvas, Duration::ZERO, parent, changes).expect("");
+        };
+        send(Event::ElementResize {
+            width: 100.,
+            height: 100.,
+        });
+        assert_eq!(grid.widths(), [80., 20.]);
+
+        //Clicking a header sorts by its column, clicking it again reverses the order.
+        send(click(1, 40., 5.));
+        assert_eq!(grid.sorted_by(), Some((0, true)));
+        assert_eq!(grid.order(), [1, 0, 2]);
+        send(click(1, 40., 5.));
+        assert_eq!(grid.sorted_by(), Some((0, false)));
+        assert_eq!(grid.order(), [2, 0, 1]);
+        send(click(1, 90., 5.));
+        assert_eq!(grid.sorted_by(), Some((1, true)));
+        assert_eq!(grid.order(), [1, 2, 0]);
+
+        //Dragging a header border resizes its column, down to the minimal width.
+        send(click(1, 81., 5.));
+        let drag = |x| Event::MouseMotion {
+            which: 0,
+            mousestate: MouseState::from_sdl_state(1),
+            x,
+            y: 5.,
+            moved_x: 0.,
+            moved_y: 0.,
+        };
+        send(drag(61.));
+        assert_eq!(grid.widths(), [60., 20.]);
+        send(drag(0.));
+        assert_eq!(grid.widths(), [MIN_COLUMN_WIDTH, 20.]);
+        send(drag(41.));
+        send(Event::MouseButtonUp {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 41.,
+            y: 5.,
+        });
+        send(drag(81.));
+        assert_eq!(grid.widths(), [40., 20.]);
+        assert_eq!(grid.sorted_by(), Some((1, true)));
+
+        //The rows are selected by clicking them or with the keys once focused.
+        send(click(1, 20., 25.));
+        assert_eq!(grid.selected(), Some(2));
+        send(click(1, 20., 25.));
+        assert_eq!(*changes, 1);
+        for (keycode, selected) in [
+            (Keycode::Up, 1),
+            (Keycode::Up, 1),
+            (Keycode::End, 0),
+            (Keycode::Down, 0),
+            (Keycode::Home, 1),
+        ] {
+            send(key(keycode));
+            assert_eq!(grid.selected(), Some(selected));
+        }
+        assert_eq!(*changes, 4);
+        //Clicking under the last row changes nothing.
+        send(click(1, 20., 60.));
+        assert_eq!(grid.selected(), Some(1));
+
+        //Return writes the edit into the item and sorts again, Escape drops it.
+        edit(grid, 1, 1, "5");
+        update();
+        assert!(grid.is_editing());
+        send(key(Keycode::Return));
+        assert!(!grid.is_editing());
+        assert_eq!(grid.items()[1], ("a".to_owned(), 5));
+        assert_eq!(grid.order(), [2, 0, 1]);
+        edit(grid, 0, 0, "d");
+        send(key(Keycode::Escape));
+        assert!(!grid.is_editing());
+        assert_eq!(grid.items()[0].0, "b");
+        //A click outside of the editor commits it.
+        edit(grid, 0, 0, "d");
+        update();
+        send(click(1, 20., 5.));
+        assert!(!grid.is_editing());
+        assert_eq!(grid.items()[0].0, "d");
+        assert_eq!(grid.sorted_by(), Some((0, true)));
+        assert_eq!(grid.order(), [1, 2, 0]);
+
+        //The edit is dropped when its column or its item is removed.
+        edit(grid, 2, 1, "7");
+        grid.columns_mut().pop();
+        update();
+        assert!(!grid.is_editing());
+        edit(grid, 0, 0, "e");
+        grid.items_mut().truncate(0);
+        update();
+        assert!(!grid.is_editing());
+        assert_eq!(grid.selected(), None);
+        assert!(grid.order().is_empty());
+    }
+}
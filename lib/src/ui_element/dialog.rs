@@ -0,0 +1,620 @@
+use std::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+    rc::Rc,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+use sdl2::{
+    keyboard::Keycode,
+    mouse::MouseButton,
+    pixels::Color,
+    rect::{FPoint, FRect},
+    render::Canvas,
+    ttf::Font,
+    video::Window,
+};
+
+use crate::{
+    event::Event,
+    functions::FnColor,
+    missing::ui_string::UIString,
+    overlay::{self, Layer, LayerId},
+    refs::{MutRef, Ref},
+    state_manager::StateManager,
+    user_control::UserControl,
+    zero,
+};
+
+const TITLE: f32 = 28.;
+const MARGIN: f32 = 8.;
+const BUTTON_WIDTH: f32 = 80.;
+const BUTTON_HEIGHT: f32 = 28.;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogResult {
+    Ok,
+    Cancel,
+    Yes,
+    No,
+}
+
+///It gets called once with the button the user chose to close the dialog.
+pub type FnResult<Parent, State> =
+    Box<dyn FnOnce(DialogResult, MutRef<Parent>, MutRef<State>, &Canvas<Window>) -> Result<()>>;
+
+///Gives the result to a closure queued in the manager, for results that edit what it guards.</br>
+///The caller keeps the other handle on the manager and applies it.
+pub fn queued<Parent: 'static, State: 'static, Args: Copy + 'static>(
+    manager: Rc<RefCell<StateManager<Args>>>,
+    func: impl FnOnce(DialogResult, Args) -> Result<()> + 'static,
+) -> FnResult<Parent, State> {
+    Box::new(move |result, _, _, _| {
+        manager
+            .borrow_mut()
+            .add(Box::new(move |args| func(result, args)));
+        Ok(())
+    })
+}
+
+///Moves the dialog to the center of the window when it isn't already there.
+fn center<Parent: 'static, State: 'static, Dialog: UserControl<Parent, State>>(
+    canvas: &Canvas<Window>,
+    dialog: MutRef<Dialog>,
+    (width, height): (f32, f32),
+    parent: MutRef<Parent>,
+    state: MutRef<State>,
+) -> Result<()> {
+    let (window_width, window_height) = canvas.output_size().map_err(|e| anyhow!(e))?;
+    let (x, y) = (
+        ((window_width as f32 - width) / 2.).floor(),
+        ((window_height as f32 - height) / 2.).floor(),
+    );
+    let surface = UserControl::surface(dialog.into(), parent.into(), state.into());
+    if surface.x() != x || surface.y() != y {
+        UserControl::event(dialog, canvas, Event::ElementMove { x, y }, parent, state)?;
+    }
+    if surface.width() != width || surface.height() != height {
+        UserControl::event(
+            dialog,
+            canvas,
+            Event::ElementResize { width, height },
+            parent,
+            state,
+        )?;
+    }
+    Ok(())
+}
+
+///Shows the dialog centered above everything with the rest of the window dimmed.</br>
+///Nothing under it gets any input until the returned layer is removed.</br>
+///The dialog gets the parent and the state of the game through the overlay, it is placed on the next update.
+pub fn show_modal<State: 'static, Dialog: UserControl<(), State> + 'static>(
+    dialog: Dialog,
+    size: (f32, f32),
+    dim: Color,
+) -> LayerId {
+    let dialog = Rc::new(RefCell::new(dialog));
+    let surface = Rc::new(Cell::new(zero()));
+    let placed = surface.clone();
+    let (draw, event, update) = (dialog.clone(), dialog.clone(), dialog);
+    let layer = Layer::new(
+        Box::new(move || surface.get()),
+        Box::new(move |canvas, state| {
+            canvas.set_draw_color(dim);
+            canvas.fill_rect(None).map_err(|e| anyhow!(e))?;
+            UserControl::draw(
+                Ref::new(&*draw.borrow()),
+                canvas,
+                Ref::new(&()),
+                overlay::state_ref(state)?,
+            )
+        }),
+    )
+    .modal(true)
+    .event(Box::new(move |canvas, e, state| {
+        let mut parent = ();
+        UserControl::event(
+            MutRef::new(&mut *event.borrow_mut()),
+            canvas,
+            e.clone(),
+            MutRef::new(&mut parent),
+            overlay::state(state)?,
+        )?;
+        Ok(e.is_input())
+    }))
+    .update(Box::new(move |canvas, elapsed, state| {
+        let mut parent = ();
+        let parent = MutRef::new(&mut parent);
+        let state = overlay::state(state)?;
+        let dialog = MutRef::new(&mut *update.borrow_mut());
+        center(canvas, dialog, size, parent, state)?;
+        placed.set(UserControl::surface(
+            dialog.into(),
+            parent.into(),
+            state.into(),
+        ));
+        UserControl::update(dialog, canvas, elapsed, parent, state)
+    }));
+    overlay::push(layer)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageBoxButtons {
+    Ok,
+    OkCancel,
+    YesNoCancel,
+}
+
+impl MessageBoxButtons {
+    pub const fn results(self) -> &'static [DialogResult] {
+        match self {
+            Self::Ok => &[DialogResult::Ok],
+            Self::OkCancel => &[DialogResult::Ok, DialogResult::Cancel],
+            Self::YesNoCancel => &[DialogResult::Yes, DialogResult::No, DialogResult::Cancel],
+        }
+    }
+}
+
+const fn label(result: DialogResult) -> &'static str {
+    match result {
+        DialogResult::Ok => "OK",
+        DialogResult::Cancel => "Cancel",
+        DialogResult::Yes => "Yes",
+        DialogResult::No => "No",
+    }
+}
+
+///A modal message with a title and a row of buttons, Return picks the first one and Escape the last one.
+pub struct MessageBox<Parent: 'static, State: 'static> {
+    parent: PhantomData<Parent>,
+    statel: PhantomData<State>,
+    surface: FRect,
+    title: UIString,
+    text: UIString,
+    buttons: Vec<(DialogResult, UIString)>,
+    ///It gets called once with the button the user chose.
+    on_result: Option<FnResult<Parent, State>>,
+    back_color: FnColor<Self, Parent, State>,
+    front_color: FnColor<Self, Parent, State>,
+    button_color: FnColor<Self, Parent, State>,
+    size: (f32, f32),
+    dim: Color,
+    ///Set once shown so the message box closes itself.
+    layer: Rc<Cell<Option<LayerId>>>,
+    hover: Option<usize>,
+}
+
+impl<Parent: 'static, State: 'static> MessageBox<Parent, State> {
+    pub fn new(
+        font: &'static Font<'static, 'static>,
+        title: &str,
+        text: &str,
+        buttons: MessageBoxButtons,
+        on_result: FnResult<Parent, State>,
+    ) -> Self {
+        Self {
+            parent: PhantomData,
+            statel: PhantomData,
+            surface: zero(),
+            title: UIString::new_const(font, title),
+            text: UIString::new_const(font, text),
+            buttons: buttons
+                .results()
+                .iter()
+                .map(|result| (*result, UIString::new_const(font, label(*result))))
+                .collect(),
+            on_result: Some(on_result),
+            back_color: Box::new(|_, _, _| Color::RGB(240, 240, 240)),
+            front_color: Box::new(|_, _, _| Color::BLACK),
+            button_color: Box::new(|_, _, _| Color::RGB(200, 200, 200)),
+            size: (360., 160.),
+            dim: Color::RGBA(0, 0, 0, 128),
+            layer: Rc::new(Cell::new(None)),
+            hover: None,
+        }
+    }
+
+    pub fn ok(
+        font: &'static Font<'static, 'static>,
+        title: &str,
+        text: &str,
+        on_result: FnResult<Parent, State>,
+    ) -> Self {
+        Self::new(font, title, text, MessageBoxButtons::Ok, on_result)
+    }
+
+    pub fn ok_cancel(
+        font: &'static Font<'static, 'static>,
+        title: &str,
+        text: &str,
+        on_result: FnResult<Parent, State>,
+    ) -> Self {
+        Self::new(font, title, text, MessageBoxButtons::OkCancel, on_result)
+    }
+
+    pub fn yes_no_cancel(
+        font: &'static Font<'static, 'static>,
+        title: &str,
+        text: &str,
+        on_result: FnResult<Parent, State>,
+    ) -> Self {
+        Self::new(font, title, text, MessageBoxButtons::YesNoCancel, on_result)
+    }
+
+    pub const fn surface(&self) -> FRect {
+        self.surface
+    }
+
+    pub const fn size(mut self, width: f32, height: f32) -> Self {
+        self.size = (width, height);
+        self
+    }
+
+    ///The color drawn over the window under the message box.
+    pub const fn dim(mut self, dim: Color) -> Self {
+        self.dim = dim;
+        self
+    }
+
+    pub fn back_color(mut self, back_color: FnColor<Self, Parent, State>) -> Self {
+        self.back_color = back_color;
+        self
+    }
+
+    pub fn front_color(mut self, front_color: FnColor<Self, Parent, State>) -> Self {
+        self.front_color = front_color;
+        self
+    }
+
+    pub fn button_color(mut self, button_color: FnColor<Self, Parent, State>) -> Self {
+        self.button_color = button_color;
+        self
+    }
+
+    fn button(&self, index: usize) -> FRect {
+        let from_right = (self.buttons.len() - index) as f32;
+        FRect::new(
+            self.surface.x() + self.surface.width() - from_right * (BUTTON_WIDTH + MARGIN),
+            self.surface.y() + self.surface.height() - MARGIN - BUTTON_HEIGHT,
+            BUTTON_WIDTH,
+            BUTTON_HEIGHT,
+        )
+    }
+
+    fn button_at(&self, x: f32, y: f32) -> Option<usize> {
+        (0..self.buttons.len()).find(|index| self.button(*index).contains_point(FPoint::new(x, y)))
+    }
+
+    fn finish(
+        mut this: MutRef<Self>,
+        result: DialogResult,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        if let Some(layer) = this.layer.take() {
+            overlay::remove(layer);
+        }
+        if let Some(on_result) = this.on_result.take() {
+            on_result(result, parent, state, canvas)?;
+        }
+        Ok(())
+    }
+}
+
+impl<State: 'static> MessageBox<(), State> {
+    ///Shows the message box above everything, returns its layer to close it without a result.</br>
+    ///The result gets the parent and the state of the game.
+    pub fn show(self) -> LayerId {
+        let layer = self.layer.clone();
+        let (size, dim) = (self.size, self.dim);
+        let id = show_modal(self, size, dim);
+        layer.set(Some(id));
+        id
+    }
+}
+
+impl<Parent: 'static, State: 'static> UserControl<Parent, State> for MessageBox<Parent, State> {
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match event {
+            Event::ElementMove { x, y } => {
+                this.surface.set_x(x);
+                this.surface.set_y(y);
+            }
+            Event::ElementResize { width, height } => {
+                this.surface.set_width(width);
+                this.surface.set_height(height);
+            }
+            Event::MouseMotion { x, y, .. } => this.hover = this.button_at(x, y),
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => {
+                if let Some(index) = this.button_at(x, y) {
+                    Self::finish(this, this.buttons[index].0, canvas, parent, state)?;
+                }
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Return | Keycode::KpEnter),
+                ..
+            } => {
+                let result = this.buttons.first().ok_or(anyhow!("Checked"))?.0;
+                Self::finish(this, result, canvas, parent, state)?;
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Escape),
+                ..
+            } => {
+                let result = this.buttons.last().ok_or(anyhow!("Checked"))?.0;
+                Self::finish(this, result, canvas, parent, state)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn update(
+        _: MutRef<Self>,
+        _: &Canvas<Window>,
+        _: Duration,
+        _: MutRef<Parent>,
+        _: MutRef<State>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        let surface = this.surface;
+        let front_color = (this.front_color)(this, parent, state);
+        let button_color = (this.button_color)(this, parent, state);
+        canvas.set_draw_color((this.back_color)(this, parent, state));
+        canvas.fill_frect(surface).map_err(|e| anyhow!(e))?;
+        let title = FRect::new(surface.x(), surface.y(), surface.width(), TITLE);
+        canvas.set_draw_color(button_color);
+        canvas.fill_frect(title).map_err(|e| anyhow!(e))?;
+        this.title.draw_left(
+            canvas,
+            FRect::new(
+                title.x() + MARGIN,
+                title.y(),
+                title.width() - 2. * MARGIN,
+                TITLE,
+            ),
+            front_color,
+        )?;
+        this.text.draw_left(
+            canvas,
+            FRect::new(
+                surface.x() + MARGIN,
+                surface.y() + TITLE,
+                surface.width() - 2. * MARGIN,
+                surface.height() - TITLE - BUTTON_HEIGHT - 2. * MARGIN,
+            ),
+            front_color,
+        )?;
+        for (index, (_, text)) in this.buttons.iter().enumerate() {
+            let button = this.button(index);
+            canvas.set_draw_color(button_color);
+            canvas.fill_frect(button).map_err(|e| anyhow!(e))?;
+            if this.hover == Some(index) {
+                canvas.set_draw_color(front_color);
+                canvas.draw_frect(button).map_err(|e| anyhow!(e))?;
+            }
+            let (width, _) = text.size()?;
+            let width = width.min(button.width());
+            text.draw_left(
+                canvas,
+                FRect::new(
+                    button.x() + (button.width() - width) / 2.,
+                    button.y(),
+                    width,
+                    button.height(),
+                ),
+                front_color,
+            )?;
+        }
+        canvas.set_draw_color(front_color);
+        canvas.draw_frect(surface).map_err(|e| anyhow!(e))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod dialog_test {
+    use sdl2::keyboard::Mod;
+
+    use crate::{
+        functions::StateEnum,
+        ui_element::{stack_panel::stack_panel_test::block, ui_rect::UIRect},
+    };
+
+    use super::*;
+
+    pub(crate) fn test_modal_blocking(canvas: &mut Canvas<Window>) {
+        let mut state = ();
+        let state = MutRef::new(&mut state);
+        let layer = show_modal::<(), _>(
+            UIRect::new(
+                Box::new(|_, _, _| StateEnum::Enable),
+                Box::new(|_, _, _| Color::WHITE),
+            ),
+            (20., 10.),
+            Color::RGBA(0, 0, 0, 128),
+        );
+
+        let click = Event::MouseButtonDown {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 1.,
+            y: 1.,
+        };
+        let key = Event::KeyDown {
+            keycode: Some(Keycode::A),
+            scancode: None,
+            keymod: Mod::NOMOD,
+            repeat: false,
+        };
+        assert!(overlay::event(canvas, &click, state).expect(""));
+        assert!(overlay::event(canvas, &key, state).expect(""));
+        assert!(!overlay::event(canvas, &Event::Quit, state).expect(""));
+        overlay::update(canvas, Duration::ZERO, state).expect("");
+        overlay::draw(canvas, state.into()).expect("");
+
+        assert!(overlay::remove(layer));
+        assert!(!overlay::event(canvas, &click, state).expect(""));
+        assert!(!overlay::event(canvas, &key, state).expect(""));
+    }
+
+    fn key(keycode: Keycode) -> Event {
+        Event::KeyDown {
+            keycode: Some(keycode),
+            scancode: None,
+            keymod: Mod::NOMOD,
+            repeat: false,
+        }
+    }
+
+    fn remember() -> FnResult<(), Option<DialogResult>> {
+        Box::new(|result, _, mut state, _| {
+            *state = Some(result);
+            Ok(())
+        })
+    }
+
+    pub(crate) fn test_message_box(canvas: &mut Canvas<Window>) {
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        let mut result = None;
+        let mut result = MutRef::new(&mut result);
+        let font = UIString::no_font();
+        let canvas = &*canvas;
+        let send = move |message, event| {
+            UserControl::event(message, canvas, event, unit, result).expect("");
+        };
+        let click = |x| Event::MouseButtonDown {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x,
+            y: 70.,
+        };
+
+        //The buttons are in a row at the bottom right, the result is only given once.
+        let mut message = MessageBox::yes_no_cancel(font, "Title", "Text", remember());
+        let message = MutRef::new(&mut message);
+        send(
+            message,
+            Event::ElementResize {
+                width: 300.,
+                height: 100.,
+            },
+        );
+        assert_eq!(message.button(0), FRect::new(36., 64., 80., 28.));
+        send(
+            message,
+            Event::MouseMotion {
+                which: 0,
+                mousestate: sdl2::mouse::MouseState::from_sdl_state(0),
+                x: 130.,
+                y: 70.,
+                moved_x: 0.,
+                moved_y: 0.,
+            },
+        );
+        assert_eq!(message.hover, Some(1));
+        send(message, click(130.));
+        assert_eq!(*result, Some(DialogResult::No));
+        send(message, click(50.));
+        assert_eq!(*result, Some(DialogResult::No));
+
+        let mut message = MessageBox::ok_cancel(font, "Title", "Text", remember());
+        send(MutRef::new(&mut message), key(Keycode::Return));
+        assert_eq!(*result, Some(DialogResult::Ok));
+        let mut message = MessageBox::ok_cancel(font, "Title", "Text", remember());
+        send(MutRef::new(&mut message), key(Keycode::Escape));
+        assert_eq!(*result, Some(DialogResult::Cancel));
+
+        //Shown, it closes its own layer.
+        *result = None;
+        let layer = MessageBox::ok(font, "Title", "Text", remember())
+            .size(30., 20.)
+            .show();
+        overlay::update(canvas, Duration::ZERO, result).expect("");
+        assert!(overlay::event(canvas, &key(Keycode::Escape), result).expect(""));
+        assert_eq!(*result, Some(DialogResult::Ok));
+        assert!(!overlay::contains(layer));
+    }
+
+    pub(crate) fn test_dialog_queued(canvas: &mut Canvas<Window>) {
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        let mut results = Vec::new();
+        let results = MutRef::new(&mut results);
+        let manager = Rc::new(RefCell::new(StateManager::new()));
+        let mut message = MessageBox::<(), ()>::ok_cancel(
+            UIString::no_font(),
+            "Title",
+            "Text",
+            queued(
+                manager.clone(),
+                |result, mut results: MutRef<Vec<DialogResult>>| {
+                    results.push(result);
+                    Ok(())
+                },
+            ),
+        );
+        UserControl::event(
+            MutRef::new(&mut message),
+            canvas,
+            key(Keycode::Escape),
+            unit,
+            unit,
+        )
+        .expect("");
+
+        //The result waits for the manager to be applied.
+        assert!(results.is_empty());
+        assert!(manager.borrow_mut().apply(results).expect(""));
+        assert_eq!(*results, [DialogResult::Cancel]);
+        assert!(!manager.borrow_mut().apply(results).expect(""));
+    }
+
+    pub(crate) fn test_dialog_center(canvas: &mut Canvas<Window>) {
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        let mut dialog = block(0., 0.);
+        let dialog = MutRef::new(&mut dialog);
+        let (width, height) = canvas.output_size().expect("");
+        center(canvas, dialog, (20., 10.), unit, unit).expect("");
+        assert_eq!(
+            dialog.surface,
+            FRect::new(
+                ((width as f32 - 20.) / 2.).floor(),
+                ((height as f32 - 10.) / 2.).floor(),
+                20.,
+                10.,
+            )
+        );
+        center(canvas, dialog, (21., 10.), unit, unit).expect("");
+        assert_eq!(dialog.surface.x(), ((width as f32 - 21.) / 2.).floor());
+    }
+}
@@ -0,0 +1,228 @@
+use std::{marker::PhantomData, time::Duration};
+
+use anyhow::Result;
+use sdl2::{rect::FRect, render::Canvas, video::Window};
+
+use crate::{
+    event::Event,
+    refs::{MutRef, Ref},
+    ui_element::place,
+    user_control::UserControl,
+    zero,
+};
+
+///The side of the space left a child takes, with its width on the left and the right and its height on the top and the bottom.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dock {
+    Left(f32),
+    Top(f32),
+    Right(f32),
+    Bottom(f32),
+    ///Takes all the space left, the children after it get nothing.
+    Fill,
+}
+
+///Docks its children in order, each one taking a side of the space the previous ones left.</br>
+///A docked child gets the size of its dock, or what is left when there isn't enough, and stretches along the side.
+pub struct DockPanel<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static> {
+    parent: PhantomData<Parent>,
+    statel: PhantomData<State>,
+    surface: FRect,
+    children: Vec<(Child, Dock)>,
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    DockPanel<Parent, State, Child>
+{
+    pub fn new(children: Vec<(Child, Dock)>) -> Self {
+        Self {
+            parent: PhantomData,
+            statel: PhantomData,
+            surface: zero(),
+            children,
+        }
+    }
+
+    pub const fn surface(&self) -> FRect {
+        self.surface
+    }
+
+    pub fn children(&self) -> &[(Child, Dock)] {
+        &self.children
+    }
+
+    pub fn children_mut(&mut self) -> &mut Vec<(Child, Dock)> {
+        &mut self.children
+    }
+
+    fn reform(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let mut left = this.surface;
+        for (child, dock) in this.children.iter_mut() {
+            let rect = match *dock {
+                Dock::Left(width) => {
+                    let width = width.clamp(0., left.width());
+                    let rect = FRect::new(left.x(), left.y(), width, left.height());
+                    left.set_x(left.x() + width);
+                    left.set_width(left.width() - width);
+                    rect
+                }
+                Dock::Top(height) => {
+                    let height = height.clamp(0., left.height());
+                    let rect = FRect::new(left.x(), left.y(), left.width(), height);
+                    left.set_y(left.y() + height);
+                    left.set_height(left.height() - height);
+                    rect
+                }
+                Dock::Right(width) => {
+                    let width = width.clamp(0., left.width());
+                    left.set_width(left.width() - width);
+                    FRect::new(left.x() + left.width(), left.y(), width, left.height())
+                }
+                Dock::Bottom(height) => {
+                    let height = height.clamp(0., left.height());
+                    left.set_height(left.height() - height);
+                    FRect::new(left.x(), left.y() + left.height(), left.width(), height)
+                }
+                Dock::Fill => {
+                    let rect = left;
+                    left.set_width(0.);
+                    left.set_height(0.);
+                    rect
+                }
+            };
+            place(child.into(), rect, canvas, parent, state)?;
+        }
+        Ok(())
+    }
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    UserControl<Parent, State> for DockPanel<Parent, State, Child>
+{
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match event {
+            Event::ElementMove { x, y } => {
+                this.surface.set_x(x);
+                this.surface.set_y(y);
+                return Self::reform(this, canvas, parent, state);
+            }
+            Event::ElementResize { width, height } => {
+                this.surface.set_width(width);
+                this.surface.set_height(height);
+                return Self::reform(this, canvas, parent, state);
+            }
+            _ => {}
+        }
+        for (child, _) in this.children.iter_mut() {
+            UserControl::event(child.into(), canvas, event.clone(), parent, state)?;
+        }
+        Ok(())
+    }
+
+    fn update(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        elapsed: Duration,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        for (child, _) in this.children.iter_mut() {
+            UserControl::update(child.into(), canvas, elapsed, parent, state)?;
+        }
+        Self::reform(this, canvas, parent, state)
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        for (child, _) in &this.children {
+            UserControl::draw(child.into(), canvas, parent, state)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod dock_panel_test {
+    use crate::ui_element::stack_panel::stack_panel_test::block;
+
+    use super::*;
+
+    pub(crate) fn test_dock_panel_layout(canvas: &mut Canvas<Window>) {
+        let mut dock = DockPanel::new(vec![
+            (block(0., 0.), Dock::Top(10.)),
+            (block(0., 0.), Dock::Bottom(5.)),
+            (block(0., 0.), Dock::Left(20.)),
+            (block(0., 0.), Dock::Right(15.)),
+            (block(0., 0.), Dock::Fill),
+            (block(10., 10.), Dock::Left(10.)),
+        ]);
+        let mut dock = MutRef::new(&mut dock);
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        for event in [
+            Event::ElementMove { x: 5., y: 5. },
+            Event::ElementResize {
+                width: 100.,
+                height: 50.,
+            },
+        ] {
+            UserControl::event(dock, canvas, event, unit, unit).expect("");
+        }
+
+        let surfaces: Vec<FRect> = dock
+            .children()
+            .iter()
+            .map(|(child, _)| child.surface)
+            .collect();
+        assert_eq!(
+            surfaces,
+            [
+                FRect::new(5., 5., 100., 10.),
+                FRect::new(5., 50., 100., 5.),
+                FRect::new(5., 15., 20., 35.),
+                FRect::new(90., 15., 15., 35.),
+                FRect::new(25., 15., 65., 35.),
+                FRect::new(25., 15., 0., 0.),
+            ]
+        );
+
+        //Docking the first child at the bottom gives the top to the children docked after it.
+        dock.children_mut()[0].1 = Dock::Bottom(10.);
+        UserControl::update(dock, canvas, Duration::ZERO, unit, unit).expect("");
+        let surfaces: Vec<FRect> = dock
+            .children()
+            .iter()
+            .map(|(child, _)| child.surface)
+            .collect();
+        assert_eq!(
+            surfaces,
+            [
+                FRect::new(5., 45., 100., 10.),
+                FRect::new(5., 40., 100., 5.),
+                FRect::new(5., 5., 20., 35.),
+                FRect::new(90., 5., 15., 35.),
+                FRect::new(25., 5., 65., 35.),
+                FRect::new(25., 5., 0., 0.),
+            ]
+        );
+    }
+}
@@ -0,0 +1,480 @@
+use std::{marker::PhantomData, ops::Range, time::Duration};
+
+use anyhow::Result;
+use sdl2::{rect::FRect, render::Canvas, video::Window};
+
+use crate::{
+    event::Event,
+    refs::{MutRef, Ref},
+    ui_element::{place, Alignment, Orientation},
+    user_control::UserControl,
+    zero,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlexDirection {
+    Row,
+    RowReverse,
+    Column,
+    ColumnReverse,
+}
+
+impl FlexDirection {
+    const fn orientation(self) -> Orientation {
+        match self {
+            Self::Row | Self::RowReverse => Orientation::Horizontal,
+            Self::Column | Self::ColumnReverse => Orientation::Vertical,
+        }
+    }
+
+    const fn is_reverse(self) -> bool {
+        matches!(self, Self::RowReverse | Self::ColumnReverse)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlexWrap {
+    NoWrap,
+    ///Starts a new line when the bases of the children don't fit.
+    Wrap,
+}
+
+///How the space left in a line gets shared between its children.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JustifyContent {
+    Start,
+    End,
+    Center,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlexItem {
+    ///The size along the direction before growing or shrinking.
+    pub basis: f32,
+    ///The size across the direction when the child isn't stretched.</br>
+    ///A wrapped line of stretched children takes the biggest one.
+    pub cross: f32,
+    ///The part of the space left the child takes.
+    pub grow: f32,
+    ///How much the child gives when there is not enough space, scaled by its basis.
+    pub shrink: f32,
+    ///Replaces the align items of the container for this child.
+    pub align_self: Option<Alignment>,
+}
+
+impl FlexItem {
+    pub const fn new(basis: f32) -> Self {
+        Self {
+            basis,
+            cross: 0.,
+            grow: 0.,
+            shrink: 1.,
+            align_self: None,
+        }
+    }
+
+    pub const fn cross(mut self, cross: f32) -> Self {
+        self.cross = cross;
+        self
+    }
+
+    pub const fn grow(mut self, grow: f32) -> Self {
+        self.grow = grow;
+        self
+    }
+
+    pub const fn shrink(mut self, shrink: f32) -> Self {
+        self.shrink = shrink;
+        self
+    }
+
+    pub const fn align_self(mut self, align_self: Alignment) -> Self {
+        self.align_self = Some(align_self);
+        self
+    }
+}
+
+///Lays its children out like a CSS flexbox, the children that aren't stretched take the cross of their item.
+pub struct Flex<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static> {
+    parent: PhantomData<Parent>,
+    statel: PhantomData<State>,
+    surface: FRect,
+    direction: FlexDirection,
+    wrap: FlexWrap,
+    justify: JustifyContent,
+    align_items: Alignment,
+    ///Between the children and between the lines.
+    gap: f32,
+    children: Vec<(Child, FlexItem)>,
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    Flex<Parent, State, Child>
+{
+    pub fn new(direction: FlexDirection, children: Vec<(Child, FlexItem)>) -> Self {
+        Self {
+            parent: PhantomData,
+            statel: PhantomData,
+            surface: zero(),
+            direction,
+            wrap: FlexWrap::NoWrap,
+            justify: JustifyContent::Start,
+            align_items: Alignment::Stretch,
+            gap: 0.,
+            children,
+        }
+    }
+
+    pub const fn surface(&self) -> FRect {
+        self.surface
+    }
+
+    pub const fn wrap(mut self, wrap: FlexWrap) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub const fn justify(mut self, justify: JustifyContent) -> Self {
+        self.justify = justify;
+        self
+    }
+
+    pub const fn align_items(mut self, align_items: Alignment) -> Self {
+        self.align_items = align_items;
+        self
+    }
+
+    pub const fn gap(mut self, gap: f32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    pub fn children(&self) -> &[(Child, FlexItem)] {
+        &self.children
+    }
+
+    pub fn children_mut(&mut self) -> &mut Vec<(Child, FlexItem)> {
+        &mut self.children
+    }
+
+    fn alignment(&self, item: &FlexItem) -> Alignment {
+        item.align_self.unwrap_or(self.align_items)
+    }
+
+    ///The children of each line, a single line when it doesn't wrap.
+    fn lines(&self, space: f32) -> Vec<Range<usize>> {
+        if self.wrap == FlexWrap::NoWrap {
+            return std::iter::once(0..self.children.len()).collect();
+        }
+        let mut lines = Vec::new();
+        let (mut start, mut used) = (0, 0.);
+        for (index, (_, item)) in self.children.iter().enumerate() {
+            if index > start && used + self.gap + item.basis > space {
+                lines.push(start..index);
+                (start, used) = (index, 0.);
+            }
+            used += if index > start { self.gap } else { 0. } + item.basis;
+        }
+        if start < self.children.len() {
+            lines.push(start..self.children.len());
+        }
+        lines
+    }
+
+    ///The size of each child of the line along the direction, with the space left.
+    fn sizes(&self, range: Range<usize>, space: f32) -> (Vec<f32>, f32) {
+        let items: Vec<&FlexItem> = self.children[range].iter().map(|(_, item)| item).collect();
+        let gaps = items.len().saturating_sub(1) as f32 * self.gap;
+        let free = space - gaps - items.iter().map(|item| item.basis).sum::<f32>();
+        let grow: f32 = items.iter().map(|item| item.grow).sum();
+        let shrink: f32 = items.iter().map(|item| item.shrink * item.basis).sum();
+        if free > 0. && grow > 0. {
+            let sizes = items
+                .iter()
+                .map(|item| item.basis + free * item.grow / grow)
+                .collect();
+            (sizes, 0.)
+        } else if free < 0. && shrink > 0. {
+            let sizes = items
+                .iter()
+                .map(|item| (item.basis + free * item.shrink * item.basis / shrink).max(0.))
+                .collect();
+            (sizes, 0.)
+        } else {
+            (items.iter().map(|item| item.basis).collect(), free)
+        }
+    }
+
+    ///Where the first child of the line starts and the space between two children.
+    fn justified(&self, count: usize, left: f32) -> (f32, f32) {
+        let left = left.max(0.);
+        let count = count as f32;
+        match self.justify {
+            JustifyContent::Start => (0., self.gap),
+            JustifyContent::End => (left, self.gap),
+            JustifyContent::Center => (left / 2., self.gap),
+            JustifyContent::SpaceBetween if count > 1. => (0., self.gap + left / (count - 1.)),
+            JustifyContent::SpaceBetween => (0., self.gap),
+            JustifyContent::SpaceAround => (left / count / 2., self.gap + left / count),
+            JustifyContent::SpaceEvenly => (left / (count + 1.), self.gap + left / (count + 1.)),
+        }
+    }
+
+    ///The rect of each child from their items.
+    fn layout(&self) -> Vec<FRect> {
+        let orientation = self.direction.orientation();
+        let (main, space, cross, cross_space) = orientation.split(self.surface);
+        let lines = self.lines(space);
+        let mut rects = Vec::with_capacity(self.children.len());
+        let mut line_cross = cross;
+        for range in lines {
+            //A single line takes the whole container, the wrapped ones the size of their biggest child.
+            let thickness = if self.wrap == FlexWrap::NoWrap {
+                cross_space
+            } else {
+                let items = || self.children[range.clone()].iter().map(|(_, item)| item);
+                let fixed = items()
+                    .filter(|item| self.alignment(item) != Alignment::Stretch)
+                    .map(|item| item.cross)
+                    .reduce(f32::max);
+                fixed.unwrap_or_else(|| items().map(|item| item.cross).fold(0., f32::max))
+            };
+            let (sizes, left) = self.sizes(range.clone(), space);
+            let (mut at, between) = self.justified(sizes.len(), left);
+            for ((_, item), size) in self.children[range].iter().zip(sizes) {
+                let from = if self.direction.is_reverse() {
+                    main + space - at - size
+                } else {
+                    main + at
+                };
+                let (child_cross, child_cross_len) = self
+                    .alignment(item)
+                    .place(line_cross, thickness, item.cross);
+                rects.push(orientation.join(from, size, child_cross, child_cross_len));
+                at += size + between;
+            }
+            line_cross += thickness + self.gap;
+        }
+        rects
+    }
+
+    ///Computes the rects of the children and sends them to those not already there.
+    fn reform(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let rects = this.layout();
+        for ((child, _), rect) in this.children.iter_mut().zip(rects) {
+            place(child.into(), rect, canvas, parent, state)?;
+        }
+        Ok(())
+    }
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    UserControl<Parent, State> for Flex<Parent, State, Child>
+{
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match event {
+            Event::ElementMove { x, y } => {
+                this.surface.set_x(x);
+                this.surface.set_y(y);
+                return Self::reform(this, canvas, parent, state);
+            }
+            Event::ElementResize { width, height } => {
+                this.surface.set_width(width);
+                this.surface.set_height(height);
+                return Self::reform(this, canvas, parent, state);
+            }
+            _ => {}
+        }
+        for (child, _) in this.children.iter_mut() {
+            UserControl::event(child.into(), canvas, event.clone(), parent, state)?;
+        }
+        Ok(())
+    }
+
+    fn update(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        elapsed: Duration,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        for (child, _) in this.children.iter_mut() {
+            UserControl::update(child.into(), canvas, elapsed, parent, state)?;
+        }
+        Self::reform(this, canvas, parent, state)
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        for (child, _) in &this.children {
+            UserControl::draw(child.into(), canvas, parent, state)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod flex_test {
+    use crate::ui_element::stack_panel::stack_panel_test::block;
+
+    use super::*;
+
+    fn rects(
+        flex: Ref<Flex<(), (), crate::ui_element::stack_panel::stack_panel_test::Block>>,
+    ) -> Vec<FRect> {
+        flex.children()
+            .iter()
+            .map(|(child, _)| child.surface)
+            .collect()
+    }
+
+    #[allow(clippy::too_many_lines)]
+    pub(crate) fn test_flex_layout(canvas: &mut Canvas<Window>) {
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        let resize = Event::ElementResize {
+            width: 100.,
+            height: 20.,
+        };
+
+        let mut flex = Flex::new(
+            FlexDirection::Row,
+            vec![
+                (block(0., 0.), FlexItem::new(20.).cross(10.).grow(1.)),
+                (block(0., 0.), FlexItem::new(20.).cross(10.).grow(3.)),
+                (
+                    block(0., 0.),
+                    FlexItem::new(20.).cross(10.).align_self(Alignment::End),
+                ),
+            ],
+        );
+        let grown = MutRef::new(&mut flex);
+        UserControl::event(grown, canvas, resize.clone(), unit, unit).expect("");
+        assert_eq!(
+            rects(grown.into()),
+            [
+                FRect::new(0., 0., 30., 20.),
+                FRect::new(30., 0., 50., 20.),
+                FRect::new(80., 10., 20., 10.),
+            ]
+        );
+
+        let mut flex = Flex::new(
+            FlexDirection::RowReverse,
+            vec![
+                (block(0., 0.), FlexItem::new(20.).cross(10.)),
+                (block(0., 0.), FlexItem::new(20.).cross(10.)),
+                (block(0., 0.), FlexItem::new(20.).cross(10.)),
+            ],
+        )
+        .justify(JustifyContent::SpaceBetween)
+        .align_items(Alignment::Center);
+        let spaced = MutRef::new(&mut flex);
+        UserControl::event(spaced, canvas, resize.clone(), unit, unit).expect("");
+        assert_eq!(
+            rects(spaced.into()),
+            [
+                FRect::new(80., 5., 20., 10.),
+                FRect::new(40., 5., 20., 10.),
+                FRect::new(0., 5., 20., 10.),
+            ]
+        );
+
+        let mut flex = Flex::new(
+            FlexDirection::Row,
+            vec![
+                (block(0., 0.), FlexItem::new(40.).cross(10.)),
+                (block(0., 0.), FlexItem::new(40.).cross(10.)),
+                (block(0., 0.), FlexItem::new(40.).cross(10.).shrink(2.)),
+            ],
+        )
+        .align_items(Alignment::Start);
+        let shrunk = MutRef::new(&mut flex);
+        UserControl::event(shrunk, canvas, resize.clone(), unit, unit).expect("");
+        assert_eq!(
+            rects(shrunk.into()),
+            [
+                FRect::new(0., 0., 35., 10.),
+                FRect::new(35., 0., 35., 10.),
+                FRect::new(70., 0., 30., 10.),
+            ]
+        );
+
+        let mut flex = Flex::new(
+            FlexDirection::Row,
+            vec![
+                (block(0., 0.), FlexItem::new(40.).cross(10.)),
+                (block(0., 0.), FlexItem::new(40.).cross(5.)),
+                (block(0., 0.), FlexItem::new(40.).cross(8.)),
+            ],
+        )
+        .wrap(FlexWrap::Wrap)
+        .align_items(Alignment::Start)
+        .gap(4.);
+        let wrapped = MutRef::new(&mut flex);
+        UserControl::event(wrapped, canvas, resize.clone(), unit, unit).expect("");
+        assert_eq!(
+            rects(wrapped.into()),
+            [
+                FRect::new(0., 0., 40., 10.),
+                FRect::new(44., 0., 40., 5.),
+                FRect::new(0., 14., 40., 8.),
+            ]
+        );
+
+        //The lines of stretched children take their thickness from the items, not from what they got,
+        //so a thinner item makes its line thinner.
+        let mut flex = Flex::new(
+            FlexDirection::Row,
+            vec![
+                (block(0., 0.), FlexItem::new(40.).cross(6.)),
+                (block(0., 0.), FlexItem::new(40.).cross(9.)),
+                (block(0., 0.), FlexItem::new(40.).cross(4.)),
+            ],
+        )
+        .wrap(FlexWrap::Wrap)
+        .gap(4.);
+        let mut stretched = MutRef::new(&mut flex);
+        UserControl::event(stretched, canvas, resize, unit, unit).expect("");
+        assert_eq!(
+            rects(stretched.into()),
+            [
+                FRect::new(0., 0., 40., 9.),
+                FRect::new(44., 0., 40., 9.),
+                FRect::new(0., 13., 40., 4.),
+            ]
+        );
+        stretched.children_mut()[1].1 = FlexItem::new(40.).cross(3.);
+        UserControl::update(stretched, canvas, Duration::ZERO, unit, unit).expect("");
+        assert_eq!(
+            rects(stretched.into()),
+            [
+                FRect::new(0., 0., 40., 6.),
+                FRect::new(44., 0., 40., 6.),
+                FRect::new(0., 10., 40., 4.),
+            ]
+        );
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
     event::Event,
     refs::{MutRef, Ref},
     state_manager::StateManager,
+    ui_element::dispatch,
     user_control::UserControl,
     zero,
 };
@@ -65,6 +66,7 @@ pub struct Grid<Parent: 'static, State: 'static, Child: UserControl<Parent, Stat
     parent: PhantomData<Parent>,
     state: PhantomData<State>,
     elements: HashMap<Pos, Child>,
+    z_indexes: HashMap<Pos, i32>,
     static_x: f32,
     static_y: f32,
     cols: Vec<ColType>,
@@ -87,6 +89,7 @@ impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'stati
             parent: PhantomData,
             state: PhantomData,
             elements,
+            z_indexes: HashMap::new(),
             static_x: 0.,
             static_y: 0.,
             cols,
@@ -100,6 +103,7 @@ impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'stati
         self.cols.clear();
         self.rows.clear();
         self.elements.clear();
+        self.z_indexes.clear();
     }
 
     pub fn rows(&self) -> &[RowType] {
@@ -118,6 +122,22 @@ impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'stati
         self.elements.get_mut(&Pos { x, y })
     }
 
+    pub fn z_index(&self, x: usize, y: usize) -> i32 {
+        self.z_indexes.get(&Pos { x, y }).copied().unwrap_or(0)
+    }
+
+    ///The higher ones are drawn above, 0 by default.
+    pub fn set_z_index(&mut self, x: usize, y: usize, z_index: i32) {
+        self.z_indexes.insert(Pos { x, y }, z_index);
+    }
+
+    ///The positions of the elements from the first drawn to the last, by z index then row by row.
+    pub fn draw_order(&self) -> Vec<Pos> {
+        let mut order: Vec<Pos> = self.elements.keys().copied().collect();
+        order.sort_by_key(|pos| (self.z_index(pos.x, pos.y), pos.y, pos.x));
+        order
+    }
+
     fn reform(
         &'static mut self,
         canvas: &Canvas<Window>,
@@ -240,9 +260,15 @@ impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'stati
                 }
             }
             _ => {
-                for element in this.elements.values_mut() {
-                    UserControl::event(element.into(), canvas, event.clone(), parent, state)?;
-                }
+                let elements = &mut this.as_mut().elements;
+                let order = this.draw_order().into_iter().rev();
+                dispatch(
+                    order.filter_map(|pos| elements.get_mut(&pos).map(MutRef::from)),
+                    canvas,
+                    &event,
+                    parent,
+                    state,
+                )?;
             }
         }
         Ok(())
@@ -266,7 +292,11 @@ impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'stati
             .state_manager
             .apply((parent, cols, rows, elements))?
         {
-            this.as_mut().reform(canvas, parent, state)?;
+            //The z indexes belong to the elements, not to their old positions.
+            let this = this.as_mut();
+            this.z_indexes
+                .retain(|pos, _| this.elements.contains_key(pos));
+            this.reform(canvas, parent, state)?;
         }
         Ok(())
     }
@@ -277,8 +307,10 @@ impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'stati
         parent: Ref<Parent>,
         state: Ref<State>,
     ) -> Result<()> {
-        for element in this.elements.values() {
-            UserControl::draw(element.into(), canvas, parent, state)?;
+        for pos in this.draw_order() {
+            if let Some(element) = this.elements.get(&pos) {
+                UserControl::draw(element.into(), canvas, parent, state)?;
+            }
         }
         Ok(())
     }
@@ -467,6 +499,89 @@ pub(crate) mod grid_test {
         assert_eq!(*counter, 5);
     }
 
+    pub(crate) fn test_grid_draw_order(canvas: &mut Canvas<Window>) {
+        let mut counter = 0_usize;
+        let counter = MutRef::new(&mut counter);
+        let mut grid = simple_grid!(
+            ColType::Px(10.),
+            ColType::Px(10.);
+            RowType::Px(10.),
+            RowType::Px(10.);
+            Pos { x: 0, y: 0 } => Button { surface: zero() },
+            Pos { x: 1, y: 0 } => Button { surface: zero() },
+            Pos { x: 0, y: 1 } => Button { surface: zero() },
+            Pos { x: 1, y: 1 } => Button { surface: zero() },
+        );
+        grid.set_z_index(0, 0, 1);
+        grid.set_z_index(1, 1, -1);
+        assert_eq!(
+            grid.draw_order(),
+            [
+                Pos { x: 1, y: 1 },
+                Pos { x: 1, y: 0 },
+                Pos { x: 0, y: 1 },
+                Pos { x: 0, y: 0 },
+            ]
+        );
+        let mut grid = MutRef::new(&mut grid);
+        let mut parent = ();
+        let parent = MutRef::new(&mut parent);
+        UserControl::event(
+            grid,
+            canvas,
+            Event::ElementResize {
+                width: 20.,
+                height: 20.,
+            },
+            parent,
+            counter,
+        )
+        .expect("");
+        let canvas = &*canvas;
+        let click = move |x, y| {
+            UserControl::event(
+                grid,
+                canvas,
+                Event::MouseButtonDown {
+                    which: 0,
+                    mouse_btn: MouseButton::Left,
+                    clicks: 1,
+                    x,
+                    y,
+                },
+                parent,
+                counter,
+            )
+            .expect("");
+        };
+        click(15., 5.);
+        assert_eq!(*counter, 1);
+
+        //The element at 0,0 now covers the one at 1,0 which misses the click, until it goes above.
+        grid.get_element_mut(0, 0).expect("").surface = FRect::new(0., 0., 20., 10.);
+        click(15., 5.);
+        assert_eq!(*counter, 2);
+        grid.set_z_index(1, 0, 2);
+        click(15., 5.);
+        assert_eq!(*counter, 3);
+
+        //The z index goes away with its element.
+        grid.state_manager.add(Box::new(|(_, _, _, mut elements)| {
+            elements.remove(&Pos { x: 1, y: 0 });
+            Ok(())
+        }));
+        UserControl::update(grid, canvas, Duration::ZERO, parent, counter).expect("");
+        assert_eq!(grid.z_index(1, 0), 0);
+        assert_eq!(grid.z_index(0, 0), 1);
+        grid.state_manager.add(Box::new(|(_, _, _, mut elements)| {
+            elements.insert(Pos { x: 1, y: 0 }, Button { surface: zero() });
+            Ok(())
+        }));
+        UserControl::update(grid, canvas, Duration::ZERO, parent, counter).expect("");
+        assert_eq!(grid.draw_order()[0], Pos { x: 1, y: 1 });
+        assert_eq!(grid.draw_order()[3], Pos { x: 0, y: 0 });
+    }
+
     fn click(
         grid: MutRef<Grid<(), usize, TestGridClickChilds>>,
         parent: MutRef<()>,
@@ -0,0 +1,947 @@
+use std::{marker::PhantomData, time::Duration};
+
+use anyhow::{anyhow, Result};
+use sdl2::{
+    keyboard::Keycode,
+    mouse::MouseButton,
+    rect::{FPoint, FRect},
+    render::Canvas,
+    video::Window,
+};
+
+use crate::{
+    event::{mark_handled, Event},
+    functions::{FnAction, FnColor},
+    missing::rect::clipped,
+    refs::{MutRef, Ref},
+    ui_element::{
+        place,
+        scroll_view::{ScrollBarPlacement, ScrollBarStyle},
+    },
+    user_control::UserControl,
+    zero,
+};
+
+///Creates the row control of an item, reusing the recycled row when one is given.
+pub type FnTemplate<Item, Row> = Box<dyn Fn(Option<Row>, usize, &Item) -> Result<Row>>;
+
+pub type FnMeasure<Item> = Box<dyn Fn(usize, &Item) -> f32>;
+
+pub enum RowHeight<Item: 'static> {
+    Fixed(f32),
+    ///Measures each item once, until the items change.
+    Measured(FnMeasure<Item>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionMode {
+    None,
+    Single,
+    ///Shift extends the selection and ctrl toggles items.
+    Multiple,
+}
+
+///Shows a list of items by only creating the row controls that are visible.
+pub struct ListView<
+    Parent: 'static,
+    State: 'static,
+    Item: 'static,
+    Row: UserControl<Parent, State> + 'static,
+> {
+    parent: PhantomData<Parent>,
+    state: PhantomData<State>,
+    surface: FRect,
+    items: Vec<Item>,
+    template: FnTemplate<Item, Row>,
+    row_height: RowHeight<Item>,
+    ///The top of each row followed by the total height.
+    offsets: Vec<f32>,
+    ///The visible rows sorted by index.
+    rows: Vec<(usize, Row)>,
+    pool: Vec<Row>,
+    dirty: bool,
+    scroll: f32,
+    wheel_step: f32,
+    style: ScrollBarStyle,
+    scroll_color: FnColor<Self, Parent, State>,
+    select_color: FnColor<Self, Parent, State>,
+    selection: SelectionMode,
+    ///Sorted indexes of the selected items.
+    selected: Vec<usize>,
+    cursor: Option<usize>,
+    anchor: Option<usize>,
+    ///It gets called when the user changes the selection.
+    selection_changed: Option<FnAction<Self, Parent, State>>,
+    focused: bool,
+    shift: bool,
+    ctrl: bool,
+    hover: bool,
+    thumb_hover: bool,
+    thumb_selected: bool,
+}
+
+impl<Parent: 'static, State: 'static, Item: 'static, Row: UserControl<Parent, State> + 'static>
+    ListView<Parent, State, Item, Row>
+{
+    pub fn new(
+        items: Vec<Item>,
+        template: FnTemplate<Item, Row>,
+        row_height: RowHeight<Item>,
+        scroll_color: FnColor<Self, Parent, State>,
+        select_color: FnColor<Self, Parent, State>,
+    ) -> Self {
+        Self {
+            parent: PhantomData,
+            state: PhantomData,
+            surface: zero(),
+            items,
+            template,
+            row_height,
+            offsets: Vec::new(),
+            rows: Vec::new(),
+            pool: Vec::new(),
+            dirty: true,
+            scroll: 0.,
+            wheel_step: 40.,
+            style: ScrollBarStyle::default(),
+            scroll_color,
+            select_color,
+            selection: SelectionMode::Single,
+            selected: Vec::new(),
+            cursor: None,
+            anchor: None,
+            selection_changed: None,
+            focused: false,
+            shift: false,
+            ctrl: false,
+            hover: false,
+            thumb_hover: false,
+            thumb_selected: false,
+        }
+    }
+
+    pub const fn selection(mut self, selection: SelectionMode) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    pub const fn style(mut self, style: ScrollBarStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub const fn wheel_step(mut self, wheel_step: f32) -> Self {
+        self.wheel_step = wheel_step;
+        self
+    }
+
+    pub fn selection_changed(mut self, selection_changed: FnAction<Self, Parent, State>) -> Self {
+        self.selection_changed = Some(selection_changed);
+        self
+    }
+
+    pub fn selection_changed_mut(&mut self) -> &mut Option<FnAction<Self, Parent, State>> {
+        &mut self.selection_changed
+    }
+
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    ///Gives access to the items and rebuilds the visible rows on the next update.
+    pub fn items_mut(&mut self) -> &mut Vec<Item> {
+        self.dirty = true;
+        &mut self.items
+    }
+
+    ///Rebuilds the visible rows and the measured heights on the next update.
+    pub fn refresh(&mut self) {
+        self.dirty = true;
+    }
+
+    ///The visible rows with their item index.
+    pub fn rows(&self) -> impl Iterator<Item = (usize, &Row)> {
+        self.rows.iter().map(|(index, row)| (*index, row))
+    }
+
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = (usize, &mut Row)> {
+        self.rows.iter_mut().map(|(index, row)| (*index, row))
+    }
+
+    pub fn selected(&self) -> &[usize] {
+        &self.selected
+    }
+
+    pub fn set_selected(&mut self, mut selected: Vec<usize>) {
+        selected.retain(|index| *index < self.items.len());
+        selected.sort_unstable();
+        selected.dedup();
+        if self.selection == SelectionMode::Single {
+            selected.truncate(1);
+        } else if self.selection == SelectionMode::None {
+            selected.clear();
+        }
+        self.cursor = selected.first().copied();
+        self.anchor = self.cursor;
+        self.selected = selected;
+    }
+
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selected.binary_search(&index).is_ok()
+    }
+
+    ///The item the keyboard navigation starts from.
+    pub const fn cursor(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    pub const fn scroll(&self) -> f32 {
+        self.scroll
+    }
+
+    pub fn set_scroll(&mut self, scroll: f32) {
+        self.scroll = scroll.clamp(0., self.max_scroll());
+    }
+
+    ///Scrolls as little as possible to make the item visible.
+    pub fn scroll_to(&mut self, index: usize) {
+        if self.dirty {
+            self.measure();
+        }
+        if index >= self.items.len() {
+            return;
+        }
+        let (top, bottom) = (self.offsets[index], self.offsets[index + 1]);
+        if top < self.scroll {
+            self.set_scroll(top);
+        } else if bottom > self.scroll + self.surface.height() {
+            self.set_scroll(bottom - self.surface.height());
+        }
+    }
+
+    fn measure(&mut self) {
+        self.offsets.clear();
+        self.offsets.reserve(self.items.len() + 1);
+        let mut top = 0.;
+        self.offsets.push(top);
+        for (index, item) in self.items.iter().enumerate() {
+            top += match &self.row_height {
+                RowHeight::Fixed(height) => *height,
+                RowHeight::Measured(measure) => measure(index, item),
+            };
+            self.offsets.push(top);
+        }
+        //The rows of removed items can't be placed with the new offsets.
+        let len = self.items.len();
+        let at = self.rows.partition_point(|(index, _)| *index < len);
+        let removed = self.rows.split_off(at);
+        self.pool.extend(removed.into_iter().map(|(_, row)| row));
+    }
+
+    fn total_height(&self) -> f32 {
+        self.offsets.last().copied().unwrap_or(0.)
+    }
+
+    fn max_scroll(&self) -> f32 {
+        (self.total_height() - self.surface.height()).max(0.)
+    }
+
+    fn bar(&self) -> bool {
+        self.total_height() > self.surface.height()
+    }
+
+    ///The part of the surface where the rows are visible.
+    fn view(&self) -> FRect {
+        if self.style.placement == ScrollBarPlacement::Gutter && self.bar() {
+            FRect::new(
+                self.surface.x(),
+                self.surface.y(),
+                self.surface.width() - self.style.thickness,
+                self.surface.height(),
+            )
+        } else {
+            self.surface
+        }
+    }
+
+    fn track(&self) -> FRect {
+        FRect::new(
+            self.surface.x() + self.surface.width() - self.style.thickness,
+            self.surface.y(),
+            self.style.thickness,
+            self.surface.height(),
+        )
+    }
+
+    fn thumb(&self) -> FRect {
+        let track = self.track();
+        let h = (track.height() * track.height() / self.total_height())
+            .max(self.style.min_thumb_size)
+            .min(track.height());
+        let max_scroll = self.max_scroll();
+        let ratio = if max_scroll > 0. {
+            self.scroll / max_scroll
+        } else {
+            0.
+        };
+        FRect::new(
+            track.x(),
+            track.y() + ratio * (track.height() - h),
+            track.width(),
+            h,
+        )
+    }
+
+    const fn bar_visible(&self) -> bool {
+        !self.style.auto_hide || self.hover || self.thumb_selected
+    }
+
+    ///The index of the item at the window position y.
+    pub fn index_at(&self, y: f32) -> Option<usize> {
+        let y = y - self.surface.y() + self.scroll;
+        if y < 0. || y >= self.total_height() {
+            return None;
+        }
+        Some(self.offsets.partition_point(|top| *top <= y) - 1)
+            .filter(|index| *index < self.items.len())
+    }
+
+    ///Creates, recycles and positions the rows so only the visible ones exist.
+    fn layout(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        if this.dirty {
+            this.measure();
+            let rows: Vec<(usize, Row)> = this.rows.drain(..).collect();
+            this.pool.extend(rows.into_iter().map(|(_, row)| row));
+            let len = this.items.len();
+            this.selected.retain(|index| *index < len);
+            this.cursor = this.cursor.filter(|index| *index < len);
+            this.anchor = this.anchor.filter(|index| *index < len);
+            this.dirty = false;
+        }
+        this.scroll = this.scroll.clamp(0., this.max_scroll());
+        let view = this.view();
+        let len = this.items.len();
+        let first = (this.offsets.partition_point(|top| *top <= this.scroll) - 1).min(len);
+        let last = this
+            .offsets
+            .partition_point(|top| *top < this.scroll + view.height())
+            .min(len);
+
+        let rows: Vec<(usize, Row)> = this.rows.drain(..).collect();
+        for (index, row) in rows {
+            if first <= index && index < last {
+                this.rows.push((index, row));
+            } else {
+                this.pool.push(row);
+            }
+        }
+        for index in first..last {
+            if let Err(at) = this.rows.binary_search_by_key(&index, |(index, _)| *index) {
+                let recycled = this.pool.pop();
+                let row = (this.template)(recycled, index, &this.items[index])?;
+                this.rows.insert(at, (index, row));
+            }
+        }
+
+        for i in 0..this.rows.len() {
+            let index = this.rows[i].0;
+            let target = FRect::new(
+                view.x(),
+                view.y() + this.offsets[index] - this.scroll,
+                view.width(),
+                this.offsets[index + 1] - this.offsets[index],
+            );
+            place(
+                MutRef::new(&mut this.rows[i].1),
+                target,
+                canvas,
+                parent,
+                state,
+            )?;
+        }
+        Ok(())
+    }
+
+    ///Moves the cursor to the item and updates the selection, returns if the selection changed.
+    fn select_index(&mut self, index: usize, extend: bool, toggle: bool) -> bool {
+        self.cursor = Some(index);
+        let previous = self.selected.clone();
+        match self.selection {
+            SelectionMode::None => {}
+            SelectionMode::Single => {
+                self.selected = vec![index];
+                self.anchor = Some(index);
+            }
+            SelectionMode::Multiple => match (extend, toggle, self.anchor) {
+                (true, _, Some(anchor)) => {
+                    self.selected = (anchor.min(index)..=anchor.max(index)).collect();
+                }
+                (_, true, _) => {
+                    match self.selected.binary_search(&index) {
+                        Ok(at) => {
+                            self.selected.remove(at);
+                        }
+                        Err(at) => self.selected.insert(at, index),
+                    }
+                    self.anchor = Some(index);
+                }
+                _ => {
+                    self.selected = vec![index];
+                    self.anchor = Some(index);
+                }
+            },
+        }
+        previous != self.selected
+    }
+
+    ///Navigates with the arrows, page up/down, home, end and space, returns if it was used.
+    fn key_select(&mut self, keycode: Keycode) -> Option<bool> {
+        if self.dirty {
+            self.measure();
+        }
+        let len = self.items.len();
+        if len == 0 {
+            return None;
+        }
+        let cursor = self.cursor.unwrap_or(0).min(len - 1);
+        let page = self.surface.height();
+        let index = match keycode {
+            Keycode::Up => cursor.saturating_sub(1),
+            Keycode::Down => (cursor + 1).min(len - 1),
+            Keycode::Home => 0,
+            Keycode::End => len - 1,
+            Keycode::PageUp => self
+                .offsets
+                .partition_point(|top| *top <= self.offsets[cursor] - page)
+                .saturating_sub(1),
+            Keycode::PageDown => (self
+                .offsets
+                .partition_point(|top| *top <= self.offsets[cursor] + page)
+                - 1)
+            .min(len - 1),
+            Keycode::Space if self.selection == SelectionMode::Multiple => {
+                return Some(self.select_index(cursor, false, true));
+            }
+            _ => return None,
+        };
+        self.scroll_to(index);
+        if self.ctrl && self.selection == SelectionMode::Multiple {
+            self.cursor = Some(index);
+            return Some(false);
+        }
+        Some(self.select_index(index, self.shift, false))
+    }
+
+    fn pointer(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: &Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<bool> {
+        let bar = this.bar();
+        match *event {
+            Event::MouseMotion {
+                mousestate, x, y, ..
+            } => {
+                let point = FPoint::new(x, y);
+                this.hover = this.surface.contains_point(point);
+                this.thumb_hover = bar && this.thumb().contains_point(point);
+                if mousestate.left() && this.thumb_selected {
+                    let track = this.track();
+                    let thumb = this.thumb();
+                    let ratio = ((y - track.y() - thumb.height() / 2.)
+                        / (track.height() - thumb.height()))
+                    .clamp(0., 1.);
+                    let max_scroll = this.max_scroll();
+                    this.set_scroll(ratio * max_scroll);
+                    return Ok(true);
+                }
+            }
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => {
+                let point = FPoint::new(x, y);
+                this.focused = this.surface.contains_point(point);
+                this.thumb_selected =
+                    bar && this.bar_visible() && this.thumb().contains_point(point);
+                if this.thumb_selected {
+                    return Ok(true);
+                }
+                if this.view().contains_point(point) {
+                    if let Some(index) = this.index_at(y) {
+                        let (shift, ctrl) = (this.shift, this.ctrl);
+                        if this.select_index(index, shift, ctrl) {
+                            let t = this;
+                            if let Some(selection_changed) = this.selection_changed.as_mut() {
+                                selection_changed(t, parent, state, canvas)?;
+                            }
+                        }
+                    }
+                }
+            }
+            Event::MouseButtonDown { x, y, .. } => {
+                this.focused = this.surface.contains_point(FPoint::new(x, y));
+            }
+            Event::MouseButtonUp { .. } => this.thumb_selected = false,
+            Event::MouseWheel {
+                scroll_y,
+                mouse_x,
+                mouse_y,
+                ..
+            } if this.surface.contains_point(FPoint::new(mouse_x, mouse_y)) => {
+                let scroll = this.scroll - scroll_y * this.wheel_step;
+                this.set_scroll(scroll);
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    fn keyboard(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: &Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match *event {
+            Event::KeyDown {
+                keycode: Some(Keycode::LShift | Keycode::RShift),
+                ..
+            } => this.shift = true,
+            Event::KeyUp {
+                keycode: Some(Keycode::LShift | Keycode::RShift),
+                ..
+            } => this.shift = false,
+            Event::KeyDown {
+                keycode: Some(Keycode::LCtrl | Keycode::RCtrl),
+                ..
+            } => this.ctrl = true,
+            Event::KeyUp {
+                keycode: Some(Keycode::LCtrl | Keycode::RCtrl),
+                ..
+            } => this.ctrl = false,
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } if this.focused => {
+                if let Some(changed) = this.key_select(keycode) {
+                    mark_handled();
+                    if changed {
+                        let t = this;
+                        if let Some(selection_changed) = this.selection_changed.as_mut() {
+                            selection_changed(t, parent, state, canvas)?;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    ///Keeps positions outside of the view outside of the rows so hidden parts can't be hovered.
+    fn outside(&self, event: Event) -> Event {
+        let view = self.view();
+        let y = match event {
+            Event::MouseMotion { y, .. }
+            | Event::MouseButtonDown { y, .. }
+            | Event::MouseButtonUp { y, .. }
+            | Event::MouseWheel { mouse_y: y, .. } => y,
+            event => return event,
+        };
+        let (top, bottom) = match (self.rows.first(), self.rows.last()) {
+            (Some((first, _)), Some((last, _))) => (
+                view.y() + self.offsets[*first] - self.scroll,
+                view.y() + self.offsets[*last + 1] - self.scroll,
+            ),
+            _ => return event,
+        };
+        if y < view.y() {
+            event.translate(0., top - view.y())
+        } else if y > view.y() + view.height() {
+            event.translate(0., bottom - view.y() - view.height())
+        } else {
+            event
+        }
+    }
+}
+
+impl<Parent: 'static, State: 'static, Item: 'static, Row: UserControl<Parent, State> + 'static>
+    UserControl<Parent, State> for ListView<Parent, State, Item, Row>
+{
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match event {
+            Event::ElementMove { x, y } => {
+                this.surface.set_x(x);
+                this.surface.set_y(y);
+                return Self::layout(this, canvas, parent, state);
+            }
+            Event::ElementResize { width, height } => {
+                this.surface.set_width(width);
+                this.surface.set_height(height);
+                return Self::layout(this, canvas, parent, state);
+            }
+            _ => {}
+        }
+        if Self::pointer(this, canvas, &event, parent, state)? {
+            return Ok(());
+        }
+        Self::keyboard(this, canvas, &event, parent, state)?;
+        let event = this.outside(event);
+        for (_, row) in this.rows.iter_mut() {
+            UserControl::event(row.into(), canvas, event.clone(), parent, state)?;
+        }
+        Ok(())
+    }
+
+    fn update(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        elapsed: Duration,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        Self::layout(this, canvas, parent, state)?;
+        for (_, row) in this.rows.iter_mut() {
+            UserControl::update(row.into(), canvas, elapsed, parent, state)?;
+        }
+        Ok(())
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        clipped(canvas, this.view(), |canvas| {
+            let select_color = (this.select_color)(this, parent, state);
+            for (index, row) in this.rows.iter() {
+                if this.is_selected(*index) {
+                    canvas.set_draw_color(select_color);
+                    canvas
+                        .fill_frect(UserControl::surface(row.into(), parent, state))
+                        .map_err(|e| anyhow!(e))?;
+                }
+                UserControl::draw(row.into(), canvas, parent, state)?;
+            }
+            Ok(())
+        })?;
+        if !this.bar() || !this.bar_visible() {
+            return Ok(());
+        }
+        if let Some(track_color) = this.style.track_color {
+            canvas.set_draw_color(track_color);
+            canvas.fill_frect(this.track()).map_err(|e| anyhow!(e))?;
+        }
+        let color = (this.scroll_color)(this, parent, state);
+        canvas.set_draw_color(if this.thumb_selected {
+            this.style.thumb_pressed_color.unwrap_or(color)
+        } else if this.thumb_hover {
+            this.style.thumb_hover_color.unwrap_or(color)
+        } else {
+            color
+        });
+        canvas.fill_frect(this.thumb()).map_err(|e| anyhow!(e))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod list_view_test {
+    use std::{cell::Cell, rc::Rc};
+
+    use sdl2::{
+        keyboard::Mod,
+        mouse::{MouseButton, MouseState},
+        pixels::Color,
+    };
+
+    use super::*;
+
+    struct Label {
+        surface: FRect,
+        item: usize,
+    }
+
+    impl UserControl<(), usize> for Label {
+        fn surface(this: Ref<Self>, _: Ref<()>, _: Ref<usize>) -> FRect {
+            this.surface
+        }
+
+        fn event(
+            mut this: MutRef<Self>,
+            _: &Canvas<Window>,
+            event: Event,
+            _: MutRef<()>,
+            mut clicked: MutRef<usize>,
+        ) -> Result<()> {
+            match event {
+                Event::ElementMove { x, y } => {
+                    this.surface.set_x(x);
+                    this.surface.set_y(y);
+                }
+                Event::ElementResize { width, height } => {
+                    this.surface.set_width(width);
+                    this.surface.set_height(height);
+                }
+                Event::MouseButtonDown { .. } if event.hover(this.surface) => {
+                    *clicked = this.item;
+                }
+                _ => {}
+            }
+            Ok(())
+        }
+
+        fn update(
+            _: MutRef<Self>,
+            _: &Canvas<Window>,
+            _: Duration,
+            _: MutRef<()>,
+            _: MutRef<usize>,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        fn draw(_: Ref<Self>, _: &mut Canvas<Window>, _: Ref<()>, _: Ref<usize>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    pub(crate) fn test_list_view_virtualization(canvas: &mut Canvas<Window>) {
+        let mut clicked = usize::MAX;
+        let clicked = MutRef::new(&mut clicked);
+        let mut list = ListView::new(
+            (0..1000).collect(),
+            Box::new(|row: Option<Label>, index, _: &usize| {
+                Ok(match row {
+                    Some(row) => Label {
+                        surface: row.surface,
+                        item: index,
+                    },
+                    None => Label {
+                        surface: zero(),
+                        item: index,
+                    },
+                })
+            }),
+            RowHeight::Fixed(10.),
+            Box::new(|_, _, _| Color::WHITE),
+            Box::new(|_, _, _| Color::BLUE),
+        );
+        let mut list = MutRef::new(&mut list);
+        let mut parent = ();
+        let parent = MutRef::new(&mut parent);
+        let layout = [
+            Event::ElementMove { x: 0., y: 0. },
+            Event::ElementResize {
+                width: 50.,
+                height: 45.,
+            },
+        ];
+        for event in layout {
+            UserControl::event(list, canvas, event, parent, clicked).expect("");
+        }
+        assert_eq!(list.rows().count(), 5);
+
+        click(list, parent, clicked, canvas, 5., 25.);
+        assert_eq!(*clicked, 2);
+        assert_eq!(list.selected(), &[2]);
+
+        list.scroll_to(500);
+        UserControl::update(list, canvas, Duration::ZERO, parent, clicked).expect("");
+        assert_eq!(list.rows().count(), 5);
+        assert!(list.rows().any(|(index, _)| index == 500));
+        assert!(list.rows().all(|(index, _)| (496..=500).contains(&index)));
+
+        click(list, parent, clicked, canvas, 5., 40.);
+        assert_eq!(*clicked, 500);
+        assert_eq!(list.selected(), &[500]);
+    }
+
+    fn click(
+        list: MutRef<ListView<(), usize, usize, Label>>,
+        parent: MutRef<()>,
+        state: MutRef<usize>,
+        canvas: &Canvas<Window>,
+        x: f32,
+        y: f32,
+    ) {
+        assert!(UserControl::event(
+            list,
+            canvas,
+            Event::MouseButtonDown {
+                which: 0,
+                mouse_btn: MouseButton::Left,
+                clicks: 1,
+                x,
+                y,
+            },
+            parent,
+            state
+        )
+        .is_ok());
+    }
+
+    const fn key(keycode: Keycode, down: bool) -> Event {
+        if down {
+            Event::KeyDown {
+                keycode: Some(keycode),
+                scancode: None,
+                keymod: Mod::NOMOD,
+                repeat: false,
+            }
+        } else {
+            Event::KeyUp {
+                keycode: Some(keycode),
+                scancode: None,
+                keymod: Mod::NOMOD,
+                repeat: false,
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    pub(crate) fn test_list_view_selection(canvas: &mut Canvas<Window>) {
+        let mut clicked = usize::MAX;
+        let clicked = MutRef::new(&mut clicked);
+        let created = Rc::new(Cell::new(0));
+        let counter = created.clone();
+        let mut list = ListView::new(
+            (0..20).collect(),
+            Box::new(move |row: Option<Label>, index, _: &usize| {
+                let surface = row.map_or_else(
+                    || {
+                        counter.set(counter.get() + 1);
+                        zero()
+                    },
+                    |row| row.surface,
+                );
+                Ok(Label {
+                    surface,
+                    item: index,
+                })
+            }),
+            RowHeight::Measured(Box::new(|index, _| if index % 2 == 0 { 10. } else { 20. })),
+            Box::new(|_, _, _| Color::WHITE),
+            Box::new(|_, _, _| Color::BLUE),
+        )
+        .selection(SelectionMode::Multiple);
+        let mut list = MutRef::new(&mut list);
+        let mut parent = ();
+        let parent = MutRef::new(&mut parent);
+        let canvas = &*canvas;
+        let send = move |event| UserControl::event(list, canvas, event, parent, clicked).expect("");
+        let update = move || {
+            UserControl::update(list, canvas, Duration::ZERO, parent, clicked).expect("");
+        };
+        let indexes = move || list.rows().map(|(index, _)| index).collect::<Vec<_>>();
+        send(Event::ElementResize {
+            width: 50.,
+            height: 45.,
+        });
+
+        //The even items are 10px high and the odd ones 20px.
+        assert_eq!(indexes(), [0, 1, 2, 3]);
+        assert_eq!(list.index_at(15.), Some(1));
+        assert_eq!(list.index_at(35.), Some(2));
+        assert_eq!(created.get(), 4);
+
+        //The rows scrolled out are reused for the rows scrolled in.
+        list.set_scroll(60.);
+        update();
+        assert_eq!(indexes(), [4, 5, 6, 7]);
+        assert!(list.rows().all(|(index, row)| row.item == index));
+        assert_eq!(created.get(), 4);
+
+        //Shift extends the selection from the anchor, ctrl moves the cursor and space toggles.
+        send(click_at(5.));
+        assert_eq!(*clicked, 4);
+        assert_eq!(list.selected(), [4]);
+        for (keycode, down, selected, cursor) in [
+            (Keycode::Down, true, [5].as_slice(), 5),
+            (Keycode::LShift, true, &[5], 5),
+            (Keycode::Down, true, &[5, 6], 6),
+            (Keycode::Down, true, &[5, 6, 7], 7),
+            (Keycode::LShift, false, &[5, 6, 7], 7),
+            (Keycode::LCtrl, true, &[5, 6, 7], 7),
+            (Keycode::Down, true, &[5, 6, 7], 8),
+            (Keycode::Space, true, &[5, 6, 7, 8], 8),
+            (Keycode::Up, true, &[5, 6, 7, 8], 7),
+            (Keycode::Space, true, &[5, 6, 8], 7),
+        ] {
+            send(key(keycode, down));
+            assert_eq!(list.selected(), selected);
+            assert_eq!(list.cursor(), Some(cursor));
+        }
+
+        //The same goes for the clicks.
+        list.set_scroll(0.);
+        update();
+        send(click_at(35.));
+        assert_eq!(list.selected(), [2, 5, 6, 8]);
+        send(key(Keycode::LCtrl, false));
+        send(key(Keycode::LShift, true));
+        send(click_at(5.));
+        assert_eq!(list.selected(), [0, 1, 2]);
+        send(key(Keycode::LShift, false));
+        send(click_at(15.));
+        assert_eq!(list.selected(), [1]);
+
+        //The pages follow the measured heights.
+        for (keycode, selected) in [
+            (Keycode::End, 19),
+            (Keycode::PageUp, 15),
+            (Keycode::PageDown, 17),
+        ] {
+            send(key(keycode, true));
+            assert_eq!(list.selected(), [selected]);
+        }
+        assert!((list.scroll() - 225.).abs() < 0.01);
+
+        //Removing items keeps the cursor and the rows on the remaining ones.
+        list.items_mut().truncate(3);
+        send(key(Keycode::Up, true));
+        assert_eq!(list.selected(), [1]);
+        send(Event::MouseMotion {
+            which: 0,
+            mousestate: MouseState::from_sdl_state(0),
+            x: 5.,
+            y: 60.,
+            moved_x: 0.,
+            moved_y: 0.,
+        });
+        update();
+        assert_eq!(indexes(), [0, 1, 2]);
+        assert_eq!(created.get(), 4);
+    }
+
+    const fn click_at(y: f32) -> Event {
+        Event::MouseButtonDown {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 5.,
+            y,
+        }
+    }
+}
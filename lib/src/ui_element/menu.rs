@@ -0,0 +1,1166 @@
+use std::{cell::RefCell, marker::PhantomData, rc::Rc, time::Duration};
+
+use anyhow::{anyhow, Result};
+use sdl2::{
+    gfx::primitives::DrawRenderer,
+    keyboard::{Keycode, Mod},
+    mouse::MouseButton,
+    pixels::Color,
+    rect::{FPoint, FRect},
+    render::Canvas,
+    ttf::Font,
+    video::Window,
+};
+
+use crate::{
+    event::Event,
+    functions::FnAction,
+    missing::ui_string::{string_size, UIString},
+    overlay::{self, Layer, LayerId},
+    refs::{MutRef, Ref},
+    user_control::UserControl,
+    zero,
+};
+
+///Room on the left of the items for their check mark.
+const CHECK: f32 = 20.;
+///Room on the right of the items for their submenu arrow.
+const ARROW: f32 = 16.;
+///Space between the text of an item and its accelerator.
+const GAP: f32 = 24.;
+const PADDING: f32 = 6.;
+const SEPARATOR: f32 = 7.;
+const MIN_WIDTH: f32 = 100.;
+
+#[derive(Debug, Clone, Copy)]
+pub struct MenuStyle {
+    pub back_color: Color,
+    pub front_color: Color,
+    pub highlight_color: Color,
+    pub disabled_color: Color,
+    pub row_height: f32,
+}
+
+impl Default for MenuStyle {
+    fn default() -> Self {
+        Self {
+            back_color: Color::RGB(240, 240, 240),
+            front_color: Color::BLACK,
+            highlight_color: Color::RGB(180, 210, 240),
+            disabled_color: Color::RGB(150, 150, 150),
+            row_height: 24.,
+        }
+    }
+}
+
+enum Kind<Parent: 'static, State: 'static> {
+    Action(FnAction<MenuItem<Parent, State>, Parent, State>),
+    Submenu(Vec<MenuItem<Parent, State>>),
+    Separator,
+}
+
+pub struct MenuItem<Parent: 'static, State: 'static> {
+    text: String,
+    kind: Kind<Parent, State>,
+    accelerator: Option<(Keycode, Mod)>,
+    ///None when the item can't be checked.
+    checked: Option<bool>,
+    enabled: bool,
+}
+
+impl<Parent: 'static, State: 'static> MenuItem<Parent, State> {
+    pub fn action(text: &str, action: FnAction<Self, Parent, State>) -> Self {
+        Self {
+            text: text.to_owned(),
+            kind: Kind::Action(action),
+            accelerator: None,
+            checked: None,
+            enabled: true,
+        }
+    }
+
+    pub fn submenu(text: &str, items: Vec<Self>) -> Self {
+        Self {
+            text: text.to_owned(),
+            kind: Kind::Submenu(items),
+            accelerator: None,
+            checked: None,
+            enabled: true,
+        }
+    }
+
+    pub const fn separator() -> Self {
+        Self {
+            text: String::new(),
+            kind: Kind::Separator,
+            accelerator: None,
+            checked: None,
+            enabled: false,
+        }
+    }
+
+    ///The keys are shown next to the item and trigger it while its menu is closed.
+    pub const fn accelerator(mut self, keycode: Keycode, keymod: Mod) -> Self {
+        self.accelerator = Some((keycode, keymod));
+        self
+    }
+
+    ///Clicking the item toggles its check before calling its action.
+    pub const fn checkable(mut self, checked: bool) -> Self {
+        self.checked = Some(checked);
+        self
+    }
+
+    pub const fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub const fn text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    pub const fn is_checked(&self) -> Option<bool> {
+        self.checked
+    }
+
+    pub fn set_checked(&mut self, checked: bool) {
+        if self.checked.is_some() {
+            self.checked = Some(checked);
+        }
+    }
+
+    pub const fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    ///Empty when the item isn't a submenu.
+    pub fn items(&self) -> &[Self] {
+        match &self.kind {
+            Kind::Submenu(items) => items,
+            _ => &[],
+        }
+    }
+
+    ///None when the item isn't a submenu.
+    pub fn items_mut(&mut self) -> Option<&mut Vec<Self>> {
+        match &mut self.kind {
+            Kind::Submenu(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    fn items_slice_mut(&mut self) -> &mut [Self] {
+        match &mut self.kind {
+            Kind::Submenu(items) => items,
+            _ => &mut [],
+        }
+    }
+
+    const fn is_submenu(&self) -> bool {
+        matches!(self.kind, Kind::Submenu(_))
+    }
+
+    const fn selectable(&self) -> bool {
+        self.enabled && !matches!(self.kind, Kind::Separator)
+    }
+
+    const fn height(&self, style: &MenuStyle) -> f32 {
+        match self.kind {
+            Kind::Separator => SEPARATOR,
+            _ => style.row_height,
+        }
+    }
+
+    fn accelerator_text(&self) -> Option<String> {
+        let (keycode, keymod) = self.accelerator?;
+        let mut text = String::new();
+        for (mods, name) in [
+            (Mod::LCTRLMOD | Mod::RCTRLMOD, "Ctrl+"),
+            (Mod::LSHIFTMOD | Mod::RSHIFTMOD, "Shift+"),
+            (Mod::LALTMOD | Mod::RALTMOD, "Alt+"),
+        ] {
+            if keymod.intersects(mods) {
+                text.push_str(name);
+            }
+        }
+        text.push_str(&keycode.name());
+        Some(text)
+    }
+
+    ///Toggles the check and calls the action.
+    fn activate(
+        mut this: MutRef<Self>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+        canvas: &Canvas<Window>,
+    ) -> Result<()> {
+        if let Some(checked) = this.checked {
+            this.checked = Some(!checked);
+        }
+        let t = this;
+        if let Kind::Action(action) = &mut this.kind {
+            action(t, parent, state, canvas)?;
+        }
+        Ok(())
+    }
+}
+
+///Only compares the sides of the modifiers that matter: Ctrl, Shift and Alt.
+fn same_mods(a: Mod, b: Mod) -> bool {
+    [
+        Mod::LCTRLMOD | Mod::RCTRLMOD,
+        Mod::LSHIFTMOD | Mod::RSHIFTMOD,
+        Mod::LALTMOD | Mod::RALTMOD,
+    ]
+    .into_iter()
+    .all(|mods| a.intersects(mods) == b.intersects(mods))
+}
+
+fn find_accelerator<Parent: 'static, State: 'static>(
+    items: &mut [MenuItem<Parent, State>],
+    keycode: Keycode,
+    keymod: Mod,
+) -> Option<&mut MenuItem<Parent, State>> {
+    for item in items {
+        if !item.enabled {
+            continue;
+        }
+        if item.is_submenu() {
+            if let Some(found) = find_accelerator(item.items_slice_mut(), keycode, keymod) {
+                return Some(found);
+            }
+        } else if item
+            .accelerator
+            .is_some_and(|(key, mods)| key == keycode && same_mods(mods, keymod))
+        {
+            return Some(item);
+        }
+    }
+    None
+}
+
+///Triggers the item with these keys as accelerator, returns if there was one.
+fn accelerate<Parent: 'static, State: 'static>(
+    items: &mut [MenuItem<Parent, State>],
+    event: &Event,
+    parent: MutRef<Parent>,
+    state: MutRef<State>,
+    canvas: &Canvas<Window>,
+) -> Result<bool> {
+    if let Event::KeyDown {
+        keycode: Some(keycode),
+        keymod,
+        ..
+    } = *event
+    {
+        if let Some(item) = find_accelerator(items, keycode, keymod) {
+            MenuItem::activate(item.into(), parent, state, canvas)?;
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn items_at<'a, Parent: 'static, State: 'static>(
+    mut items: &'a [MenuItem<Parent, State>],
+    levels: &[Level],
+    depth: usize,
+) -> &'a [MenuItem<Parent, State>] {
+    for level in &levels[..depth] {
+        items = level.highlighted.map_or(&[], |index| items[index].items());
+    }
+    items
+}
+
+fn items_at_mut<'a, Parent: 'static, State: 'static>(
+    mut items: &'a mut [MenuItem<Parent, State>],
+    levels: &[Level],
+    depth: usize,
+) -> &'a mut [MenuItem<Parent, State>] {
+    for level in &levels[..depth] {
+        items = match level.highlighted {
+            Some(index) => items[index].items_slice_mut(),
+            None => &mut [],
+        };
+    }
+    items
+}
+
+///The top of each item relative to its menu.
+fn row_top<Parent: 'static, State: 'static>(
+    items: &[MenuItem<Parent, State>],
+    style: &MenuStyle,
+    index: usize,
+) -> f32 {
+    items[..index].iter().map(|item| item.height(style)).sum()
+}
+
+fn row_at<Parent: 'static, State: 'static>(
+    items: &[MenuItem<Parent, State>],
+    style: &MenuStyle,
+    y: f32,
+) -> Option<usize> {
+    let mut top = 0.;
+    for (index, item) in items.iter().enumerate() {
+        top += item.height(style);
+        if y < top {
+            return Some(index);
+        }
+    }
+    None
+}
+
+struct Level {
+    surface: FRect,
+    highlighted: Option<usize>,
+}
+
+///Whether the open menus with these surfaces use the event, so it doesn't reach the tree.
+fn uses(levels: &[FRect], event: &Event) -> bool {
+    let over = |x, y| {
+        levels
+            .iter()
+            .any(|level| level.contains_point(FPoint::new(x, y)))
+    };
+    match *event {
+        Event::MouseMotion { x, y, .. }
+        | Event::MouseButtonUp { x, y, .. }
+        | Event::MouseWheel {
+            mouse_x: x,
+            mouse_y: y,
+            ..
+        } => over(x, y),
+        //A click outside only closes the menus.
+        Event::MouseButtonDown { .. }
+        | Event::KeyDown { .. }
+        | Event::KeyUp { .. }
+        | Event::TextInput { .. } => true,
+        _ => false,
+    }
+}
+
+///What the overlay layer of the open menus shares with the menu bar or the context menu.</br>
+///The layer only queues the events, the menus handle them once their element gets an event or an update
+///since it can move in memory while they are open.
+#[derive(Default)]
+struct Inbox {
+    levels: RefCell<Vec<FRect>>,
+    events: RefCell<Vec<Event>>,
+}
+
+///The open menus, each one being the submenu of the highlighted item of the one before.
+struct MenuPopup {
+    font: &'static Font<'static, 'static>,
+    style: MenuStyle,
+    levels: Vec<Level>,
+    ///The overlay layer while the menus are open.
+    layer: Option<LayerId>,
+    inbox: Rc<Inbox>,
+}
+
+impl Drop for MenuPopup {
+    fn drop(&mut self) {
+        if let Some(layer) = self.layer {
+            overlay::remove(layer);
+        }
+    }
+}
+
+impl MenuPopup {
+    fn new(font: &'static Font<'static, 'static>) -> Self {
+        Self {
+            font,
+            style: MenuStyle::default(),
+            levels: Vec::new(),
+            layer: None,
+            inbox: Rc::default(),
+        }
+    }
+
+    const fn is_open(&self) -> bool {
+        !self.levels.is_empty()
+    }
+
+    fn close(&mut self) {
+        self.levels.clear();
+    }
+
+    ///The events the overlay layer used since the last call.
+    fn take_events(&self) -> Vec<Event> {
+        self.inbox.events.take()
+    }
+
+    ///Opens or closes the overlay layer to match if the menus are open, returns if they are.
+    fn sync_layer(&mut self) -> bool {
+        *self.inbox.levels.borrow_mut() = self.levels.iter().map(|level| level.surface).collect();
+        match (self.is_open(), self.layer) {
+            (true, None) => {
+                let inbox = self.inbox.clone();
+                //The menus are drawn by their element, see draw.
+                let layer = Layer::new(Box::new(zero), Box::new(|_, _| Ok(()))).event(Box::new(
+                    move |_, event, _| {
+                        let used = uses(&inbox.levels.borrow(), event);
+                        if used {
+                            inbox.events.borrow_mut().push(event.clone());
+                        }
+                        Ok(used)
+                    },
+                ));
+                self.layer = Some(overlay::push(layer));
+            }
+            (false, Some(layer)) => {
+                overlay::remove(layer);
+                self.layer = None;
+            }
+            _ => {}
+        }
+        self.is_open()
+    }
+
+    fn text_width(&self, text: &str) -> Result<f32> {
+        Ok(string_size(self.font, text)?.map_or(0., |(width, _)| width))
+    }
+
+    fn measure<Parent: 'static, State: 'static>(
+        &self,
+        items: &[MenuItem<Parent, State>],
+    ) -> Result<(f32, f32)> {
+        let mut width = MIN_WIDTH;
+        for item in items {
+            let accelerator = match item.accelerator_text() {
+                Some(text) => GAP + self.text_width(&text)?,
+                None => 0.,
+            };
+            width = width.max(CHECK + self.text_width(&item.text)? + accelerator + ARROW + PADDING);
+        }
+        Ok((width, row_top(items, &self.style, items.len())))
+    }
+
+    ///Opens a menu at depth, on the left of back_x when there is no room on the right of x.
+    fn open<Parent: 'static, State: 'static>(
+        &mut self,
+        items: &[MenuItem<Parent, State>],
+        canvas: &Canvas<Window>,
+        depth: usize,
+        (x, y): (f32, f32),
+        back_x: f32,
+    ) -> Result<()> {
+        let (window_width, window_height) = canvas.output_size().map_err(|e| anyhow!(e))?;
+        let (window_width, window_height) = (window_width as f32, window_height as f32);
+        let (width, height) = self.measure(items)?;
+        let x = if x + width > window_width {
+            back_x - width
+        } else {
+            x
+        };
+        let x = x.min(window_width - width).max(0.);
+        let y = y.min(window_height - height).max(0.);
+        self.levels.truncate(depth);
+        self.levels.push(Level {
+            surface: FRect::new(x, y, width, height),
+            highlighted: None,
+        });
+        Ok(())
+    }
+
+    fn open_submenu<Parent: 'static, State: 'static>(
+        &mut self,
+        root: &[MenuItem<Parent, State>],
+        canvas: &Canvas<Window>,
+        depth: usize,
+    ) -> Result<()> {
+        let items = items_at(root, &self.levels, depth);
+        let Some(index) = self.levels[depth].highlighted else {
+            return Ok(());
+        };
+        let surface = self.levels[depth].surface;
+        self.open(
+            items[index].items(),
+            canvas,
+            depth + 1,
+            (
+                surface.x() + surface.width(),
+                surface.y() + row_top(items, &self.style, index),
+            ),
+            surface.x(),
+        )
+    }
+
+    fn level_at(&self, x: f32, y: f32) -> Option<usize> {
+        self.levels
+            .iter()
+            .rposition(|level| level.surface.contains_point(FPoint::new(x, y)))
+    }
+
+    fn hover<Parent: 'static, State: 'static>(
+        &mut self,
+        root: &[MenuItem<Parent, State>],
+        canvas: &Canvas<Window>,
+        depth: usize,
+        y: f32,
+    ) -> Result<()> {
+        let items = items_at(root, &self.levels, depth);
+        let level = &self.levels[depth];
+        let index = row_at(items, &self.style, y - level.surface.y())
+            .filter(|index| items[*index].selectable());
+        if level.highlighted == index && (self.levels.len() > depth + 1 || index.is_none()) {
+            return Ok(());
+        }
+        self.levels.truncate(depth + 1);
+        self.levels[depth].highlighted = index;
+        if index.is_some_and(|index| items[index].is_submenu()) {
+            self.open_submenu(root, canvas, depth)?;
+        }
+        Ok(())
+    }
+
+    ///Opens the highlighted submenu or closes everything and triggers the highlighted item.
+    fn activate<Parent: 'static, State: 'static>(
+        &mut self,
+        root: &mut [MenuItem<Parent, State>],
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let depth = self.levels.len() - 1;
+        let Some(index) = self.levels[depth].highlighted else {
+            return Ok(());
+        };
+        let items = items_at_mut(root, &self.levels, depth);
+        if items[index].is_submenu() {
+            self.open_submenu(root, canvas, depth)?;
+            self.step(root, 1);
+        } else {
+            self.close();
+            MenuItem::activate((&mut items[index]).into(), parent, state, canvas)?;
+        }
+        Ok(())
+    }
+
+    ///Moves the highlight of the deepest menu to the next selectable item.
+    fn step<Parent: 'static, State: 'static>(
+        &mut self,
+        root: &[MenuItem<Parent, State>],
+        forward: isize,
+    ) {
+        let depth = self.levels.len() - 1;
+        let items = items_at(root, &self.levels, depth);
+        let len = items.len() as isize;
+        let mut index = self.levels[depth]
+            .highlighted
+            .map_or(if forward > 0 { -1 } else { len }, |index| index as isize);
+        for _ in 0..len {
+            index = (index + forward).rem_euclid(len);
+            if items[index as usize].selectable() {
+                self.levels[depth].highlighted = Some(index as usize);
+                return;
+            }
+        }
+    }
+
+    fn key<Parent: 'static, State: 'static>(
+        &mut self,
+        root: &mut [MenuItem<Parent, State>],
+        keycode: Keycode,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match keycode {
+            Keycode::Escape => {
+                self.levels.pop();
+            }
+            Keycode::Left if self.levels.len() > 1 => {
+                self.levels.pop();
+            }
+            Keycode::Up => self.step(root, -1),
+            Keycode::Down => self.step(root, 1),
+            Keycode::Right => {
+                let depth = self.levels.len() - 1;
+                let items = items_at(root, &self.levels, depth);
+                if self.levels[depth]
+                    .highlighted
+                    .is_some_and(|index| items[index].is_submenu())
+                {
+                    self.open_submenu(root, canvas, depth)?;
+                    self.step(root, 1);
+                }
+            }
+            Keycode::Return | Keycode::KpEnter | Keycode::Space => {
+                self.activate(root, canvas, parent, state)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    ///Handles the events used by the overlay layer, see uses.
+    fn event<Parent: 'static, State: 'static>(
+        &mut self,
+        root: &mut [MenuItem<Parent, State>],
+        canvas: &Canvas<Window>,
+        event: &Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match *event {
+            Event::MouseMotion { x, y, .. } => {
+                if let Some(depth) = self.level_at(x, y) {
+                    self.hover(root, canvas, depth, y)?;
+                }
+            }
+            Event::MouseButtonDown {
+                mouse_btn, x, y, ..
+            } => match self.level_at(x, y) {
+                Some(depth) => {
+                    self.hover(root, canvas, depth, y)?;
+                    if mouse_btn == MouseButton::Left {
+                        self.levels.truncate(depth + 1);
+                        self.activate(root, canvas, parent, state)?;
+                    }
+                }
+                None => self.close(),
+            },
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } => self.key(root, keycode, canvas, parent, state)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn draw_item<Parent: 'static, State: 'static>(
+        &self,
+        canvas: &mut Canvas<Window>,
+        item: &MenuItem<Parent, State>,
+        row: FRect,
+        highlighted: bool,
+    ) -> Result<()> {
+        let style = &self.style;
+        if let Kind::Separator = item.kind {
+            canvas.set_draw_color(style.disabled_color);
+            let y = row.y() + row.height() / 2.;
+            return canvas
+                .draw_fline(
+                    FPoint::new(row.x() + PADDING, y),
+                    FPoint::new(row.x() + row.width() - PADDING, y),
+                )
+                .map_err(|e| anyhow!(e));
+        }
+        if highlighted {
+            canvas.set_draw_color(style.highlight_color);
+            canvas.fill_frect(row).map_err(|e| anyhow!(e))?;
+        }
+        let color = if item.enabled {
+            style.front_color
+        } else {
+            style.disabled_color
+        };
+        let (x, y, size) = (row.x(), row.y(), row.height());
+        if item.checked == Some(true) {
+            let width = (size / 12.).max(1.) as u8;
+            canvas
+                .thick_line(
+                    (x + CHECK * 0.25) as i16,
+                    (y + size * 0.5) as i16,
+                    (x + CHECK * 0.45) as i16,
+                    (y + size * 0.7) as i16,
+                    width,
+                    color,
+                )
+                .map_err(|e| anyhow!(e))?;
+            canvas
+                .thick_line(
+                    (x + CHECK * 0.45) as i16,
+                    (y + size * 0.7) as i16,
+                    (x + CHECK * 0.8) as i16,
+                    (y + size * 0.3) as i16,
+                    width,
+                    color,
+                )
+                .map_err(|e| anyhow!(e))?;
+        }
+        let text_to = FRect::new(x + CHECK, y, row.width() - CHECK - ARROW, size);
+        UIString::new_const(self.font, &item.text).draw_left(canvas, text_to, color)?;
+        if let Some(text) = item.accelerator_text() {
+            let width = self.text_width(&text)?;
+            UIString::new_const(self.font, &text).draw_left(
+                canvas,
+                FRect::new(text_to.x() + text_to.width() - width, y, width, size),
+                color,
+            )?;
+        }
+        if item.is_submenu() {
+            let (cx, cy, half) = (x + row.width() - ARROW / 2., y + size / 2., size / 6.);
+            canvas
+                .filled_trigon(
+                    (cx - half / 2.) as i16,
+                    (cy - half) as i16,
+                    (cx - half / 2.) as i16,
+                    (cy + half) as i16,
+                    (cx + half) as i16,
+                    cy as i16,
+                    color,
+                )
+                .map_err(|e| anyhow!(e))?;
+        }
+        Ok(())
+    }
+
+    fn draw<Parent: 'static, State: 'static>(
+        &self,
+        root: &[MenuItem<Parent, State>],
+        canvas: &mut Canvas<Window>,
+    ) -> Result<()> {
+        for (depth, level) in self.levels.iter().enumerate() {
+            let surface = level.surface;
+            canvas.set_draw_color(self.style.back_color);
+            canvas.fill_frect(surface).map_err(|e| anyhow!(e))?;
+            let mut y = surface.y();
+            for (index, item) in items_at(root, &self.levels, depth).iter().enumerate() {
+                let height = item.height(&self.style);
+                self.draw_item(
+                    canvas,
+                    item,
+                    FRect::new(surface.x(), y, surface.width(), height),
+                    level.highlighted == Some(index),
+                )?;
+                y += height;
+            }
+            canvas.set_draw_color(self.style.front_color);
+            canvas.draw_frect(surface).map_err(|e| anyhow!(e))?;
+        }
+        Ok(())
+    }
+}
+
+///A bar of menus, each one opening its items below its title.
+pub struct MenuBar<Parent: 'static, State: 'static> {
+    parent: PhantomData<Parent>,
+    statel: PhantomData<State>,
+    surface: FRect,
+    ///The submenus shown in the bar.
+    menus: Vec<MenuItem<Parent, State>>,
+    popup: MenuPopup,
+    ///The menu shown by the popup.
+    open: Option<usize>,
+    hover: Option<usize>,
+}
+
+impl<Parent: 'static, State: 'static> MenuBar<Parent, State> {
+    pub fn new(font: &'static Font<'static, 'static>, menus: Vec<MenuItem<Parent, State>>) -> Self {
+        Self {
+            parent: PhantomData,
+            statel: PhantomData,
+            surface: zero(),
+            menus,
+            popup: MenuPopup::new(font),
+            open: None,
+            hover: None,
+        }
+    }
+
+    pub const fn surface(&self) -> FRect {
+        self.surface
+    }
+
+    pub const fn style(mut self, style: MenuStyle) -> Self {
+        self.popup.style = style;
+        self
+    }
+
+    pub fn menus(&self) -> &[MenuItem<Parent, State>] {
+        &self.menus
+    }
+
+    pub fn menus_mut(&mut self) -> &mut Vec<MenuItem<Parent, State>> {
+        &mut self.menus
+    }
+
+    pub const fn is_open(&self) -> bool {
+        self.open.is_some()
+    }
+
+    fn title(&self, index: usize) -> Result<FRect> {
+        let mut x = self.surface.x();
+        for menu in &self.menus[..index] {
+            x += self.popup.text_width(&menu.text)? + 2. * PADDING;
+        }
+        Ok(FRect::new(
+            x,
+            self.surface.y(),
+            self.popup.text_width(&self.menus[index].text)? + 2. * PADDING,
+            self.surface.height(),
+        ))
+    }
+
+    fn title_at(&self, x: f32, y: f32) -> Result<Option<usize>> {
+        for index in 0..self.menus.len() {
+            if self.title(index)?.contains_point(FPoint::new(x, y)) {
+                return Ok(Some(index));
+            }
+        }
+        Ok(None)
+    }
+
+    fn open_menu(&mut self, index: usize, canvas: &Canvas<Window>) -> Result<()> {
+        let title = self.title(index)?;
+        self.popup.open(
+            self.menus[index].items(),
+            canvas,
+            0,
+            (title.x(), title.y() + title.height()),
+            title.x() + title.width(),
+        )?;
+        self.open = Some(index);
+        self.sync_layer();
+        Ok(())
+    }
+
+    fn close(&mut self) {
+        self.popup.close();
+        self.sync_layer();
+    }
+
+    ///Handles the events the open menu got from its overlay layer since the last call.
+    fn popup_events(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        for event in this.popup.take_events() {
+            let bar = this.as_mut();
+            if let Some(open) = bar.open {
+                bar.popup.event(
+                    bar.menus[open].items_slice_mut(),
+                    canvas,
+                    &event,
+                    parent,
+                    state,
+                )?;
+            }
+        }
+        this.sync_layer();
+        Ok(())
+    }
+
+    fn sync_layer(&mut self) {
+        if !self.popup.sync_layer() {
+            self.open = None;
+        }
+    }
+}
+
+impl<Parent: 'static, State: 'static> UserControl<Parent, State> for MenuBar<Parent, State> {
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        Self::popup_events(this, canvas, parent, state)?;
+        match event {
+            Event::ElementMove { x, y } => {
+                this.surface.set_x(x);
+                this.surface.set_y(y);
+            }
+            Event::ElementResize { width, height } => {
+                this.surface.set_width(width);
+                this.surface.set_height(height);
+            }
+            Event::MouseMotion { x, y, .. } => {
+                this.hover = this.title_at(x, y)?;
+                //Moving over the other titles switches the open menu.
+                if let (Some(open), Some(hover)) = (this.open, this.hover) {
+                    if open != hover {
+                        this.open_menu(hover, canvas)?;
+                    }
+                }
+            }
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => {
+                if let Some(index) = this.title_at(x, y)? {
+                    if this.open == Some(index) {
+                        this.close();
+                    } else {
+                        this.open_menu(index, canvas)?;
+                    }
+                }
+            }
+            Event::KeyDown { .. } if this.open.is_none() => {
+                accelerate(&mut this.menus, &event, parent, state, canvas)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn update(
+        this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        _: Duration,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        Self::popup_events(this, canvas, parent, state)
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        _: Ref<Parent>,
+        _: Ref<State>,
+    ) -> Result<()> {
+        let style = &this.popup.style;
+        canvas.set_draw_color(style.back_color);
+        canvas.fill_frect(this.surface).map_err(|e| anyhow!(e))?;
+        for (index, menu) in this.menus.iter().enumerate() {
+            let title = this.title(index)?;
+            if this.open == Some(index) || this.hover == Some(index) {
+                canvas.set_draw_color(style.highlight_color);
+                canvas.fill_frect(title).map_err(|e| anyhow!(e))?;
+            }
+            let color = if menu.enabled {
+                style.front_color
+            } else {
+                style.disabled_color
+            };
+            UIString::new_const(this.popup.font, &menu.text).draw_left(
+                canvas,
+                FRect::new(
+                    title.x() + PADDING,
+                    title.y(),
+                    title.width() - 2. * PADDING,
+                    title.height(),
+                ),
+                color,
+            )?;
+        }
+        if let Some(open) = this.open {
+            //Only kept until the overlay is drawn at the end of this frame.
+            overlay::draw_above(Box::new(move |canvas| {
+                this.popup.draw(this.menus[open].items(), canvas)
+            }));
+        }
+        Ok(())
+    }
+}
+
+///Opens its items where the child gets right clicked.
+pub struct ContextMenu<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+{
+    parent: PhantomData<Parent>,
+    statel: PhantomData<State>,
+    child: Child,
+    items: Vec<MenuItem<Parent, State>>,
+    popup: MenuPopup,
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    ContextMenu<Parent, State, Child>
+{
+    pub fn new(
+        font: &'static Font<'static, 'static>,
+        child: Child,
+        items: Vec<MenuItem<Parent, State>>,
+    ) -> Self {
+        Self {
+            parent: PhantomData,
+            statel: PhantomData,
+            child,
+            items,
+            popup: MenuPopup::new(font),
+        }
+    }
+
+    pub const fn style(mut self, style: MenuStyle) -> Self {
+        self.popup.style = style;
+        self
+    }
+
+    pub const fn child(&self) -> &Child {
+        &self.child
+    }
+
+    pub fn child_mut(&mut self) -> &mut Child {
+        &mut self.child
+    }
+
+    pub fn items(&self) -> &[MenuItem<Parent, State>] {
+        &self.items
+    }
+
+    pub fn items_mut(&mut self) -> &mut Vec<MenuItem<Parent, State>> {
+        &mut self.items
+    }
+
+    pub const fn is_open(&self) -> bool {
+        self.popup.is_open()
+    }
+
+    ///Opens the menu with its top left corner at the point, or the closest place fitting in the window.
+    pub fn open_at(&mut self, canvas: &Canvas<Window>, (x, y): (f32, f32)) -> Result<()> {
+        self.popup.open(&self.items, canvas, 0, (x, y), x)?;
+        self.popup.sync_layer();
+        Ok(())
+    }
+
+    pub fn close(&mut self) {
+        self.popup.close();
+        self.popup.sync_layer();
+    }
+
+    ///Handles the events the menu got from its overlay layer since the last call.
+    fn popup_events(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        for event in this.popup.take_events() {
+            let menu = this.as_mut();
+            menu.popup
+                .event(&mut menu.items, canvas, &event, parent, state)?;
+        }
+        this.popup.sync_layer();
+        Ok(())
+    }
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    UserControl<Parent, State> for ContextMenu<Parent, State, Child>
+{
+    fn surface(this: Ref<Self>, parent: Ref<Parent>, state: Ref<State>) -> FRect {
+        UserControl::surface((&this.child).into(), parent, state)
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        Self::popup_events(this, canvas, parent, state)?;
+        UserControl::event(
+            (&mut this.child).into(),
+            canvas,
+            event.clone(),
+            parent,
+            state,
+        )?;
+        match event {
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Right,
+                x,
+                y,
+                ..
+            } if event.hover(UserControl::surface(
+                (&this.child).into(),
+                parent.into(),
+                state.into(),
+            )) =>
+            {
+                this.open_at(canvas, (x, y))?;
+            }
+            Event::KeyDown { .. } if !this.popup.is_open() => {
+                accelerate(&mut this.items, &event, parent, state, canvas)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn update(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        elapsed: Duration,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        Self::popup_events(this, canvas, parent, state)?;
+        UserControl::update((&mut this.child).into(), canvas, elapsed, parent, state)
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        UserControl::draw((&this.child).into(), canvas, parent, state)?;
+        if this.popup.is_open() {
+            //Only kept until the overlay is drawn at the end of this frame.
+            overlay::draw_above(Box::new(move |canvas| this.popup.draw(&this.items, canvas)));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod menu_test {
+    use super::*;
+
+    pub(crate) fn test_menu_accelerator(canvas: &mut Canvas<Window>) {
+        let mut items = vec![MenuItem::<(), usize>::submenu(
+            "View",
+            vec![
+                MenuItem::action(
+                    "Grid",
+                    Box::new(|_, _, mut state: MutRef<usize>, _| {
+                        *state += 1;
+                        Ok(())
+                    }),
+                )
+                .checkable(false)
+                .accelerator(Keycode::G, Mod::LCTRLMOD),
+                MenuItem::separator(),
+            ],
+        )];
+        assert_eq!(
+            items[0].items()[0].accelerator_text().as_deref(),
+            Some("Ctrl+G")
+        );
+        let mut parent = ();
+        let parent = MutRef::new(&mut parent);
+        let mut count = 0_usize;
+        let count = MutRef::new(&mut count);
+        let mut key = |keymod| {
+            accelerate(
+                &mut items,
+                &Event::KeyDown {
+                    keycode: Some(Keycode::G),
+                    scancode: None,
+                    keymod,
+                    repeat: false,
+                },
+                parent,
+                count,
+                canvas,
+            )
+            .expect("")
+        };
+
+        assert!(!key(Mod::NOMOD));
+        assert!(key(Mod::RCTRLMOD));
+        assert!(!key(Mod::LCTRLMOD | Mod::LSHIFTMOD));
+        assert_eq!(*count, 1);
+        assert_eq!(items[0].items()[0].is_checked(), Some(true));
+    }
+}
@@ -1,5 +1,147 @@
+use anyhow::Result;
+use sdl2::{rect::FRect, render::Canvas, video::Window};
+
+use crate::{event::Event, refs::MutRef, user_control::UserControl};
+
+pub mod cached;
+pub mod checkbox;
+pub mod combo_box;
+pub mod data_grid;
+pub mod dialog;
+pub mod dock_panel;
+pub mod flex;
 pub mod grid;
+pub mod list_view;
+pub mod menu;
 pub mod panel;
+pub mod progress_bar;
+pub mod radio_group;
 pub mod scroll_view;
+pub mod slider;
+pub mod spinner;
+pub mod splitter;
+pub mod stack_panel;
+pub mod tab_control;
 pub mod text_box;
+pub mod toggle_switch;
+pub mod tooltip;
+pub mod tree_view;
 pub mod ui_rect;
+pub mod wrap_panel;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Horizontal,
+    Vertical,
+}
+
+impl Orientation {
+    ///The start and the length of the rect along the orientation then across it.
+    pub fn split(self, rect: FRect) -> (f32, f32, f32, f32) {
+        match self {
+            Self::Horizontal => (rect.x(), rect.width(), rect.y(), rect.height()),
+            Self::Vertical => (rect.y(), rect.height(), rect.x(), rect.width()),
+        }
+    }
+
+    ///The opposite of split.
+    pub fn join(self, main: f32, main_len: f32, cross: f32, cross_len: f32) -> FRect {
+        match self {
+            Self::Horizontal => FRect::new(main, cross, main_len, cross_len),
+            Self::Vertical => FRect::new(cross, main, cross_len, main_len),
+        }
+    }
+}
+
+///Where a child goes in the space it is given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    Start,
+    Center,
+    End,
+    ///Takes the whole space.
+    Stretch,
+}
+
+impl Alignment {
+    ///The start and the length of something of len placed in the space.
+    pub fn place(self, start: f32, space: f32, len: f32) -> (f32, f32) {
+        match self {
+            Self::Start => (start, len),
+            Self::Center => (start + (space - len) / 2., len),
+            Self::End => (start + space - len, len),
+            Self::Stretch => (start, space),
+        }
+    }
+}
+
+///Sends ElementMove and ElementResize to the child when it isn't already at the rect.</br>
+///The layout containers place their children again on each update with it,
+///so what changed through their children_mut gets placed while the others get nothing.
+pub(crate) fn place<Parent: 'static, State: 'static, Child: UserControl<Parent, State>>(
+    child: MutRef<Child>,
+    rect: FRect,
+    canvas: &Canvas<Window>,
+    parent: MutRef<Parent>,
+    state: MutRef<State>,
+) -> Result<()> {
+    let surface = UserControl::surface(child.into(), parent.into(), state.into());
+    if surface.x() != rect.x() || surface.y() != rect.y() {
+        UserControl::event(
+            child,
+            canvas,
+            Event::ElementMove {
+                x: rect.x(),
+                y: rect.y(),
+            },
+            parent,
+            state,
+        )?;
+    }
+    if surface.width() != rect.width() || surface.height() != rect.height() {
+        UserControl::event(
+            child,
+            canvas,
+            Event::ElementResize {
+                width: rect.width(),
+                height: rect.height(),
+            },
+            parent,
+            state,
+        )?;
+    }
+    Ok(())
+}
+
+///Sends the event to the children, given from the top of the draw order to the bottom.</br>
+///A click or a wheel only reaches the topmost child under the pointer, the children it covers don't get it.</br>
+///The motions and the releases reach everyone so a drag can end over another child.
+pub(crate) fn dispatch<
+    Parent: 'static,
+    State: 'static,
+    Child: UserControl<Parent, State> + 'static,
+>(
+    children: impl IntoIterator<Item = MutRef<Child>>,
+    canvas: &Canvas<Window>,
+    event: &Event,
+    parent: MutRef<Parent>,
+    state: MutRef<State>,
+) -> Result<()> {
+    let point = match event {
+        Event::MouseButtonDown { .. } | Event::MouseWheel { .. } => event.position(),
+        _ => None,
+    };
+    let mut hit = false;
+    for child in children {
+        if let Some(point) = point {
+            let inside = UserControl::surface(child.into(), parent.into(), state.into())
+                .contains_point(point);
+            if inside && hit {
+                continue;
+            }
+            hit |= inside;
+        }
+        UserControl::event(child, canvas, event.clone(), parent, state)?;
+    }
+    Ok(())
+}
@@ -11,17 +11,180 @@ use crate::{
     event::Event,
     refs::{MutRef, Ref},
     state_manager::StateManager,
+    ui_element::{dispatch, place},
     user_control::UserControl,
     zero,
 };
 
+///Where an edge of a child goes, at a ratio of the panel plus an offset in pixels.</br>
+///The ratio is 0 at the left or the top of the panel and 1 at its right or bottom.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnchorEdge {
+    pub ratio: f32,
+    pub offset: f32,
+}
+
+impl AnchorEdge {
+    pub const fn new(ratio: f32, offset: f32) -> Self {
+        Self { ratio, offset }
+    }
+
+    fn at(self, start: f32, len: f32) -> f32 {
+        start + len * self.ratio + self.offset
+    }
+}
+
+///Places a child of a panel relative to the panel, the child follows when the panel moves or gets resized.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anchor {
+    pub left: AnchorEdge,
+    pub top: AnchorEdge,
+    pub right: AnchorEdge,
+    pub bottom: AnchorEdge,
+}
+
+impl Anchor {
+    pub const fn new(
+        left: AnchorEdge,
+        top: AnchorEdge,
+        right: AnchorEdge,
+        bottom: AnchorEdge,
+    ) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    ///Takes the whole panel.
+    pub const fn fill() -> Self {
+        Self::ratios(0., 0., 1., 1.)
+    }
+
+    ///Each edge at a ratio of the panel.
+    pub const fn ratios(left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        Self::new(
+            AnchorEdge::new(left, 0.),
+            AnchorEdge::new(top, 0.),
+            AnchorEdge::new(right, 0.),
+            AnchorEdge::new(bottom, 0.),
+        )
+    }
+
+    ///Keeps its size with its top left corner at the ratios of the panel.
+    pub const fn at(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self::new(
+            AnchorEdge::new(x, 0.),
+            AnchorEdge::new(y, 0.),
+            AnchorEdge::new(x, width),
+            AnchorEdge::new(y, height),
+        )
+    }
+
+    pub const fn top_left(width: f32, height: f32) -> Self {
+        Self::at(0., 0., width, height)
+    }
+
+    pub const fn top_right(width: f32, height: f32) -> Self {
+        Self::at(1., 0., width, height).offset(-width, 0.)
+    }
+
+    pub const fn bottom_left(width: f32, height: f32) -> Self {
+        Self::at(0., 1., width, height).offset(0., -height)
+    }
+
+    pub const fn bottom_right(width: f32, height: f32) -> Self {
+        Self::at(1., 1., width, height).offset(-width, -height)
+    }
+
+    pub const fn center(width: f32, height: f32) -> Self {
+        Self::at(0.5, 0.5, width, height).offset(-width / 2., -height / 2.)
+    }
+
+    ///Stretches along the top of the panel.
+    pub const fn top(height: f32) -> Self {
+        let mut anchor = Self::ratios(0., 0., 1., 0.);
+        anchor.bottom.offset = height;
+        anchor
+    }
+
+    ///Stretches along the bottom of the panel.
+    pub const fn bottom(height: f32) -> Self {
+        let mut anchor = Self::ratios(0., 1., 1., 1.);
+        anchor.top.offset = -height;
+        anchor
+    }
+
+    ///Stretches along the left of the panel.
+    pub const fn left(width: f32) -> Self {
+        let mut anchor = Self::ratios(0., 0., 0., 1.);
+        anchor.right.offset = width;
+        anchor
+    }
+
+    ///Stretches along the right of the panel.
+    pub const fn right(width: f32) -> Self {
+        let mut anchor = Self::ratios(1., 0., 1., 1.);
+        anchor.left.offset = -width;
+        anchor
+    }
+
+    ///Moves every edge.
+    pub const fn offset(mut self, x: f32, y: f32) -> Self {
+        self.left.offset += x;
+        self.right.offset += x;
+        self.top.offset += y;
+        self.bottom.offset += y;
+        self
+    }
+
+    ///Brings every edge inside by margin.
+    pub const fn margin(mut self, margin: f32) -> Self {
+        self.left.offset += margin;
+        self.top.offset += margin;
+        self.right.offset -= margin;
+        self.bottom.offset -= margin;
+        self
+    }
+
+    ///The rect of the child in a panel at surface.
+    pub fn rect(self, surface: FRect) -> FRect {
+        let left = self.left.at(surface.x(), surface.width());
+        let top = self.top.at(surface.y(), surface.height());
+        let right = self.right.at(surface.x(), surface.width());
+        let bottom = self.bottom.at(surface.y(), surface.height());
+        FRect::new(left, top, (right - left).max(0.), (bottom - top).max(0.))
+    }
+}
+
+///What the panel keeps for each child.
+#[derive(Debug, Clone, Copy, Default)]
+struct Slot {
+    anchor: Option<Anchor>,
+    z_index: i32,
+    ///Not placed yet since it was pushed or inserted.
+    fresh: bool,
+}
+
+///Children without an anchor get ElementMove and ElementResize as the panel gets them.</br>
+///The children are drawn by z index then in order, the pointer events go to the topmost child under the pointer.
 pub struct Panel<Parent: 'static, State: 'static, T: UserControl<Parent, State> + 'static> {
     state: PhantomData<State>,
     parent: PhantomData<Parent>,
     surface: FRect,
     subs: Vec<T>,
+    ///One for each child, they only change together.
+    slots: Vec<Slot>,
+    ///Changes the children directly, their anchors and z indexes stay at the same index.</br>
+    ///The children it adds at the end get placed like the pushed ones.
     #[allow(clippy::type_complexity)]
     pub state_manager: StateManager<(MutRef<Parent>, MutRef<Vec<T>>)>,
+    ///Goes through the panel so the anchors and the z indexes follow their child.
+    #[allow(clippy::type_complexity)]
+    pub panel_manager: StateManager<(MutRef<Parent>, MutRef<Panel<Parent, State, T>>)>,
+    changes: StateManager<MutRef<Panel<Parent, State, T>>>,
 }
 
 impl<Parent: 'static, State: 'static, T: UserControl<Parent, State>> Panel<Parent, State, T> {
@@ -30,11 +193,143 @@ impl<Parent: 'static, State: 'static, T: UserControl<Parent, State>> Panel<Paren
             state: PhantomData,
             parent: PhantomData,
             surface: zero(),
+            slots: vec![Slot::default(); subs.len()],
             subs,
             state_manager: StateManager::new(),
+            panel_manager: StateManager::new(),
+            changes: StateManager::new(),
+        }
+    }
+
+    pub fn anchored(subs: Vec<(T, Anchor)>) -> Self {
+        let (subs, slots) = subs
+            .into_iter()
+            .map(|(sub, anchor)| {
+                let slot = Slot {
+                    anchor: Some(anchor),
+                    ..Slot::default()
+                };
+                (sub, slot)
+            })
+            .unzip();
+        Self {
+            subs,
+            slots,
+            ..Self::new(Vec::new())
         }
     }
 
+    fn slot(&self, index: usize) -> Slot {
+        self.slots.get(index).copied().unwrap_or_default()
+    }
+
+    fn queue_insert(&mut self, index: Option<usize>, sub: T, anchor: Option<Anchor>) {
+        self.changes.add(Box::new(move |mut this: MutRef<Self>| {
+            let index = index.unwrap_or(this.subs.len());
+            if index > this.subs.len() {
+                return Ok(());
+            }
+            this.subs.insert(index, sub);
+            let slot = Slot {
+                anchor,
+                fresh: true,
+                ..Slot::default()
+            };
+            this.slots.insert(index, slot);
+            Ok(())
+        }));
+    }
+
+    ///Adds the child at the end on the next update, so it can be called while the children handle an event.</br>
+    ///It then gets its ElementMove and ElementResize.
+    pub fn push(&mut self, sub: T) {
+        self.queue_insert(None, sub, None);
+    }
+
+    pub fn push_anchored(&mut self, sub: T, anchor: Anchor) {
+        self.queue_insert(None, sub, Some(anchor));
+    }
+
+    ///Like push, the index is resolved when the change is applied, after the changes queued before it.</br>
+    ///Nothing is inserted when index is past the end by then.
+    pub fn insert(&mut self, index: usize, sub: T) {
+        self.queue_insert(Some(index), sub, None);
+    }
+
+    ///Like insert, with the anchor of the child.
+    pub fn insert_anchored(&mut self, index: usize, sub: T, anchor: Anchor) {
+        self.queue_insert(Some(index), sub, Some(anchor));
+    }
+
+    ///Removes the child on the next update, if there is still one at index.</br>
+    ///The index is resolved after the changes queued before it,
+    ///so remove(1) then remove(2) in the same handler removes the children 1 and 3 of before.
+    pub fn remove(&mut self, index: usize) {
+        self.changes.add(Box::new(move |mut this: MutRef<Self>| {
+            if index >= this.subs.len() {
+                return Ok(());
+            }
+            this.subs.remove(index);
+            this.slots.remove(index);
+            Ok(())
+        }));
+    }
+
+    ///Keeps the children for which keep is true on the next update.
+    pub fn retain(&mut self, mut keep: impl FnMut(&T) -> bool + 'static) {
+        self.changes.add(Box::new(move |mut this: MutRef<Self>| {
+            let this = this.as_mut();
+            (this.subs, this.slots) = this
+                .subs
+                .drain(..)
+                .zip(this.slots.drain(..))
+                .filter(|(sub, _)| keep(sub))
+                .unzip();
+            Ok(())
+        }));
+    }
+
+    ///Moves the child at the end on the next update, above the children with the same z index.</br>
+    ///The index is resolved once the changes queued before it are applied,
+    ///nothing moves when there is no child at index by then.
+    pub fn move_to_front(&mut self, index: usize) {
+        self.changes.add(Box::new(move |mut this: MutRef<Self>| {
+            if index >= this.subs.len() {
+                return Ok(());
+            }
+            let sub = this.subs.remove(index);
+            let slot = this.slots.remove(index);
+            this.subs.push(sub);
+            this.slots.push(slot);
+            Ok(())
+        }));
+    }
+
+    pub fn anchor(&self, index: usize) -> Option<Anchor> {
+        self.slot(index).anchor
+    }
+
+    ///The child gets placed again on the next update.
+    pub fn set_anchor(&mut self, index: usize, anchor: Option<Anchor>) {
+        self.slots[index].anchor = anchor;
+    }
+
+    pub fn z_index(&self, index: usize) -> i32 {
+        self.slot(index).z_index
+    }
+
+    ///The higher ones are drawn above, 0 by default.
+    pub fn set_z_index(&mut self, index: usize, z_index: i32) {
+        self.slots[index].z_index = z_index;
+    }
+
+    ///The indexes of the children from the first drawn to the last.
+    pub fn draw_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.subs.len()).collect();
+        order.sort_by_key(|index| self.z_index(*index));
+        order
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &T> {
         self.subs.iter()
     }
@@ -42,6 +337,22 @@ impl<Parent: 'static, State: 'static, T: UserControl<Parent, State>> Panel<Paren
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
         self.subs.iter_mut()
     }
+
+    ///Sends the anchored children their rect when they aren't already there.
+    fn reform(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let surface = this.surface;
+        for (index, sub) in this.as_mut().subs.iter_mut().enumerate() {
+            if let Some(anchor) = this.anchor(index) {
+                place(sub.into(), anchor.rect(surface), canvas, parent, state)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl<Parent: 'static, State: 'static, T: UserControl<Parent, State>> UserControl<Parent, State>
@@ -69,10 +380,26 @@ impl<Parent: 'static, State: 'static, T: UserControl<Parent, State>> UserControl
             }
             _ => {}
         }
-        for sub in this.subs.iter_mut() {
-            UserControl::event(sub.into(), canvas, event.clone(), parent, state)?;
+        if !matches!(
+            event,
+            Event::ElementMove { .. } | Event::ElementResize { .. }
+        ) {
+            let subs = &mut this.as_mut().subs;
+            let order = this.draw_order().into_iter().rev();
+            return dispatch(
+                order.map(|index| (&mut subs[index]).into()),
+                canvas,
+                &event,
+                parent,
+                state,
+            );
         }
-        Ok(())
+        for (index, sub) in this.as_mut().subs.iter_mut().enumerate() {
+            if this.anchor(index).is_none() {
+                UserControl::event(sub.into(), canvas, event.clone(), parent, state)?;
+            }
+        }
+        Self::reform(this, canvas, parent, state)
     }
 
     fn update(
@@ -87,7 +414,41 @@ impl<Parent: 'static, State: 'static, T: UserControl<Parent, State>> UserControl
         }
         let subs = (&mut this.subs).into();
         this.as_mut().state_manager.apply((parent, subs))?;
-        Ok(())
+        let fresh = Slot {
+            fresh: true,
+            ..Slot::default()
+        };
+        let len = this.subs.len();
+        this.slots.resize(len, fresh);
+        this.as_mut().panel_manager.apply((parent, this))?;
+        this.as_mut().changes.apply(this)?;
+        //The anchored children get their rect from reform.
+        let surface = this.surface;
+        for (index, sub) in this.as_mut().subs.iter_mut().enumerate() {
+            let fresh = this
+                .slots
+                .get_mut(index)
+                .is_some_and(|slot| std::mem::take(&mut slot.fresh));
+            if fresh && this.anchor(index).is_none() {
+                let (x, y) = (surface.x(), surface.y());
+                let (width, height) = (surface.width(), surface.height());
+                UserControl::event(
+                    sub.into(),
+                    canvas,
+                    Event::ElementMove { x, y },
+                    parent,
+                    state,
+                )?;
+                UserControl::event(
+                    sub.into(),
+                    canvas,
+                    Event::ElementResize { width, height },
+                    parent,
+                    state,
+                )?;
+            }
+        }
+        Self::reform(this, canvas, parent, state)
     }
 
     fn draw(
@@ -96,8 +457,8 @@ impl<Parent: 'static, State: 'static, T: UserControl<Parent, State>> UserControl
         parent: Ref<Parent>,
         state: Ref<State>,
     ) -> Result<()> {
-        for sub in this.subs.iter() {
-            UserControl::draw(sub.into(), canvas, parent, state)?;
+        for index in this.draw_order() {
+            UserControl::draw((&this.subs[index]).into(), canvas, parent, state)?;
         }
         Ok(())
     }
@@ -124,9 +485,12 @@ impl<Parent: 'static, State: 'static, T: UserControl<Parent, State>> IndexMut<us
 #[cfg(test)]
 pub(crate) mod panel_test {
     use anyhow::Result;
-    use sdl2::mouse::MouseButton;
+    use sdl2::mouse::{MouseButton, MouseState};
 
-    use crate::refs::{MutRef, Ref};
+    use crate::{
+        refs::{MutRef, Ref},
+        ui_element::stack_panel::stack_panel_test::block,
+    };
 
     use super::*;
 
@@ -169,6 +533,52 @@ pub(crate) mod panel_test {
         }
     }
 
+    ///Follows the pointer from a click on it to the release, counting the releases in the state.
+    struct Handle {
+        surface: FRect,
+        dragging: bool,
+        x: f32,
+    }
+
+    impl UserControl<(), usize> for Handle {
+        fn surface(this: Ref<Self>, _: Ref<()>, _: Ref<usize>) -> FRect {
+            this.surface
+        }
+
+        fn event(
+            mut this: MutRef<Self>,
+            _: &Canvas<Window>,
+            event: Event,
+            _: MutRef<()>,
+            mut state: MutRef<usize>,
+        ) -> Result<()> {
+            match event {
+                Event::MouseButtonDown { .. } => this.dragging = event.hover(this.surface),
+                Event::MouseMotion { x, .. } if this.dragging => this.x = x,
+                Event::MouseButtonUp { .. } if this.dragging => {
+                    this.dragging = false;
+                    *state += 1;
+                }
+                _ => {}
+            }
+            Ok(())
+        }
+
+        fn update(
+            _: MutRef<Self>,
+            _: &Canvas<Window>,
+            _: Duration,
+            _: MutRef<()>,
+            _: MutRef<usize>,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        fn draw(_: Ref<Self>, _: &mut Canvas<Window>, _: Ref<()>, _: Ref<usize>) -> Result<()> {
+            Ok(())
+        }
+    }
+
     #[allow(clippy::too_many_lines)]
     pub(crate) fn test_panel_click(canvas: &mut Canvas<Window>) {
         let mut counter = 0_usize;
@@ -239,6 +649,212 @@ pub(crate) mod panel_test {
         assert_eq!(*counter, 5);
     }
 
+    pub(crate) fn test_panel_anchors(canvas: &mut Canvas<Window>) {
+        let mut panel = Panel::anchored(vec![
+            (block(0., 0.), Anchor::top_left(10., 5.).offset(2., 2.)),
+            (block(0., 0.), Anchor::bottom_right(10., 5.)),
+            (block(0., 0.), Anchor::center(20., 10.)),
+            (block(0., 0.), Anchor::bottom(8.).margin(1.)),
+            (block(0., 0.), Anchor::ratios(0.25, 0., 0.75, 0.5)),
+            (block(1., 1.), Anchor::fill()),
+        ]);
+        panel.set_anchor(5, None);
+        let panel = MutRef::new(&mut panel);
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        for event in [
+            Event::ElementMove { x: 10., y: 20. },
+            Event::ElementResize {
+                width: 100.,
+                height: 40.,
+            },
+        ] {
+            UserControl::event(panel, canvas, event, unit, unit).expect("");
+        }
+
+        let surfaces: Vec<FRect> = panel.iter().map(|sub| sub.surface).collect();
+        assert_eq!(
+            surfaces,
+            [
+                FRect::new(12., 22., 10., 5.),
+                FRect::new(100., 55., 10., 5.),
+                FRect::new(50., 35., 20., 10.),
+                FRect::new(11., 53., 98., 6.),
+                FRect::new(35., 20., 50., 20.),
+                FRect::new(10., 20., 100., 40.),
+            ]
+        );
+    }
+
+    pub(crate) fn test_panel_z_order(canvas: &mut Canvas<Window>) {
+        let mut counter = 0_usize;
+        let counter = MutRef::new(&mut counter);
+        let mut panel = Panel::new(vec![
+            Button {
+                surface: FRect::new(0., 0., 20., 20.),
+            },
+            Button {
+                surface: FRect::new(10., 10., 20., 20.),
+            },
+            Button {
+                surface: FRect::new(0., 0., 5., 5.),
+            },
+        ]);
+        panel.set_z_index(0, 1);
+        assert_eq!(panel.draw_order(), [1, 2, 0]);
+        let panel = MutRef::new(&mut panel);
+        let mut parent = ();
+        let parent = MutRef::new(&mut parent);
+
+        //Only the topmost button under the pointer gets the click.
+        click(panel, parent, counter, canvas, 15., 15.);
+        assert_eq!(*counter, 1);
+        click(panel, parent, counter, canvas, 2., 2.);
+        assert_eq!(*counter, 2);
+        click(panel, parent, counter, canvas, 25., 25.);
+        assert_eq!(*counter, 3);
+        click(panel, parent, counter, canvas, 40., 40.);
+        assert_eq!(*counter, 3);
+    }
+
+    pub(crate) fn test_panel_drag_release(canvas: &mut Canvas<Window>) {
+        let handle = |x, width| Handle {
+            surface: FRect::new(x, 0., width, 20.),
+            dragging: false,
+            x: 0.,
+        };
+        let mut panel = Panel::new(vec![handle(0., 30.), handle(20., 20.)]);
+        panel.set_z_index(1, 1);
+        let panel = MutRef::new(&mut panel);
+        let mut parent = ();
+        let parent = MutRef::new(&mut parent);
+        let mut released = 0_usize;
+        let released = MutRef::new(&mut released);
+        let send = |event| UserControl::event(panel, canvas, event, parent, released).expect("");
+
+        send(Event::MouseButtonDown {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 5.,
+            y: 5.,
+        });
+        //The drag goes on and ends over the handle covering the first one.
+        send(Event::MouseMotion {
+            which: 0,
+            mousestate: MouseState::from_sdl_state(0),
+            x: 25.,
+            y: 5.,
+            moved_x: 20.,
+            moved_y: 0.,
+        });
+        send(Event::MouseButtonUp {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 25.,
+            y: 5.,
+        });
+        let drags = |panel: MutRef<Panel<(), usize, Handle>>| -> Vec<(bool, f32)> {
+            panel
+                .iter()
+                .map(|handle| (handle.dragging, handle.x))
+                .collect()
+        };
+        assert_eq!(drags(panel), [(false, 25.), (false, 0.)]);
+        assert_eq!(*released, 1);
+
+        //The click itself still only reaches the topmost handle.
+        send(Event::MouseButtonDown {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 25.,
+            y: 5.,
+        });
+        assert_eq!(drags(panel), [(false, 25.), (true, 0.)]);
+    }
+
+    pub(crate) fn test_panel_children(canvas: &mut Canvas<Window>) {
+        let mut panel = Panel::new(vec![block(1., 1.)]);
+        let mut panel = MutRef::new(&mut panel);
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        for event in [
+            Event::ElementMove { x: 10., y: 20. },
+            Event::ElementResize {
+                width: 100.,
+                height: 40.,
+            },
+        ] {
+            UserControl::event(panel, canvas, event, unit, unit).expect("");
+        }
+
+        panel.push(block(0., 0.));
+        panel.push_anchored(block(0., 0.), Anchor::top_left(5., 5.));
+        panel.insert(0, block(0., 0.));
+        assert_eq!(panel.iter().count(), 1);
+        UserControl::update(panel, canvas, Duration::ZERO, unit, unit).expect("");
+        let surfaces: Vec<FRect> = panel.iter().map(|sub| sub.surface).collect();
+        assert_eq!(
+            surfaces,
+            [
+                FRect::new(10., 20., 100., 40.),
+                FRect::new(10., 20., 100., 40.),
+                FRect::new(10., 20., 100., 40.),
+                FRect::new(10., 20., 5., 5.),
+            ]
+        );
+
+        panel.set_z_index(1, 7);
+        panel.move_to_front(1);
+        panel.remove(0);
+        panel.retain(|sub| sub.surface.width() == 100.);
+        UserControl::update(panel, canvas, Duration::ZERO, unit, unit).expect("");
+        assert_eq!(panel.iter().count(), 2);
+        assert_eq!((panel.z_index(0), panel.z_index(1)), (0, 7));
+        assert_eq!(panel.draw_order(), [0, 1]);
+
+        //The panel manager goes through the panel so the z indexes follow their child.
+        panel.panel_manager.add(Box::new(|(_, mut panel)| {
+            panel.move_to_front(0);
+            Ok(())
+        }));
+        UserControl::update(panel, canvas, Duration::ZERO, unit, unit).expect("");
+        assert_eq!((panel.z_index(0), panel.z_index(1)), (7, 0));
+        assert_eq!(panel.draw_order(), [1, 0]);
+
+        //The stale indexes are skipped without stopping the changes after them.
+        panel.remove(2);
+        panel.move_to_front(5);
+        panel.insert(3, block(0., 0.));
+        panel.push(block(0., 0.));
+        UserControl::update(panel, canvas, Duration::ZERO, unit, unit).expect("");
+        assert_eq!(panel.iter().count(), 3);
+
+        //Each index is resolved after the changes queued before it.
+        panel.set_z_index(0, 0);
+        panel.set_z_index(1, 1);
+        panel.set_z_index(2, 2);
+        panel.push(block(0., 0.));
+        UserControl::update(panel, canvas, Duration::ZERO, unit, unit).expect("");
+        panel.set_z_index(3, 3);
+        panel.remove(1);
+        panel.remove(2);
+        UserControl::update(panel, canvas, Duration::ZERO, unit, unit).expect("");
+        assert_eq!((panel.z_index(0), panel.z_index(1)), (0, 2));
+
+        //The children pushed through the state manager are placed like the others.
+        panel.state_manager.add(Box::new(|(_, mut subs)| {
+            subs.push(block(0., 0.));
+            Ok(())
+        }));
+        UserControl::update(panel, canvas, Duration::ZERO, unit, unit).expect("");
+        assert_eq!(panel.iter().count(), 3);
+        assert_eq!(panel.z_index(2), 0);
+        assert_eq!(panel[2].surface, FRect::new(10., 20., 100., 40.));
+    }
+
     fn click(
         panel: MutRef<Panel<(), usize, Button>>,
         parent: MutRef<()>,
@@ -0,0 +1,234 @@
+use std::{marker::PhantomData, time::Duration};
+
+use anyhow::{anyhow, Result};
+use sdl2::{rect::FRect, render::Canvas, video::Window};
+
+use crate::{
+    event::Event,
+    functions::{FnColor, FnProgress, FnText},
+    missing::rect::clipped,
+    refs::{MutRef, Ref},
+    ui_element::Orientation,
+    user_control::UserControl,
+    zero,
+};
+
+///How much of the bar the moving band takes when the progress is unknown.
+const BAND: f32 = 0.3;
+
+///Fills itself with the progress, or moves a band across when the progress is unknown.
+pub struct ProgressBar<Parent: 'static, State: 'static> {
+    parent: PhantomData<Parent>,
+    state: PhantomData<State>,
+    surface: FRect,
+    orientation: Orientation,
+    progress: FnProgress<Self, Parent, State>,
+    text: Option<FnText<Self, Parent, State>>,
+    back_color: FnColor<Self, Parent, State>,
+    front_color: FnColor<Self, Parent, State>,
+    ///How long the band takes to cross the bar.
+    period: Duration,
+    ///Where the band is, from 0 to 1.
+    phase: f32,
+}
+
+impl<Parent: 'static, State: 'static> ProgressBar<Parent, State> {
+    pub fn new(
+        progress: FnProgress<Self, Parent, State>,
+        back_color: FnColor<Self, Parent, State>,
+        front_color: FnColor<Self, Parent, State>,
+    ) -> Self {
+        Self {
+            parent: PhantomData,
+            state: PhantomData,
+            surface: zero(),
+            orientation: Orientation::Horizontal,
+            progress,
+            text: None,
+            back_color,
+            front_color,
+            period: Duration::from_millis(1500),
+            phase: 0.,
+        }
+    }
+
+    pub const fn surface(&self) -> FRect {
+        self.surface
+    }
+
+    pub const fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub const fn period(mut self, period: Duration) -> Self {
+        self.period = period;
+        self
+    }
+
+    pub fn text(mut self, text: FnText<Self, Parent, State>) -> Self {
+        self.text = Some(text);
+        self
+    }
+
+    pub fn text_mut(&mut self) -> &mut Option<FnText<Self, Parent, State>> {
+        &mut self.text
+    }
+
+    pub fn progress_mut(&mut self) -> &mut FnProgress<Self, Parent, State> {
+        &mut self.progress
+    }
+
+    ///The part of the surface between two ratios, vertical bars fill from the bottom.
+    fn part(&self, from: f32, to: f32) -> FRect {
+        match self.orientation {
+            Orientation::Horizontal => FRect::new(
+                self.surface.x() + from * self.surface.width(),
+                self.surface.y(),
+                (to - from) * self.surface.width(),
+                self.surface.height(),
+            ),
+            Orientation::Vertical => FRect::new(
+                self.surface.x(),
+                self.surface.y() + (1. - to) * self.surface.height(),
+                self.surface.width(),
+                (to - from) * self.surface.height(),
+            ),
+        }
+    }
+}
+
+impl<Parent: 'static, State: 'static> UserControl<Parent, State> for ProgressBar<Parent, State> {
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        _: &Canvas<Window>,
+        event: Event,
+        _: MutRef<Parent>,
+        _: MutRef<State>,
+    ) -> Result<()> {
+        match event {
+            Event::ElementMove { x, y } => {
+                this.surface.set_x(x);
+                this.surface.set_y(y);
+            }
+            Event::ElementResize { width, height } => {
+                this.surface.set_width(width);
+                this.surface.set_height(height);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn update(
+        mut this: MutRef<Self>,
+        _: &Canvas<Window>,
+        elapsed: Duration,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        if (this.progress)(this.into(), parent.into(), state.into()).is_some() {
+            this.phase = 0.;
+        } else if !this.period.is_zero() {
+            this.phase = (this.phase + elapsed.as_secs_f32() / this.period.as_secs_f32()) % 1.;
+        }
+        Ok(())
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        canvas.set_draw_color((this.back_color)(this, parent, state));
+        canvas.fill_frect(this.surface).map_err(|e| anyhow!(e))?;
+        canvas.set_draw_color((this.front_color)(this, parent, state));
+        match (this.progress)(this, parent, state) {
+            Some(progress) => {
+                canvas
+                    .fill_frect(this.part(0., progress.clamp(0., 1.)))
+                    .map_err(|e| anyhow!(e))?;
+            }
+            None => {
+                //The band enters from one side and leaves by the other.
+                let from = this.phase * (1. + BAND) - BAND;
+                clipped(canvas, this.surface, |canvas| {
+                    canvas
+                        .fill_frect(this.part(from, from + BAND))
+                        .map_err(|e| anyhow!(e))
+                })?;
+            }
+        }
+        if let Some(text) = this.text.as_ref() {
+            if let (Some(text), color) = text(this, parent, state)? {
+                text.draw(canvas, None, this.surface, color)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod progress_bar_test {
+    use sdl2::pixels::Color;
+
+    use super::*;
+
+    pub(crate) fn test_progress_bar(canvas: &mut Canvas<Window>) {
+        let mut progress = None;
+        let mut progress = MutRef::new(&mut progress);
+        let mut parent = ();
+        let parent = MutRef::new(&mut parent);
+        let mut bar = ProgressBar::new(
+            Box::new(|_, _, progress: Ref<Option<f32>>| *progress),
+            Box::new(|_, _, _| Color::BLACK),
+            Box::new(|_, _, _| Color::WHITE),
+        )
+        .period(Duration::from_secs(1));
+        let mut bar = MutRef::new(&mut bar);
+        UserControl::event(
+            bar,
+            canvas,
+            Event::ElementResize {
+                width: 40.,
+                height: 10.,
+            },
+            parent,
+            progress,
+        )
+        .expect("");
+        let update = move |canvas: &Canvas<Window>, millis| {
+            UserControl::update(bar, canvas, Duration::from_millis(millis), parent, progress)
+                .expect("");
+        };
+        assert_eq!(bar.part(0., 0.25), FRect::new(0., 0., 10., 10.));
+
+        //Without progress, the band keeps going around.
+        update(canvas, 250);
+        assert_eq!(bar.phase, 0.25);
+        update(canvas, 500);
+        update(canvas, 500);
+        assert_eq!(bar.phase, 0.25);
+        UserControl::draw(bar.into(), canvas, parent.into(), progress.into()).expect("");
+
+        *progress = Some(0.5);
+        update(canvas, 250);
+        assert_eq!(bar.phase, 0.);
+        UserControl::draw(bar.into(), canvas, parent.into(), progress.into()).expect("");
+
+        *progress = None;
+        bar.period = Duration::ZERO;
+        update(canvas, 250);
+        assert_eq!(bar.phase, 0.);
+
+        //Vertical bars fill from the bottom.
+        bar.orientation = Orientation::Vertical;
+        bar.surface = FRect::new(0., 0., 10., 40.);
+        assert_eq!(bar.part(0., 0.25), FRect::new(0., 30., 10., 10.));
+    }
+}
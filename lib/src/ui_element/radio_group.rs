@@ -0,0 +1,316 @@
+use std::{marker::PhantomData, time::Duration};
+
+use anyhow::{anyhow, Result};
+use sdl2::{
+    gfx::primitives::DrawRenderer,
+    mouse::MouseButton,
+    rect::{FPoint, FRect},
+    render::Canvas,
+    video::Window,
+};
+
+use crate::{
+    event::Event,
+    functions::{FnAction, FnColor, FnState, StateEnum},
+    missing::ui_string::UIString,
+    refs::{MutRef, Ref},
+    ui_element::Orientation,
+    user_control::UserControl,
+    zero,
+};
+
+///Lets the user pick one of its options, each one gets an equal part of the surface.
+pub struct RadioGroup<Parent: 'static, State: 'static> {
+    parent: PhantomData<Parent>,
+    statel: PhantomData<State>,
+    surface: FRect,
+    options: Vec<UIString>,
+    selected: Option<usize>,
+    orientation: Orientation,
+    ///It gets called when the user selects another option.
+    changed: Option<FnAction<Self, Parent, State>>,
+    state: FnState<Self, Parent, State>,
+    back_color: FnColor<Self, Parent, State>,
+    front_color: FnColor<Self, Parent, State>,
+    hover: Option<usize>,
+}
+
+impl<Parent: 'static, State: 'static> RadioGroup<Parent, State> {
+    pub fn new(
+        options: Vec<UIString>,
+        state: FnState<Self, Parent, State>,
+        back_color: FnColor<Self, Parent, State>,
+        front_color: FnColor<Self, Parent, State>,
+    ) -> Self {
+        Self {
+            parent: PhantomData,
+            statel: PhantomData,
+            surface: zero(),
+            options,
+            selected: None,
+            orientation: Orientation::Vertical,
+            changed: None,
+            state,
+            back_color,
+            front_color,
+            hover: None,
+        }
+    }
+
+    pub const fn surface(&self) -> FRect {
+        self.surface
+    }
+
+    pub const fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub const fn selected(mut self, selected: usize) -> Self {
+        self.selected = Some(selected);
+        self
+    }
+
+    pub fn changed(mut self, changed: FnAction<Self, Parent, State>) -> Self {
+        self.changed = Some(changed);
+        self
+    }
+
+    pub fn changed_mut(&mut self) -> &mut Option<FnAction<Self, Parent, State>> {
+        &mut self.changed
+    }
+
+    pub fn state_mut(&mut self) -> &mut FnState<Self, Parent, State> {
+        &mut self.state
+    }
+
+    pub fn options(&self) -> &[UIString] {
+        &self.options
+    }
+
+    pub fn options_mut(&mut self) -> &mut Vec<UIString> {
+        &mut self.options
+    }
+
+    pub const fn get_selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn set_selected(&mut self, selected: Option<usize>) {
+        self.selected = selected.filter(|index| *index < self.options.len());
+    }
+
+    ///The option the mouse is over.
+    pub const fn hover(&self) -> Option<usize> {
+        self.hover
+    }
+
+    fn option_rect(&self, index: usize) -> FRect {
+        let len = self.options.len().max(1) as f32;
+        match self.orientation {
+            Orientation::Horizontal => {
+                let width = self.surface.width() / len;
+                FRect::new(
+                    self.surface.x() + index as f32 * width,
+                    self.surface.y(),
+                    width,
+                    self.surface.height(),
+                )
+            }
+            Orientation::Vertical => {
+                let height = self.surface.height() / len;
+                FRect::new(
+                    self.surface.x(),
+                    self.surface.y() + index as f32 * height,
+                    self.surface.width(),
+                    height,
+                )
+            }
+        }
+    }
+
+    fn option_at(&self, x: f32, y: f32) -> Option<usize> {
+        (0..self.options.len())
+            .find(|index| self.option_rect(*index).contains_point(FPoint::new(x, y)))
+    }
+}
+
+impl<Parent: 'static, State: 'static> UserControl<Parent, State> for RadioGroup<Parent, State> {
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        if let Event::ElementMove { x, y } = event {
+            this.surface.set_x(x);
+            this.surface.set_y(y);
+            return Ok(());
+        }
+        if let Event::ElementResize { width, height } = event {
+            this.surface.set_width(width);
+            this.surface.set_height(height);
+            return Ok(());
+        }
+        if (this.state)(this.into(), parent.into(), state.into()) != StateEnum::Enable {
+            return Ok(());
+        }
+        match event {
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => {
+                if let Some(index) = this.option_at(x, y) {
+                    if this.selected != Some(index) {
+                        this.selected = Some(index);
+                        let t = this;
+                        if let Some(changed) = this.changed.as_mut() {
+                            changed(t, parent, state, canvas)?;
+                        }
+                    }
+                }
+            }
+            Event::MouseMotion { x, y, .. } => this.hover = this.option_at(x, y),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn update(
+        _: MutRef<Self>,
+        _: &Canvas<Window>,
+        _: Duration,
+        _: MutRef<Parent>,
+        _: MutRef<State>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        if (this.state)(this, parent, state) == StateEnum::Hidden {
+            return Ok(());
+        }
+        canvas.set_draw_color((this.back_color)(this, parent, state));
+        canvas.fill_frect(this.surface).map_err(|e| anyhow!(e))?;
+        let front_color = (this.front_color)(this, parent, state);
+        for (index, option) in this.options.iter().enumerate() {
+            let rect = this.option_rect(index);
+            let size = rect.height().min(rect.width());
+            let (x, y) = (rect.x() + size / 2., rect.y() + rect.height() / 2.);
+            canvas
+                .aa_circle(x as i16, y as i16, (size * 0.3) as i16, front_color)
+                .map_err(|e| anyhow!(e))?;
+            if this.selected == Some(index) {
+                canvas
+                    .filled_circle(x as i16, y as i16, (size * 0.18) as i16, front_color)
+                    .map_err(|e| anyhow!(e))?;
+            }
+            option.draw_left(
+                canvas,
+                FRect::new(
+                    rect.x() + size,
+                    rect.y(),
+                    (rect.width() - size).max(0.),
+                    rect.height(),
+                ),
+                front_color,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod radio_group_test {
+    use sdl2::pixels::Color;
+
+    use super::*;
+
+    pub(crate) fn test_radio_group_select(canvas: &mut Canvas<Window>) {
+        let mut changes = 0_usize;
+        let changes = MutRef::new(&mut changes);
+        let mut enabled = true;
+        let mut enabled = MutRef::new(&mut enabled);
+        let mut radio = RadioGroup::new(
+            vec![
+                UIString::unmeasured("a"),
+                UIString::unmeasured("b"),
+                UIString::unmeasured("c"),
+            ],
+            Box::new(|_, enabled: Ref<bool>, _| {
+                if *enabled {
+                    StateEnum::Enable
+                } else {
+                    StateEnum::Showing
+                }
+            }),
+            Box::new(|_, _, _| Color::BLACK),
+            Box::new(|_, _, _| Color::WHITE),
+        )
+        .orientation(Orientation::Horizontal)
+        .changed(Box::new(|_, _, mut changes: MutRef<usize>, _| {
+            *changes += 1;
+            Ok(())
+        }));
+        let mut radio = MutRef::new(&mut radio);
+        let canvas = &*canvas;
+        let send =
+            move |event| UserControl::event(radio, canvas, event, enabled, changes).expect("");
+        let click = |x| Event::MouseButtonDown {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x,
+            y: 5.,
+        };
+        let motion = |x| Event::MouseMotion {
+            which: 0,
+            mousestate: sdl2::mouse::MouseState::from_sdl_state(0),
+            x,
+            y: 5.,
+            moved_x: 0.,
+            moved_y: 0.,
+        };
+        send(Event::ElementResize {
+            width: 90.,
+            height: 10.,
+        });
+
+        send(click(45.));
+        assert_eq!(radio.get_selected(), Some(1));
+        send(click(50.));
+        assert_eq!(*changes, 1);
+        send(click(80.));
+        assert_eq!(radio.get_selected(), Some(2));
+        send(click(100.));
+        assert_eq!(radio.get_selected(), Some(2));
+        assert_eq!(*changes, 2);
+        send(motion(5.));
+        assert_eq!(radio.hover(), Some(0));
+        send(motion(100.));
+        assert_eq!(radio.hover(), None);
+
+        *enabled = false;
+        send(click(5.));
+        assert_eq!(radio.get_selected(), Some(2));
+        assert_eq!(*changes, 2);
+
+        radio.set_selected(Some(3));
+        assert_eq!(radio.get_selected(), None);
+        radio.set_selected(Some(0));
+        assert_eq!(radio.get_selected(), Some(0));
+        assert_eq!(*changes, 2);
+    }
+}
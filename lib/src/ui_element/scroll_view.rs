@@ -1,22 +1,103 @@
-use std::{marker::PhantomData, time::Duration};
+use std::{cell::Cell, collections::VecDeque, marker::PhantomData, time::Duration};
 
 use anyhow::{anyhow, Result};
 use sdl2::{
+    keyboard::Keycode,
     mouse::MouseButton,
+    pixels::Color,
     rect::{FPoint, FRect},
-    render::{BlendMode, Canvas},
+    render::Canvas,
     video::Window,
 };
 
 use crate::{
-    event::Event,
-    functions::FnColor,
-    missing::rect::as_rect,
+    event::{mark_handled, take_handled, Event},
+    functions::{FnAction, FnColor},
+    missing::rect::clipped,
     refs::{MutRef, Ref},
     user_control::UserControl,
     zero,
 };
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollBarPlacement {
+    ///The scroll bars are drawn over the child.
+    Overlay,
+    ///The child's view shrinks so the scroll bars never cover it.
+    Gutter,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ScrollBarStyle {
+    pub thickness: f32,
+    pub min_thumb_size: f32,
+    ///The track is not drawn when None.
+    pub track_color: Option<Color>,
+    ///Uses the scroll color when None.
+    pub thumb_hover_color: Option<Color>,
+    ///Uses the scroll color when None.
+    pub thumb_pressed_color: Option<Color>,
+    ///Only shows the scroll bars while the mouse is over the ScrollView.
+    pub auto_hide: bool,
+    pub placement: ScrollBarPlacement,
+}
+
+impl Default for ScrollBarStyle {
+    fn default() -> Self {
+        Self {
+            thickness: 30.,
+            min_thumb_size: 30.,
+            track_color: None,
+            thumb_hover_color: None,
+            thumb_pressed_color: None,
+            auto_hide: false,
+            placement: ScrollBarPlacement::Overlay,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ScrollBehavior {
+    ///Pixels scrolled for each mouse wheel notch.
+    pub wheel_step: f32,
+    ///Animates the mouse wheel scrolling instead of jumping to the new position.
+    pub smooth: bool,
+    ///How fast the smooth scrolling catches up to its target, higher is faster.
+    pub smooth_speed: f32,
+    ///Keeps scrolling with inertia after a touch drag is released.
+    pub kinetic: bool,
+    ///How fast the kinetic scrolling slows down, higher stops sooner.
+    pub friction: f32,
+}
+
+impl Default for ScrollBehavior {
+    fn default() -> Self {
+        Self {
+            wheel_step: 40.,
+            smooth: true,
+            smooth_speed: 15.,
+            kinetic: true,
+            friction: 4.,
+        }
+    }
+}
+
+///How far back the touch drags count for the speed of the kinetic scrolling.
+const VELOCITY_WINDOW: Duration = Duration::from_millis(100);
+
+thread_local! {
+    ///The mouse wheel scrolling a nested ScrollView couldn't use.
+    static WHEEL_LEFT: Cell<Option<(f32, f32)>> = const { Cell::new(None) };
+}
+
+fn ratio(px: f32, range: f32) -> f32 {
+    if range > 0. {
+        (px / range).clamp(0., 1.)
+    } else {
+        0.
+    }
+}
+
 ///Let you have an unrestrained sized sub element inside your restrained sized Window/SubElement.
 pub struct ScrollView<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
 {
@@ -26,10 +107,29 @@ pub struct ScrollView<Parent: 'static, State: 'static, Child: UserControl<Parent
     child_size: (f32, f32),
     child_surface: FRect,
     scroll_color: FnColor<Self, Parent, State>,
+    style: ScrollBarStyle,
+    ///It gets called on the next update after the scroll position changed.
+    scroll_changed: Option<FnAction<Self, Parent, State>>,
+    last_scroll: (f32, f32),
+    behavior: ScrollBehavior,
+    ///Where the smooth scrolling is heading to in pixels.
+    target: (Option<f32>, Option<f32>),
+    ///Kinetic scrolling speed in pixels per second.
+    velocity: (f32, f32),
+    touch: Option<i64>,
+    ///Dragged by the touch since the last update.
+    dragged: (f32, f32),
+    ///The last drags in seconds and pixels, the kinetic scrolling starts at their average speed.
+    drags: VecDeque<(f32, (f32, f32))>,
+    ///Set by clicking inside the ScrollView, lets the keyboard scroll it.
+    focused: bool,
     v_scroll: f32,
     h_scroll: f32,
     v_selected: bool,
     h_selected: bool,
+    hover: bool,
+    v_hover: bool,
+    h_hover: bool,
 }
 
 impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
@@ -48,10 +148,372 @@ impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'stati
             child_size: (child_width, child_height),
             child_surface: zero(),
             scroll_color,
+            style: ScrollBarStyle::default(),
+            scroll_changed: None,
+            last_scroll: (0., 0.),
+            behavior: ScrollBehavior::default(),
+            target: (None, None),
+            velocity: (0., 0.),
+            touch: None,
+            dragged: (0., 0.),
+            drags: VecDeque::new(),
+            focused: false,
             h_scroll: 0.,
             v_scroll: 0.,
             h_selected: false,
             v_selected: false,
+            hover: false,
+            v_hover: false,
+            h_hover: false,
+        }
+    }
+
+    pub const fn style(mut self, style: ScrollBarStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub const fn get_style(&self) -> &ScrollBarStyle {
+        &self.style
+    }
+
+    pub fn style_mut(&mut self) -> &mut ScrollBarStyle {
+        &mut self.style
+    }
+
+    pub const fn behavior(mut self, behavior: ScrollBehavior) -> Self {
+        self.behavior = behavior;
+        self
+    }
+
+    pub const fn get_behavior(&self) -> &ScrollBehavior {
+        &self.behavior
+    }
+
+    pub fn behavior_mut(&mut self) -> &mut ScrollBehavior {
+        &mut self.behavior
+    }
+
+    pub fn scroll_changed(mut self, scroll_changed: FnAction<Self, Parent, State>) -> Self {
+        self.scroll_changed = Some(scroll_changed);
+        self
+    }
+
+    pub fn scroll_changed_mut(&mut self) -> &mut Option<FnAction<Self, Parent, State>> {
+        &mut self.scroll_changed
+    }
+
+    ///Horizontal scroll position from 0 (left) to 1 (right).
+    pub const fn h_scroll(&self) -> f32 {
+        self.h_scroll
+    }
+
+    ///Vertical scroll position from 0 (top) to 1 (bottom).
+    pub const fn v_scroll(&self) -> f32 {
+        self.v_scroll
+    }
+
+    ///Jumps to the position, stopping any smooth or kinetic scrolling.
+    pub fn set_h_scroll(&mut self, ratio: f32) {
+        self.h_scroll = ratio.clamp(0., 1.);
+        self.target.0 = None;
+        self.velocity.0 = 0.;
+    }
+
+    ///Jumps to the position, stopping any smooth or kinetic scrolling.
+    pub fn set_v_scroll(&mut self, ratio: f32) {
+        self.v_scroll = ratio.clamp(0., 1.);
+        self.target.1 = None;
+        self.velocity.1 = 0.;
+    }
+
+    ///How many pixels the child can be scrolled horizontally and vertically.
+    pub fn scroll_range(&self) -> (f32, f32) {
+        let view = self.view();
+        let (width, height) = self.content_size();
+        (width - view.width(), height - view.height())
+    }
+
+    ///Horizontal scroll position in pixels from the left of the child.
+    pub fn h_scroll_px(&self) -> f32 {
+        self.h_scroll * self.scroll_range().0
+    }
+
+    ///Vertical scroll position in pixels from the top of the child.
+    pub fn v_scroll_px(&self) -> f32 {
+        self.v_scroll * self.scroll_range().1
+    }
+
+    pub fn set_h_scroll_px(&mut self, px: f32) {
+        let range = self.scroll_range().0;
+        self.set_h_scroll(ratio(px, range));
+    }
+
+    pub fn set_v_scroll_px(&mut self, px: f32) {
+        let range = self.scroll_range().1;
+        self.set_v_scroll(ratio(px, range));
+    }
+
+    ///Scrolls as little as possible to make the region visible.</br>
+    ///The region is relative to the top left of the child.
+    pub fn scroll_to_rect(&mut self, region: FRect) {
+        let view = self.view();
+        let (x, y) = (self.h_scroll_px(), self.v_scroll_px());
+        if region.x() < x || region.width() > view.width() {
+            self.set_h_scroll_px(region.x());
+        } else if region.x() + region.width() > x + view.width() {
+            self.set_h_scroll_px(region.x() + region.width() - view.width());
+        }
+        if region.y() < y || region.height() > view.height() {
+            self.set_v_scroll_px(region.y());
+        } else if region.y() + region.height() > y + view.height() {
+            self.set_v_scroll_px(region.y() + region.height() - view.height());
+        }
+    }
+
+    ///Scrolls by pixels, animated when the behavior is smooth.
+    pub fn scroll_by(&mut self, dx: f32, dy: f32) {
+        if !self.behavior.smooth {
+            if dx != 0. {
+                self.set_h_scroll_px(self.h_scroll_px() + dx);
+            }
+            if dy != 0. {
+                self.set_v_scroll_px(self.v_scroll_px() + dy);
+            }
+            return;
+        }
+        let (h_range, v_range) = self.scroll_range();
+        if dx != 0. {
+            let from = self.target.0.unwrap_or(self.h_scroll_px());
+            self.target.0 = Some((from + dx).clamp(0., h_range.max(0.)));
+            self.velocity.0 = 0.;
+        }
+        if dy != 0. {
+            let from = self.target.1.unwrap_or(self.v_scroll_px());
+            self.target.1 = Some((from + dy).clamp(0., v_range.max(0.)));
+            self.velocity.1 = 0.;
+        }
+    }
+
+    pub const fn focused(&self) -> bool {
+        self.focused
+    }
+
+    ///Where the scroll position is, or is heading to, in pixels.
+    fn scroll_pos(&self) -> (f32, f32) {
+        (
+            self.target.0.unwrap_or(self.h_scroll_px()),
+            self.target.1.unwrap_or(self.v_scroll_px()),
+        )
+    }
+
+    ///Limits the scrolling to what is left before hitting the edges.
+    fn absorb(&self, dx: f32, dy: f32, h_bar: bool, v_bar: bool) -> (f32, f32) {
+        let (h_pos, v_pos) = self.scroll_pos();
+        let (h_range, v_range) = self.scroll_range();
+        (
+            if h_bar {
+                dx.clamp(-h_pos, h_range - h_pos)
+            } else {
+                0.
+            },
+            if v_bar {
+                dy.clamp(-v_pos, v_range - v_pos)
+            } else {
+                0.
+            },
+        )
+    }
+
+    ///Scrolls by the mouse wheel notches and returns the notches it couldn't use.
+    fn wheel(&mut self, scroll_x: f32, scroll_y: f32, h_bar: bool, v_bar: bool) -> (f32, f32) {
+        let step = self.behavior.wheel_step;
+        if step == 0. {
+            return (scroll_x, scroll_y);
+        }
+        let (dx, dy) = self.absorb(-scroll_x * step, -scroll_y * step, h_bar, v_bar);
+        self.scroll_by(dx, dy);
+        (scroll_x + dx / step, scroll_y + dy / step)
+    }
+
+    ///Scrolls with the arrows, page up/down, home and end keys, returns if it scrolled.
+    fn key_scroll(&mut self, keycode: Keycode, h_bar: bool, v_bar: bool) -> bool {
+        let step = self.behavior.wheel_step;
+        let view = self.view();
+        let (_, v_pos) = self.scroll_pos();
+        let (_, v_range) = self.scroll_range();
+        let (dx, dy) = match keycode {
+            Keycode::Up => (0., -step),
+            Keycode::Down => (0., step),
+            Keycode::Left => (-step, 0.),
+            Keycode::Right => (step, 0.),
+            Keycode::PageUp => (0., -view.height()),
+            Keycode::PageDown => (0., view.height()),
+            Keycode::Home => (0., -v_pos),
+            Keycode::End => (0., v_range - v_pos),
+            _ => return false,
+        };
+        let (dx, dy) = self.absorb(dx, dy, h_bar, v_bar);
+        if dx == 0. && dy == 0. {
+            return false;
+        }
+        self.scroll_by(dx, dy);
+        true
+    }
+
+    ///Sends the event to the child, keeping mouse positions outside of the view outside of it.
+    fn forward(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        UserControl::event(
+            MutRef::new(&mut this.child),
+            canvas,
+            match event {
+                Event::MouseMotion {
+                    which,
+                    mousestate,
+                    x,
+                    y,
+                    moved_x,
+                    moved_y,
+                } => {
+                    let (x, y) = this.offset_event(x, y);
+                    Event::MouseMotion {
+                        which,
+                        mousestate,
+                        x,
+                        y,
+                        moved_x,
+                        moved_y,
+                    }
+                }
+                Event::MouseButtonDown {
+                    which,
+                    mouse_btn,
+                    clicks,
+                    x,
+                    y,
+                } => {
+                    let (x, y) = this.offset_event(x, y);
+                    Event::MouseButtonDown {
+                        which,
+                        mouse_btn,
+                        clicks,
+                        x,
+                        y,
+                    }
+                }
+                Event::MouseButtonUp {
+                    which,
+                    mouse_btn,
+                    clicks,
+                    x,
+                    y,
+                } => {
+                    let (x, y) = this.offset_event(x, y);
+                    Event::MouseButtonUp {
+                        which,
+                        mouse_btn,
+                        clicks,
+                        x,
+                        y,
+                    }
+                }
+                Event::MouseWheel {
+                    which,
+                    scroll_x,
+                    scroll_y,
+                    direction,
+                    mouse_x,
+                    mouse_y,
+                } => {
+                    let (mouse_x, mouse_y) = this.offset_event(mouse_x, mouse_y);
+                    Event::MouseWheel {
+                        which,
+                        scroll_x,
+                        scroll_y,
+                        direction,
+                        mouse_x,
+                        mouse_y,
+                    }
+                }
+                event => event,
+            },
+            parent,
+            state,
+        )
+    }
+
+    ///Advances the smooth and kinetic scrolling.
+    fn animate(&mut self, elapsed: Duration) {
+        let dt = elapsed.as_secs_f32();
+        if self.touch.is_some() {
+            //A frame without motion right before the release doesn't stop the kinetic scrolling.
+            self.drags.push_back((dt, self.dragged));
+            self.dragged = (0., 0.);
+            let window = VELOCITY_WINDOW.as_secs_f32();
+            while self.drags.iter().skip(1).map(|(dt, _)| dt).sum::<f32>() >= window {
+                self.drags.pop_front();
+            }
+            let time: f32 = self.drags.iter().map(|(dt, _)| dt).sum();
+            if time > 0. {
+                let (x, y) = self
+                    .drags
+                    .iter()
+                    .fold((0., 0.), |(x, y), (_, (dx, dy))| (x + dx, y + dy));
+                self.velocity = (x / time, y / time);
+            }
+            return;
+        }
+        let (h_range, v_range) = self.scroll_range();
+        if self.behavior.kinetic && dt > 0. {
+            let decay = (-self.behavior.friction * dt).exp();
+            if self.velocity.0 != 0. {
+                let px = self.h_scroll_px() + self.velocity.0 * dt;
+                self.h_scroll = ratio(px, h_range);
+                self.velocity.0 *= decay;
+                if self.velocity.0.abs() < 1. || px <= 0. || px >= h_range {
+                    self.velocity.0 = 0.;
+                }
+            }
+            if self.velocity.1 != 0. {
+                let px = self.v_scroll_px() + self.velocity.1 * dt;
+                self.v_scroll = ratio(px, v_range);
+                self.velocity.1 *= decay;
+                if self.velocity.1.abs() < 1. || px <= 0. || px >= v_range {
+                    self.velocity.1 = 0.;
+                }
+            }
+        } else {
+            self.velocity = (0., 0.);
+        }
+        //Without elapsed time, like in run_event, it jumps straight to the target.
+        let step = if dt > 0. {
+            1. - (-self.behavior.smooth_speed * dt).exp()
+        } else {
+            1.
+        };
+        if let Some(target) = self.target.0 {
+            let px = self.h_scroll_px();
+            let px = px + (target - px) * step;
+            self.h_scroll = ratio(px, h_range);
+            if (target - px).abs() < 0.5 {
+                self.h_scroll = ratio(target, h_range);
+                self.target.0 = None;
+            }
+        }
+        if let Some(target) = self.target.1 {
+            let px = self.v_scroll_px();
+            let px = px + (target - px) * step;
+            self.v_scroll = ratio(px, v_range);
+            if (target - px).abs() < 0.5 {
+                self.v_scroll = ratio(target, v_range);
+                self.target.1 = None;
+            }
         }
     }
 
@@ -71,46 +533,123 @@ impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'stati
         &mut self.child_size
     }
 
+    ///Which of the horizontal and vertical scroll bars are needed.
+    fn bars(&self) -> (bool, bool) {
+        let gutter = match self.style.placement {
+            ScrollBarPlacement::Overlay => 0.,
+            ScrollBarPlacement::Gutter => self.style.thickness,
+        };
+        let mut v_bar = self.child_size.1 > self.surface.height();
+        let h_bar = self.child_size.0 > self.surface.width() - if v_bar { gutter } else { 0. };
+        if h_bar && !v_bar {
+            v_bar = self.child_size.1 > self.surface.height() - gutter;
+        }
+        (h_bar, v_bar)
+    }
+
+    ///The part of the surface where the child is visible.
+    fn view(&self) -> FRect {
+        if self.style.placement == ScrollBarPlacement::Overlay {
+            return self.surface;
+        }
+        let (h_bar, v_bar) = self.bars();
+        FRect::new(
+            self.surface.x(),
+            self.surface.y(),
+            self.surface.width() - if v_bar { self.style.thickness } else { 0. },
+            self.surface.height() - if h_bar { self.style.thickness } else { 0. },
+        )
+    }
+
+    ///The size the child is laid out with, never smaller than the view.
+    fn content_size(&self) -> (f32, f32) {
+        let view = self.view();
+        (
+            self.child_size.0.max(view.width()),
+            self.child_size.1.max(view.height()),
+        )
+    }
+
+    ///Keeps positions outside of the view outside of the child so hidden parts can't be hovered.
     fn offset_event(&self, x: f32, y: f32) -> (f32, f32) {
+        let view = self.view();
         (
-            if self.surface.x() > x {
-                x - self.surface.x()
-            } else if self.surface.x() + self.surface.width() < x {
-                self.child_size.0 + x - self.surface.x() - self.surface.width()
+            if view.x() > x {
+                self.child_surface.x() + x - view.x()
+            } else if view.x() + view.width() < x {
+                self.child_surface.x() + self.child_surface.width() + x - view.x() - view.width()
             } else {
-                (x - self.surface.x()) / self.surface.width() * self.child_surface.width()
-                    + self.child_surface.x()
+                x
             },
-            if self.surface.y() > y {
-                y - self.surface.y()
-            } else if self.surface.y() + self.surface.height() < y {
-                self.child_size.1 + y - self.surface.y() - self.surface.height()
+            if view.y() > y {
+                self.child_surface.y() + y - view.y()
+            } else if view.y() + view.height() < y {
+                self.child_surface.y() + self.child_surface.height() + y - view.y() - view.height()
             } else {
-                (y - self.surface.y()) / self.surface.height() * self.child_surface.height()
-                    + self.child_surface.y()
+                y
             },
         )
     }
 
-    fn h_scroll(&self) -> FRect {
-        let w = (2. * self.surface.width() - self.child_size.0).max(30.);
+    fn h_track(&self) -> FRect {
+        let view = self.view();
+        FRect::new(
+            view.x(),
+            self.surface.y() + self.surface.height() - self.style.thickness,
+            view.width(),
+            self.style.thickness,
+        )
+    }
+
+    fn v_track(&self) -> FRect {
+        let view = self.view();
+        FRect::new(
+            self.surface.x() + self.surface.width() - self.style.thickness,
+            view.y(),
+            self.style.thickness,
+            view.height(),
+        )
+    }
+
+    fn h_thumb(&self) -> FRect {
+        let track = self.h_track();
+        let w = (2. * track.width() - self.child_size.0)
+            .max(self.style.min_thumb_size)
+            .min(track.width());
         FRect::new(
-            self.surface.x() + self.h_scroll * (self.surface.width() - w),
-            self.surface.y() + self.surface.height() - 30.,
+            track.x() + self.h_scroll * (track.width() - w),
+            track.y(),
             w,
-            30.,
+            track.height(),
         )
     }
 
-    fn v_scroll(&self) -> FRect {
-        let h = (2. * self.surface.height() - self.child_size.1).max(30.);
+    fn v_thumb(&self) -> FRect {
+        let track = self.v_track();
+        let h = (2. * track.height() - self.child_size.1)
+            .max(self.style.min_thumb_size)
+            .min(track.height());
         FRect::new(
-            self.surface.x() + self.surface.width() - 30.,
-            self.surface.y() + self.v_scroll * (self.surface.height() - h),
-            30.,
+            track.x(),
+            track.y() + self.v_scroll * (track.height() - h),
+            track.width(),
             h,
         )
     }
+
+    const fn bars_visible(&self) -> bool {
+        !self.style.auto_hide || self.hover || self.h_selected || self.v_selected
+    }
+
+    fn thumb_color(&self, selected: bool, hover: bool, color: Color) -> Color {
+        if selected {
+            self.style.thumb_pressed_color.unwrap_or(color)
+        } else if hover {
+            self.style.thumb_hover_color.unwrap_or(color)
+        } else {
+            color
+        }
+    }
 }
 
 impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
@@ -140,14 +679,59 @@ impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'stati
             return Ok(());
         }
 
-        if this.child_size.0 > this.surface.width() {
-            let h_scroll = this.h_scroll();
+        let (h_bar, v_bar) = this.bars();
+        if let Event::MouseMotion { x, y, .. } = event {
+            let point = FPoint::new(x, y);
+            this.hover = this.surface.contains_point(point);
+            this.h_hover = h_bar && this.h_thumb().contains_point(point);
+            this.v_hover = v_bar && this.v_thumb().contains_point(point);
+        }
+        if let Event::MouseButtonDown { x, y, .. } = event {
+            this.focused = this.surface.contains_point(FPoint::new(x, y));
+        }
+        match event {
+            Event::FingerDown {
+                finger_id, x, y, ..
+            } => {
+                let (width, height) = canvas.window().size();
+                let point = FPoint::new(x * width as f32, y * height as f32);
+                if this.touch.is_none() && this.view().contains_point(point) {
+                    this.touch = Some(finger_id);
+                    this.target = (None, None);
+                    this.velocity = (0., 0.);
+                    this.dragged = (0., 0.);
+                    this.drags.clear();
+                }
+            }
+            Event::FingerMotion {
+                finger_id, dx, dy, ..
+            } if this.touch == Some(finger_id) => {
+                let (width, height) = canvas.window().size();
+                let (dx, dy) = (
+                    if h_bar { -dx * width as f32 } else { 0. },
+                    if v_bar { -dy * height as f32 } else { 0. },
+                );
+                let (h_px, v_px) = (this.h_scroll_px(), this.v_scroll_px());
+                let (h_range, v_range) = this.scroll_range();
+                this.h_scroll = ratio(h_px + dx, h_range);
+                this.v_scroll = ratio(v_px + dy, v_range);
+                this.dragged.0 += dx;
+                this.dragged.1 += dy;
+            }
+            Event::FingerUp { finger_id, .. } if this.touch == Some(finger_id) => {
+                this.touch = None;
+            }
+            _ => {}
+        }
+        if h_bar {
+            let track = this.h_track();
+            let thumb = this.h_thumb();
             match event {
                 Event::MouseMotion { mousestate, x, .. } => {
                     if mousestate.left() && this.h_selected {
-                        this.h_scroll = ((x - this.surface.x() - h_scroll.width() / 2.)
-                            / (this.surface.width() - h_scroll.width()))
-                        .clamp(0., 1.);
+                        this.set_h_scroll(
+                            (x - track.x() - thumb.width() / 2.) / (track.width() - thumb.width()),
+                        );
                         return Ok(());
                     }
                 }
@@ -157,7 +741,8 @@ impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'stati
                     y,
                     ..
                 } => {
-                    this.h_selected = h_scroll.contains_point(FPoint::new(x, y));
+                    this.h_selected =
+                        this.bars_visible() && thumb.contains_point(FPoint::new(x, y));
                     if this.h_selected {
                         return Ok(());
                     }
@@ -166,14 +751,16 @@ impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'stati
                 _ => {}
             }
         }
-        if this.child_size.1 > this.surface.height() {
-            let v_scroll = this.v_scroll();
+        if v_bar {
+            let track = this.v_track();
+            let thumb = this.v_thumb();
             match event {
                 Event::MouseMotion { mousestate, y, .. } => {
                     if mousestate.left() && this.v_selected {
-                        this.v_scroll = ((y - this.surface.y() - v_scroll.height() / 2.)
-                            / (this.surface.height() - v_scroll.height()))
-                        .clamp(0., 1.);
+                        this.set_v_scroll(
+                            (y - track.y() - thumb.height() / 2.)
+                                / (track.height() - thumb.height()),
+                        );
                         return Ok(());
                     }
                 }
@@ -183,7 +770,8 @@ impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'stati
                     y,
                     ..
                 } => {
-                    this.v_selected = v_scroll.contains_point(FPoint::new(x, y));
+                    this.v_selected =
+                        this.bars_visible() && thumb.contains_point(FPoint::new(x, y));
                     if this.v_selected {
                         return Ok(());
                     }
@@ -192,90 +780,35 @@ impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'stati
                 _ => {}
             }
         }
-        UserControl::event(
-            MutRef::new(&mut this.child),
-            canvas,
-            match event {
-                Event::MouseMotion {
-                    which,
-                    mousestate,
-                    x,
-                    y,
-                    moved_x,
-                    moved_y,
-                } => {
-                    let (x, y) = this.offset_event(x, y);
-                    Event::MouseMotion {
-                        which,
-                        mousestate,
-                        x,
-                        y,
-                        moved_x,
-                        moved_y,
-                    }
-                }
-                Event::MouseButtonDown {
-                    which,
-                    mouse_btn,
-                    clicks,
-                    x,
-                    y,
-                } => {
-                    let (x, y) = this.offset_event(x, y);
-                    Event::MouseButtonDown {
-                        which,
-                        mouse_btn,
-                        clicks,
-                        x,
-                        y,
-                    }
-                }
-                Event::MouseButtonUp {
-                    which,
-                    mouse_btn,
-                    clicks,
-                    x,
-                    y,
-                } => {
-                    let (x, y) = this.offset_event(x, y);
-                    Event::MouseButtonUp {
-                        which,
-                        mouse_btn,
-                        clicks,
-                        x,
-                        y,
-                    }
-                }
-                Event::MouseWheel {
-                    which,
-                    scroll_x,
-                    scroll_y,
-                    direction,
-                    mouse_x,
-                    mouse_y,
-                } => {
-                    if this.child_size.0 > this.surface.width() {
-                        this.h_scroll = (this.h_scroll - scroll_x * 0.1).clamp(0., 1.);
-                    }
-                    if this.child_size.1 > this.surface.height() {
-                        this.v_scroll = (this.v_scroll - scroll_y * 0.1).clamp(0., 1.);
-                    }
-
-                    let (mouse_x, mouse_y) = this.offset_event(mouse_x, mouse_y);
-                    Event::MouseWheel {
-                        which,
-                        scroll_x,
-                        scroll_y,
-                        direction,
-                        mouse_x,
-                        mouse_y,
-                    }
+        match event {
+            Event::MouseWheel {
+                scroll_x,
+                scroll_y,
+                mouse_x,
+                mouse_y,
+                ..
+            } if this.surface.contains_point(FPoint::new(mouse_x, mouse_y)) => {
+                //The innermost ScrollView scrolls first and passes on what it couldn't use.
+                WHEEL_LEFT.set(None);
+                Self::forward(this, canvas, event, parent, state)?;
+                let (scroll_x, scroll_y) = WHEEL_LEFT.take().unwrap_or((scroll_x, scroll_y));
+                let left = this.wheel(scroll_x, scroll_y, h_bar, v_bar);
+                WHEEL_LEFT.set(Some(left));
+                Ok(())
+            }
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } if this.focused => {
+                take_handled();
+                Self::forward(this, canvas, event, parent, state)?;
+                if take_handled() || this.key_scroll(keycode, h_bar, v_bar) {
+                    mark_handled();
                 }
-                event => event,
-            },
-            parent,
-            state,
-        )
+                Ok(())
+            }
+            event => Self::forward(this, canvas, event, parent, state),
+        }
     }
 
     fn update(
@@ -285,36 +818,51 @@ impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'stati
         parent: MutRef<Parent>,
         state: MutRef<State>,
     ) -> Result<()> {
+        this.animate(elapsed);
+        let (width, height) = this.content_size();
+        let view = this.view();
+        this.child_surface = FRect::new(
+            view.x() - this.h_scroll * (width - view.width()),
+            view.y() - this.v_scroll * (height - view.height()),
+            width,
+            height,
+        );
+        let child_surface = this.child_surface;
         let surface = Child::surface(Ref::new(&this.child), parent.into(), state.into());
-        if 0. != surface.x() || 0. != surface.y() {
+        if child_surface.x() != surface.x() || child_surface.y() != surface.y() {
             Child::event(
                 MutRef::new(&mut this.child),
                 canvas,
-                Event::ElementMove { x: 0., y: 0. },
+                Event::ElementMove {
+                    x: child_surface.x(),
+                    y: child_surface.y(),
+                },
                 parent,
                 state,
             )?;
         }
-        if this.child_size.0 != surface.width() || this.child_size.1 != surface.height() {
+        if child_surface.width() != surface.width() || child_surface.height() != surface.height() {
             Child::event(
                 MutRef::new(&mut this.child),
                 canvas,
                 Event::ElementResize {
-                    width: this.child_size.0,
-                    height: this.child_size.1,
+                    width: child_surface.width(),
+                    height: child_surface.height(),
                 },
                 parent,
                 state,
             )?;
         }
-        let (a, b) = this.child_size;
-        let s = this.surface;
-        let (h, v) = (this.h_scroll, this.v_scroll);
-        this.child_surface.set_width(s.width().min(a));
-        this.child_surface.set_height(s.height().min(b));
-        this.child_surface.set_x(h * (a - s.width()));
-        this.child_surface.set_y(v * (b - s.height()));
-        Child::update(MutRef::new(&mut this.child), canvas, elapsed, parent, state)
+        Child::update(MutRef::new(&mut this.child), canvas, elapsed, parent, state)?;
+        let scroll = (this.h_scroll, this.v_scroll);
+        if scroll != this.last_scroll {
+            this.last_scroll = scroll;
+            let t = this;
+            if let Some(scroll_changed) = this.scroll_changed.as_mut() {
+                scroll_changed(t, parent, state, canvas)?;
+            }
+        }
+        Ok(())
     }
 
     fn draw(
@@ -323,29 +871,468 @@ impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'stati
         parent: Ref<Parent>,
         state: Ref<State>,
     ) -> Result<()> {
-        let tc = canvas.texture_creator();
-        let mut sub = tc
-            .create_texture_target(None, this.child_size.0 as u32, this.child_size.1 as u32)
-            .map_err(|e| anyhow!(e))?;
-        sub.set_blend_mode(BlendMode::Blend);
-        let mut success = Ok(());
-        canvas
-            .with_texture_canvas(&mut sub, |sub| {
-                success = Child::draw(Ref::new(&this.child), sub, parent, state)
-            })
-            .map_err(|e| anyhow!(e))?;
-        success?;
-        canvas
-            .copy_f(&sub, Some(as_rect(this.child_surface)), this.surface)
-            .map_err(|e| anyhow!(e))?;
+        //The child keeps its real position, only the visible part of it gets drawn.
+        clipped(canvas, this.view(), |canvas| {
+            Child::draw(Ref::new(&this.child), canvas, parent, state)
+        })?;
+        if !this.bars_visible() {
+            return Ok(());
+        }
         let color = this.scroll_color.as_ref()(this, parent, state);
-        canvas.set_draw_color(color);
-        if this.child_size.0 > this.surface.width() {
-            canvas.fill_frect(this.h_scroll()).map_err(|e| anyhow!(e))?;
+        let (h_bar, v_bar) = this.bars();
+        if h_bar {
+            if let Some(track_color) = this.style.track_color {
+                canvas.set_draw_color(track_color);
+                canvas.fill_frect(this.h_track()).map_err(|e| anyhow!(e))?;
+            }
+            canvas.set_draw_color(this.thumb_color(this.h_selected, this.h_hover, color));
+            canvas.fill_frect(this.h_thumb()).map_err(|e| anyhow!(e))?;
         }
-        if this.child_size.1 > this.surface.height() {
-            canvas.fill_frect(this.v_scroll()).map_err(|e| anyhow!(e))?;
+        if v_bar {
+            if let Some(track_color) = this.style.track_color {
+                canvas.set_draw_color(track_color);
+                canvas.fill_frect(this.v_track()).map_err(|e| anyhow!(e))?;
+            }
+            canvas.set_draw_color(this.thumb_color(this.v_selected, this.v_hover, color));
+            canvas.fill_frect(this.v_thumb()).map_err(|e| anyhow!(e))?;
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+pub(crate) mod scroll_view_test {
+    use std::rc::Rc;
+
+    use sdl2::{keyboard::Mod, mouse::MouseWheelDirection, rect::Rect};
+
+    use crate::ui_element::stack_panel::stack_panel_test::{block, Block};
+
+    use super::*;
+
+    ///Remembers the clip rect it was drawn with.
+    struct Probe {
+        surface: FRect,
+        clip: Cell<Option<Rect>>,
+    }
+
+    impl UserControl<(), ()> for Probe {
+        fn surface(this: Ref<Self>, _: Ref<()>, _: Ref<()>) -> FRect {
+            this.surface
+        }
+
+        fn event(
+            mut this: MutRef<Self>,
+            _: &Canvas<Window>,
+            event: Event,
+            _: MutRef<()>,
+            _: MutRef<()>,
+        ) -> Result<()> {
+            match event {
+                Event::ElementMove { x, y } => {
+                    this.surface.set_x(x);
+                    this.surface.set_y(y);
+                }
+                Event::ElementResize { width, height } => {
+                    this.surface.set_width(width);
+                    this.surface.set_height(height);
+                }
+                _ => {}
+            }
+            Ok(())
+        }
+
+        fn update(
+            _: MutRef<Self>,
+            _: &Canvas<Window>,
+            _: Duration,
+            _: MutRef<()>,
+            _: MutRef<()>,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        fn draw(
+            this: Ref<Self>,
+            canvas: &mut Canvas<Window>,
+            _: Ref<()>,
+            _: Ref<()>,
+        ) -> Result<()> {
+            this.clip.set(canvas.clip_rect());
+            Ok(())
+        }
+    }
+
+    fn scroll_view(width: f32, height: f32) -> ScrollView<(), (), Block> {
+        ScrollView::new(
+            block(0., 0.),
+            width,
+            height,
+            Box::new(|_, _, _| Color::WHITE),
+        )
+    }
+
+    fn layout<Child: UserControl<(), ()>>(
+        view: MutRef<ScrollView<(), (), Child>>,
+        canvas: &Canvas<Window>,
+        rect: FRect,
+    ) {
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        for event in [
+            Event::ElementMove {
+                x: rect.x(),
+                y: rect.y(),
+            },
+            Event::ElementResize {
+                width: rect.width(),
+                height: rect.height(),
+            },
+        ] {
+            UserControl::event(view, canvas, event, unit, unit).expect("");
+        }
+        UserControl::update(view, canvas, Duration::ZERO, unit, unit).expect("");
+    }
+
+    pub(crate) fn test_scroll_view_kinetic(canvas: &mut Canvas<Window>) {
+        let mut view = scroll_view(100., 400.);
+        let view = MutRef::new(&mut view);
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        layout(view, canvas, FRect::new(0., 0., 100., 100.));
+        let (_, height) = canvas.window().size();
+        let finger = |dy: f32| Event::FingerMotion {
+            touch_id: 0,
+            finger_id: 1,
+            x: 0.1,
+            y: 0.1,
+            dx: 0.,
+            dy: -dy / height as f32,
+            pressure: 1.,
+        };
+        let send = |event| UserControl::event(view, canvas, event, unit, unit).expect("");
+        let wait = |millis| {
+            UserControl::update(view, canvas, Duration::from_millis(millis), unit, unit).expect("");
+        };
+
+        send(Event::FingerDown {
+            touch_id: 0,
+            finger_id: 1,
+            x: 0.1,
+            y: 0.1,
+            dx: 0.,
+            dy: 0.,
+            pressure: 1.,
+        });
+        send(finger(10.));
+        wait(16);
+        assert!((view.v_scroll_px() - 10.).abs() < 0.01);
+        //No motion in the last frame before the release.
+        wait(16);
+        send(Event::FingerUp {
+            touch_id: 0,
+            finger_id: 1,
+            x: 0.1,
+            y: 0.1,
+            dx: 0.,
+            dy: 0.,
+            pressure: 1.,
+        });
+        wait(16);
+        assert!(view.v_scroll_px() > 10.);
+
+        //Holding still before the release leaves nothing to fling.
+        send(Event::FingerDown {
+            touch_id: 0,
+            finger_id: 1,
+            x: 0.1,
+            y: 0.1,
+            dx: 0.,
+            dy: 0.,
+            pressure: 1.,
+        });
+        send(finger(10.));
+        wait(16);
+        wait(200);
+        let held = view.v_scroll_px();
+        send(Event::FingerUp {
+            touch_id: 0,
+            finger_id: 1,
+            x: 0.1,
+            y: 0.1,
+            dx: 0.,
+            dy: 0.,
+            pressure: 1.,
+        });
+        wait(16);
+        assert!((view.v_scroll_px() - held).abs() < 0.01);
+    }
+
+    pub(crate) fn test_scroll_view_clip(canvas: &mut Canvas<Window>) {
+        let mut view = ScrollView::new(
+            Probe {
+                surface: zero(),
+                clip: Cell::new(None),
+            },
+            100.,
+            400.,
+            Box::new(|_, _, _| Color::WHITE),
+        );
+        let mut view = MutRef::new(&mut view);
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        layout(view, canvas, FRect::new(10., 20., 30., 25.));
+        assert_eq!(view.child().surface, FRect::new(10., 20., 100., 400.));
+
+        //The child moves by the scrolled pixels instead of being copied from a texture.
+        view.set_v_scroll(0.5);
+        view.set_h_scroll(1.);
+        UserControl::update(view, canvas, Duration::ZERO, unit, unit).expect("");
+        assert_eq!(view.child().surface, FRect::new(-60., -167.5, 100., 400.));
+
+        UserControl::draw(view.into(), canvas, unit.into(), unit.into()).expect("");
+        assert_eq!(view.child().clip.get(), Some(Rect::new(10, 20, 30, 25)));
+        assert_eq!(canvas.clip_rect(), None);
+
+        //An enclosing clip still applies to the child.
+        canvas.set_clip_rect(Rect::new(0, 0, 20, 30));
+        UserControl::draw(view.into(), canvas, unit.into(), unit.into()).expect("");
+        assert_eq!(view.child().clip.get(), Some(Rect::new(10, 20, 10, 10)));
+        assert_eq!(canvas.clip_rect(), Some(Rect::new(0, 0, 20, 30)));
+        canvas.set_clip_rect(None);
+    }
+
+    pub(crate) fn test_scroll_view_bars(canvas: &mut Canvas<Window>) {
+        let mut view = scroll_view(50., 400.).style(ScrollBarStyle {
+            thickness: 10.,
+            min_thumb_size: 10.,
+            thumb_hover_color: Some(Color::GREEN),
+            thumb_pressed_color: Some(Color::RED),
+            ..ScrollBarStyle::default()
+        });
+        let mut view = MutRef::new(&mut view);
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        let rect = FRect::new(0., 0., 50., 40.);
+        layout(view, canvas, rect);
+
+        //Over the child, the vertical bar doesn't take any room.
+        assert_eq!(view.bars(), (false, true));
+        assert_eq!(view.view(), rect);
+        assert_eq!(view.child().surface, FRect::new(0., 0., 50., 400.));
+
+        //In a gutter, the vertical bar takes room from the width so the horizontal one is needed too.
+        view.style_mut().placement = ScrollBarPlacement::Gutter;
+        layout(view, canvas, rect);
+        assert_eq!(view.bars(), (true, true));
+        assert_eq!(view.view(), FRect::new(0., 0., 40., 30.));
+        assert_eq!(view.child().surface, FRect::new(0., 0., 50., 400.));
+        *view.child_size_mut() = (40., 30.);
+        layout(view, canvas, rect);
+        assert_eq!(view.bars(), (false, false));
+        assert_eq!(view.child().surface, rect);
+
+        *view.child_size_mut() = (40., 400.);
+        layout(view, canvas, rect);
+        let thumb = view.v_thumb();
+        assert_eq!(thumb, FRect::new(40., 0., 10., 10.));
+        let send = move |event| UserControl::event(view, canvas, event, unit, unit).expect("");
+        let motion = |y, left| Event::MouseMotion {
+            which: 0,
+            mousestate: sdl2::mouse::MouseState::from_sdl_state(u32::from(left)),
+            x: 45.,
+            y,
+            moved_x: 0.,
+            moved_y: 0.,
+        };
+        let color = |view: MutRef<ScrollView<(), (), Block>>| {
+            view.thumb_color(view.v_selected, view.v_hover, Color::WHITE)
+        };
+        send(motion(5., false));
+        assert_eq!(color(view), Color::GREEN);
+        send(Event::MouseButtonDown {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 45.,
+            y: 5.,
+        });
+        assert_eq!(color(view), Color::RED);
+        send(motion(100., true));
+        assert_eq!(view.v_scroll(), 1.);
+        send(Event::MouseButtonUp {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 45.,
+            y: 100.,
+        });
+        assert_eq!(color(view), Color::WHITE);
+
+        //Hidden bars can't be grabbed.
+        view.style_mut().auto_hide = true;
+        send(motion(100., false));
+        assert!(!view.bars_visible());
+        send(Event::MouseButtonDown {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 45.,
+            y: 35.,
+        });
+        assert!(!view.v_selected);
+        send(motion(25., false));
+        assert!(view.bars_visible());
+    }
+
+    pub(crate) fn test_scroll_view_scroll_to(canvas: &mut Canvas<Window>) {
+        let changes = Rc::new(Cell::new(0));
+        let counter = changes.clone();
+        let mut view = scroll_view(100., 400.).scroll_changed(Box::new(move |this, _, _, _| {
+            //The child is already in its new place.
+            assert_eq!(this.child().surface.y(), -this.v_scroll_px());
+            counter.set(counter.get() + 1);
+            Ok(())
+        }));
+        let mut view = MutRef::new(&mut view);
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        layout(view, canvas, FRect::new(0., 0., 100., 100.));
+        let update = move || {
+            UserControl::update(view, canvas, Duration::ZERO, unit, unit).expect("");
+        };
+        assert_eq!(view.scroll_range(), (0., 300.));
+        assert_eq!(changes.get(), 0);
+
+        view.scroll_to_rect(FRect::new(0., 150., 10., 20.));
+        assert!((view.v_scroll_px() - 70.).abs() < 0.01);
+        view.scroll_to_rect(FRect::new(0., 100., 10., 20.));
+        assert!((view.v_scroll_px() - 70.).abs() < 0.01);
+        assert_eq!(changes.get(), 0);
+        update();
+        assert_eq!(changes.get(), 1);
+        update();
+        assert_eq!(changes.get(), 1);
+
+        view.scroll_to_rect(FRect::new(0., 20., 10., 20.));
+        assert!((view.v_scroll_px() - 20.).abs() < 0.01);
+        //Too tall to fit, its top is shown.
+        view.scroll_to_rect(FRect::new(0., 200., 10., 150.));
+        assert!((view.v_scroll_px() - 200.).abs() < 0.01);
+        update();
+        assert_eq!(changes.get(), 2);
+
+        view.set_v_scroll_px(1000.);
+        assert_eq!(view.v_scroll(), 1.);
+        view.set_v_scroll(-1.);
+        assert_eq!(view.v_scroll_px(), 0.);
+        update();
+        assert_eq!(changes.get(), 3);
+    }
+
+    fn wheel(scroll_y: f32) -> Event {
+        Event::MouseWheel {
+            which: 0,
+            scroll_x: 0.,
+            scroll_y,
+            direction: MouseWheelDirection::Normal,
+            mouse_x: 50.,
+            mouse_y: 50.,
+        }
+    }
+
+    pub(crate) fn test_scroll_view_smooth(canvas: &mut Canvas<Window>) {
+        let mut view = scroll_view(100., 400.);
+        let mut view = MutRef::new(&mut view);
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        let canvas = &*canvas;
+        layout(view, canvas, FRect::new(0., 0., 100., 100.));
+        let send = move |event| UserControl::event(view, canvas, event, unit, unit).expect("");
+        let wait = move |millis| {
+            UserControl::update(view, canvas, Duration::from_millis(millis), unit, unit).expect("");
+        };
+
+        //The notches add up to the target and the position eases towards it.
+        send(wheel(-1.));
+        send(wheel(-1.));
+        assert_eq!(view.v_scroll_px(), 0.);
+        wait(16);
+        let first = view.v_scroll_px();
+        assert!(first > 0. && first < 40.);
+        wait(16);
+        assert!(view.v_scroll_px() > first && view.v_scroll_px() < 80.);
+        for _ in 0..60 {
+            wait(16);
+        }
+        assert!((view.v_scroll_px() - 80.).abs() < 0.01);
+        assert_eq!(view.target, (None, None));
+
+        //Without elapsed time it jumps straight to the target.
+        send(wheel(1.));
+        wait(0);
+        assert!((view.v_scroll_px() - 40.).abs() < 0.01);
+
+        //Jumping cancels the animation.
+        send(wheel(-1.));
+        view.set_v_scroll(0.);
+        wait(16);
+        assert_eq!(view.v_scroll_px(), 0.);
+
+        view.behavior_mut().smooth = false;
+        send(wheel(-2.));
+        assert!((view.v_scroll_px() - 80.).abs() < 0.01);
+    }
+
+    pub(crate) fn test_scroll_view_nested(canvas: &mut Canvas<Window>) {
+        let behavior = ScrollBehavior {
+            smooth: false,
+            ..ScrollBehavior::default()
+        };
+        let mut view = ScrollView::new(
+            scroll_view(100., 800.).behavior(behavior),
+            100.,
+            400.,
+            Box::new(|_, _, _| Color::WHITE),
+        )
+        .behavior(behavior);
+        let view = MutRef::new(&mut view);
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        let canvas = &*canvas;
+        layout(view, canvas, FRect::new(0., 0., 100., 100.));
+        let send = move |event| UserControl::event(view, canvas, event, unit, unit).expect("");
+        let scrolls = move || (view.child().v_scroll_px(), view.v_scroll_px());
+        let key = |keycode| Event::KeyDown {
+            keycode: Some(keycode),
+            scancode: None,
+            keymod: Mod::NOMOD,
+            repeat: false,
+        };
+
+        //The inner view scrolls first and the outer one gets what is left.
+        send(wheel(-20.));
+        assert_eq!(scrolls(), (400., 300.));
+        UserControl::update(view, canvas, Duration::ZERO, unit, unit).expect("");
+        send(wheel(1.));
+        assert_eq!(scrolls(), (360., 300.));
+
+        send(Event::MouseButtonDown {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 50.,
+            y: 50.,
+        });
+        assert!(view.focused() && view.child().focused());
+        send(key(Keycode::End));
+        assert_eq!(scrolls(), (400., 300.));
+        send(key(Keycode::Home));
+        assert_eq!(scrolls(), (0., 300.));
+        send(key(Keycode::Home));
+        assert_eq!(scrolls(), (0., 0.));
+        assert!(take_handled());
+        send(key(Keycode::Home));
+        assert!(!take_handled());
+    }
+}
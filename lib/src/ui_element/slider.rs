@@ -0,0 +1,909 @@
+use std::{marker::PhantomData, time::Duration};
+
+use anyhow::{anyhow, Result};
+use sdl2::{
+    keyboard::Keycode,
+    mouse::MouseButton,
+    rect::{FPoint, FRect},
+    render::Canvas,
+    video::Window,
+};
+
+use crate::{
+    event::{mark_handled, Event},
+    functions::{FnAction, FnColor, FnState, StateEnum},
+    refs::{MutRef, Ref},
+    ui_element::Orientation,
+    user_control::UserControl,
+    zero,
+};
+
+///Rounds the value to the closest step from min, a step of 0 keeps it as is.
+fn snap(value: f32, min: f32, max: f32, step: f32) -> f32 {
+    let value = if step > 0. {
+        min + ((value - min) / step).round() * step
+    } else {
+        value
+    };
+    value.clamp(min, max)
+}
+
+///The line the center of the thumbs moves on.
+fn track(surface: FRect, orientation: Orientation, thumb_size: f32) -> FRect {
+    let half = thumb_size / 2.;
+    match orientation {
+        Orientation::Horizontal => FRect::new(
+            surface.x() + half,
+            surface.y() + surface.height() / 2.,
+            (surface.width() - thumb_size).max(0.),
+            0.,
+        ),
+        Orientation::Vertical => FRect::new(
+            surface.x() + surface.width() / 2.,
+            surface.y() + half,
+            0.,
+            (surface.height() - thumb_size).max(0.),
+        ),
+    }
+}
+
+///Where the point is on the track, from 0 at the min to 1 at the max.
+fn ratio_at(track: FRect, orientation: Orientation, x: f32, y: f32) -> f32 {
+    match orientation {
+        Orientation::Horizontal if track.width() > 0. => (x - track.x()) / track.width(),
+        //The min is at the bottom.
+        Orientation::Vertical if track.height() > 0. => 1. - (y - track.y()) / track.height(),
+        _ => 0.,
+    }
+    .clamp(0., 1.)
+}
+
+fn thumb_rect(surface: FRect, orientation: Orientation, thumb_size: f32, ratio: f32) -> FRect {
+    let track = track(surface, orientation, thumb_size);
+    let half = thumb_size / 2.;
+    match orientation {
+        Orientation::Horizontal => FRect::new(
+            track.x() + ratio * track.width() - half,
+            surface.y(),
+            thumb_size,
+            surface.height(),
+        ),
+        Orientation::Vertical => FRect::new(
+            surface.x(),
+            track.y() + (1. - ratio) * track.height() - half,
+            surface.width(),
+            thumb_size,
+        ),
+    }
+}
+
+///The part of the track drawn between two ratios.
+fn fill_rect(
+    surface: FRect,
+    orientation: Orientation,
+    thumb_size: f32,
+    from: f32,
+    to: f32,
+) -> FRect {
+    let track = track(surface, orientation, thumb_size);
+    let thickness = thumb_size / 4.;
+    match orientation {
+        Orientation::Horizontal => FRect::new(
+            track.x() + from * track.width(),
+            track.y() - thickness / 2.,
+            (to - from) * track.width(),
+            thickness,
+        ),
+        Orientation::Vertical => FRect::new(
+            track.x() - thickness / 2.,
+            track.y() + (1. - to) * track.height(),
+            thickness,
+            (to - from) * track.height(),
+        ),
+    }
+}
+
+///How much a key press moves the value, the page keys move ten times more.
+fn key_delta(keycode: Keycode, step: f32) -> Option<f32> {
+    match keycode {
+        Keycode::Left | Keycode::Down => Some(-step),
+        Keycode::Right | Keycode::Up => Some(step),
+        Keycode::PageDown => Some(-10. * step),
+        Keycode::PageUp => Some(10. * step),
+        Keycode::Home => Some(f32::NEG_INFINITY),
+        Keycode::End => Some(f32::INFINITY),
+        _ => None,
+    }
+}
+
+///Lets the user pick a value between min and max by dragging its thumb.
+pub struct Slider<Parent: 'static, State: 'static> {
+    parent: PhantomData<Parent>,
+    statel: PhantomData<State>,
+    surface: FRect,
+    orientation: Orientation,
+    min: f32,
+    max: f32,
+    ///0 for any value.
+    step: f32,
+    value: f32,
+    thumb_size: f32,
+    ///It gets called when the user changes the value.
+    changed: Option<FnAction<Self, Parent, State>>,
+    state: FnState<Self, Parent, State>,
+    track_color: FnColor<Self, Parent, State>,
+    thumb_color: FnColor<Self, Parent, State>,
+    selected: bool,
+    hover: bool,
+    focused: bool,
+}
+
+impl<Parent: 'static, State: 'static> Slider<Parent, State> {
+    pub fn new(
+        min: f32,
+        max: f32,
+        state: FnState<Self, Parent, State>,
+        track_color: FnColor<Self, Parent, State>,
+        thumb_color: FnColor<Self, Parent, State>,
+    ) -> Self {
+        Self {
+            parent: PhantomData,
+            statel: PhantomData,
+            surface: zero(),
+            orientation: Orientation::Horizontal,
+            min,
+            max: max.max(min),
+            step: 0.,
+            value: min,
+            thumb_size: 20.,
+            changed: None,
+            state,
+            track_color,
+            thumb_color,
+            selected: false,
+            hover: false,
+            focused: false,
+        }
+    }
+
+    pub const fn surface(&self) -> FRect {
+        self.surface
+    }
+
+    pub const fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn step(mut self, step: f32) -> Self {
+        self.step = step.max(0.);
+        self.value = snap(self.value, self.min, self.max, self.step);
+        self
+    }
+
+    pub fn value(mut self, value: f32) -> Self {
+        self.set_value(value);
+        self
+    }
+
+    pub const fn thumb_size(mut self, thumb_size: f32) -> Self {
+        self.thumb_size = thumb_size;
+        self
+    }
+
+    pub fn changed(mut self, changed: FnAction<Self, Parent, State>) -> Self {
+        self.changed = Some(changed);
+        self
+    }
+
+    pub fn changed_mut(&mut self) -> &mut Option<FnAction<Self, Parent, State>> {
+        &mut self.changed
+    }
+
+    pub fn state_mut(&mut self) -> &mut FnState<Self, Parent, State> {
+        &mut self.state
+    }
+
+    pub const fn min(&self) -> f32 {
+        self.min
+    }
+
+    pub const fn max(&self) -> f32 {
+        self.max
+    }
+
+    pub const fn get_value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn set_value(&mut self, value: f32) {
+        self.value = snap(value, self.min, self.max, self.step);
+    }
+
+    pub const fn hover(&self) -> bool {
+        self.hover
+    }
+
+    ///If the user is dragging the thumb.
+    pub const fn selected(&self) -> bool {
+        self.selected
+    }
+
+    fn ratio(&self) -> f32 {
+        if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.
+        }
+    }
+
+    fn thumb(&self) -> FRect {
+        thumb_rect(
+            self.surface,
+            self.orientation,
+            self.thumb_size,
+            self.ratio(),
+        )
+    }
+
+    ///The value a key press or a wheel notch moves.
+    fn key_step(&self) -> f32 {
+        if self.step > 0. {
+            self.step
+        } else {
+            (self.max - self.min) / 20.
+        }
+    }
+
+    fn user_value(
+        mut this: MutRef<Self>,
+        value: f32,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let value = snap(value, this.min, this.max, this.step);
+        if value != this.value {
+            this.value = value;
+            let t = this;
+            if let Some(changed) = this.changed.as_mut() {
+                changed(t, parent, state, canvas)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<Parent: 'static, State: 'static> UserControl<Parent, State> for Slider<Parent, State> {
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match event {
+            Event::ElementMove { x, y } => {
+                this.surface.set_x(x);
+                this.surface.set_y(y);
+                return Ok(());
+            }
+            Event::ElementResize { width, height } => {
+                this.surface.set_width(width);
+                this.surface.set_height(height);
+                return Ok(());
+            }
+            _ => {}
+        }
+        if (this.state)(this.into(), parent.into(), state.into()) != StateEnum::Enable {
+            this.selected = false;
+            return Ok(());
+        }
+        let track = track(this.surface, this.orientation, this.thumb_size);
+        let range = this.max - this.min;
+        match event {
+            Event::MouseMotion {
+                mousestate, x, y, ..
+            } => {
+                this.hover = this.thumb().contains_point(FPoint::new(x, y));
+                if mousestate.left() && this.selected {
+                    let value = this.min + ratio_at(track, this.orientation, x, y) * range;
+                    Self::user_value(this, value, canvas, parent, state)?;
+                }
+            }
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => {
+                let point = FPoint::new(x, y);
+                this.focused = this.surface.contains_point(point);
+                this.selected = this.focused;
+                //Clicking the track jumps the thumb there.
+                if this.selected && !this.thumb().contains_point(point) {
+                    let value = this.min + ratio_at(track, this.orientation, x, y) * range;
+                    Self::user_value(this, value, canvas, parent, state)?;
+                }
+            }
+            Event::MouseButtonDown { x, y, .. } => {
+                this.focused = this.surface.contains_point(FPoint::new(x, y));
+            }
+            Event::MouseButtonUp { .. } => this.selected = false,
+            Event::MouseWheel {
+                scroll_y,
+                mouse_x,
+                mouse_y,
+                ..
+            } if this.surface.contains_point(FPoint::new(mouse_x, mouse_y)) => {
+                let value = this.value + scroll_y * this.key_step();
+                Self::user_value(this, value, canvas, parent, state)?;
+            }
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } if this.focused => {
+                if let Some(delta) = key_delta(keycode, this.key_step()) {
+                    mark_handled();
+                    let value = this.value + delta;
+                    Self::user_value(this, value, canvas, parent, state)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn update(
+        _: MutRef<Self>,
+        _: &Canvas<Window>,
+        _: Duration,
+        _: MutRef<Parent>,
+        _: MutRef<State>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        if (this.state)(this, parent, state) == StateEnum::Hidden {
+            return Ok(());
+        }
+        canvas.set_draw_color((this.track_color)(this, parent, state));
+        canvas
+            .fill_frect(fill_rect(
+                this.surface,
+                this.orientation,
+                this.thumb_size,
+                0.,
+                1.,
+            ))
+            .map_err(|e| anyhow!(e))?;
+        canvas.set_draw_color((this.thumb_color)(this, parent, state));
+        canvas
+            .fill_frect(fill_rect(
+                this.surface,
+                this.orientation,
+                this.thumb_size,
+                0.,
+                this.ratio(),
+            ))
+            .map_err(|e| anyhow!(e))?;
+        canvas.fill_frect(this.thumb()).map_err(|e| anyhow!(e))
+    }
+}
+
+///Lets the user pick a range between min and max by dragging its two thumbs.
+pub struct RangeSlider<Parent: 'static, State: 'static> {
+    parent: PhantomData<Parent>,
+    statel: PhantomData<State>,
+    surface: FRect,
+    orientation: Orientation,
+    min: f32,
+    max: f32,
+    ///0 for any value.
+    step: f32,
+    low: f32,
+    high: f32,
+    thumb_size: f32,
+    ///It gets called when the user changes the range.
+    changed: Option<FnAction<Self, Parent, State>>,
+    state: FnState<Self, Parent, State>,
+    track_color: FnColor<Self, Parent, State>,
+    thumb_color: FnColor<Self, Parent, State>,
+    low_selected: bool,
+    high_selected: bool,
+    ///The thumb the keyboard and the wheel move.
+    high_active: bool,
+    focused: bool,
+}
+
+impl<Parent: 'static, State: 'static> RangeSlider<Parent, State> {
+    pub fn new(
+        min: f32,
+        max: f32,
+        state: FnState<Self, Parent, State>,
+        track_color: FnColor<Self, Parent, State>,
+        thumb_color: FnColor<Self, Parent, State>,
+    ) -> Self {
+        let max = max.max(min);
+        Self {
+            parent: PhantomData,
+            statel: PhantomData,
+            surface: zero(),
+            orientation: Orientation::Horizontal,
+            min,
+            max,
+            step: 0.,
+            low: min,
+            high: max,
+            thumb_size: 20.,
+            changed: None,
+            state,
+            track_color,
+            thumb_color,
+            low_selected: false,
+            high_selected: false,
+            high_active: false,
+            focused: false,
+        }
+    }
+
+    pub const fn surface(&self) -> FRect {
+        self.surface
+    }
+
+    pub const fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn step(mut self, step: f32) -> Self {
+        self.step = step.max(0.);
+        let (low, high) = (self.low, self.high);
+        self.set_range(low, high);
+        self
+    }
+
+    pub fn range(mut self, low: f32, high: f32) -> Self {
+        self.set_range(low, high);
+        self
+    }
+
+    pub const fn thumb_size(mut self, thumb_size: f32) -> Self {
+        self.thumb_size = thumb_size;
+        self
+    }
+
+    pub fn changed(mut self, changed: FnAction<Self, Parent, State>) -> Self {
+        self.changed = Some(changed);
+        self
+    }
+
+    pub fn changed_mut(&mut self) -> &mut Option<FnAction<Self, Parent, State>> {
+        &mut self.changed
+    }
+
+    pub fn state_mut(&mut self) -> &mut FnState<Self, Parent, State> {
+        &mut self.state
+    }
+
+    pub const fn min(&self) -> f32 {
+        self.min
+    }
+
+    pub const fn max(&self) -> f32 {
+        self.max
+    }
+
+    pub const fn low(&self) -> f32 {
+        self.low
+    }
+
+    pub const fn high(&self) -> f32 {
+        self.high
+    }
+
+    pub fn set_range(&mut self, low: f32, high: f32) {
+        let low = snap(low, self.min, self.max, self.step);
+        let high = snap(high, self.min, self.max, self.step);
+        (self.low, self.high) = (low.min(high), low.max(high));
+    }
+
+    ///If the user is dragging the low or the high thumb.
+    pub const fn selected(&self) -> (bool, bool) {
+        (self.low_selected, self.high_selected)
+    }
+
+    fn ratio(&self, value: f32) -> f32 {
+        if self.max > self.min {
+            (value - self.min) / (self.max - self.min)
+        } else {
+            0.
+        }
+    }
+
+    fn low_thumb(&self) -> FRect {
+        thumb_rect(
+            self.surface,
+            self.orientation,
+            self.thumb_size,
+            self.ratio(self.low),
+        )
+    }
+
+    fn high_thumb(&self) -> FRect {
+        thumb_rect(
+            self.surface,
+            self.orientation,
+            self.thumb_size,
+            self.ratio(self.high),
+        )
+    }
+
+    fn key_step(&self) -> f32 {
+        if self.step > 0. {
+            self.step
+        } else {
+            (self.max - self.min) / 20.
+        }
+    }
+
+    ///Moves the active thumb without passing over the other one.
+    fn user_value(
+        mut this: MutRef<Self>,
+        value: f32,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let value = snap(value, this.min, this.max, this.step);
+        let previous = (this.low, this.high);
+        if this.high_active {
+            this.high = value.max(this.low);
+        } else {
+            this.low = value.min(this.high);
+        }
+        if previous != (this.low, this.high) {
+            let t = this;
+            if let Some(changed) = this.changed.as_mut() {
+                changed(t, parent, state, canvas)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn press(
+        mut this: MutRef<Self>,
+        x: f32,
+        y: f32,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let point = FPoint::new(x, y);
+        this.focused = this.surface.contains_point(point);
+        if !this.focused {
+            return Ok(());
+        }
+        //The high thumb is checked first so it can be moved away when both are at the max.
+        if this.high_thumb().contains_point(point) {
+            this.high_selected = true;
+            this.high_active = true;
+        } else if this.low_thumb().contains_point(point) {
+            this.low_selected = true;
+            this.high_active = false;
+        } else {
+            //Clicking the track jumps the closest thumb there.
+            let track = track(this.surface, this.orientation, this.thumb_size);
+            let value = this.min + ratio_at(track, this.orientation, x, y) * (this.max - this.min);
+            this.high_active =
+                (value - this.high).abs() < (value - this.low).abs() || (value > this.high);
+            this.high_selected = this.high_active;
+            this.low_selected = !this.high_active;
+            Self::user_value(this, value, canvas, parent, state)?;
+        }
+        Ok(())
+    }
+}
+
+impl<Parent: 'static, State: 'static> UserControl<Parent, State> for RangeSlider<Parent, State> {
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match event {
+            Event::ElementMove { x, y } => {
+                this.surface.set_x(x);
+                this.surface.set_y(y);
+                return Ok(());
+            }
+            Event::ElementResize { width, height } => {
+                this.surface.set_width(width);
+                this.surface.set_height(height);
+                return Ok(());
+            }
+            _ => {}
+        }
+        if (this.state)(this.into(), parent.into(), state.into()) != StateEnum::Enable {
+            this.low_selected = false;
+            this.high_selected = false;
+            return Ok(());
+        }
+        match event {
+            Event::MouseMotion {
+                mousestate, x, y, ..
+            } if mousestate.left() && (this.low_selected || this.high_selected) => {
+                let track = track(this.surface, this.orientation, this.thumb_size);
+                let value =
+                    this.min + ratio_at(track, this.orientation, x, y) * (this.max - this.min);
+                Self::user_value(this, value, canvas, parent, state)?;
+            }
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => Self::press(this, x, y, canvas, parent, state)?,
+            Event::MouseButtonDown { x, y, .. } => {
+                this.focused = this.surface.contains_point(FPoint::new(x, y));
+            }
+            Event::MouseButtonUp { .. } => {
+                this.low_selected = false;
+                this.high_selected = false;
+            }
+            Event::MouseWheel {
+                scroll_y,
+                mouse_x,
+                mouse_y,
+                ..
+            } if this.surface.contains_point(FPoint::new(mouse_x, mouse_y)) => {
+                let value = if this.high_active {
+                    this.high
+                } else {
+                    this.low
+                };
+                let value = value + scroll_y * this.key_step();
+                Self::user_value(this, value, canvas, parent, state)?;
+            }
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } if this.focused => {
+                if let Some(delta) = key_delta(keycode, this.key_step()) {
+                    mark_handled();
+                    let value = if this.high_active {
+                        this.high
+                    } else {
+                        this.low
+                    };
+                    Self::user_value(this, value + delta, canvas, parent, state)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn update(
+        _: MutRef<Self>,
+        _: &Canvas<Window>,
+        _: Duration,
+        _: MutRef<Parent>,
+        _: MutRef<State>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        if (this.state)(this, parent, state) == StateEnum::Hidden {
+            return Ok(());
+        }
+        canvas.set_draw_color((this.track_color)(this, parent, state));
+        canvas
+            .fill_frect(fill_rect(
+                this.surface,
+                this.orientation,
+                this.thumb_size,
+                0.,
+                1.,
+            ))
+            .map_err(|e| anyhow!(e))?;
+        canvas.set_draw_color((this.thumb_color)(this, parent, state));
+        canvas
+            .fill_frect(fill_rect(
+                this.surface,
+                this.orientation,
+                this.thumb_size,
+                this.ratio(this.low),
+                this.ratio(this.high),
+            ))
+            .map_err(|e| anyhow!(e))?;
+        canvas
+            .fill_frect(this.low_thumb())
+            .map_err(|e| anyhow!(e))?;
+        canvas.fill_frect(this.high_thumb()).map_err(|e| anyhow!(e))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod slider_test {
+    use sdl2::{keyboard::Mod, mouse::MouseState, pixels::Color};
+
+    use super::*;
+
+    pub(crate) fn test_slider_drag(canvas: &mut Canvas<Window>) {
+        let mut changes = 0_usize;
+        let changes = MutRef::new(&mut changes);
+        let mut slider = Slider::new(
+            0.,
+            10.,
+            Box::new(|_, _, _| StateEnum::Enable),
+            Box::new(|_, _, _| Color::BLACK),
+            Box::new(|_, _, _| Color::WHITE),
+        )
+        .step(1.)
+        .changed(Box::new(|_, _, mut changes: MutRef<usize>, _| {
+            *changes += 1;
+            Ok(())
+        }));
+        let slider = MutRef::new(&mut slider);
+        let mut parent = ();
+        let parent = MutRef::new(&mut parent);
+        let send = |event| UserControl::event(slider, canvas, event, parent, changes).expect("");
+        send(Event::ElementResize {
+            width: 120.,
+            height: 20.,
+        });
+
+        //The track goes from 10 to 110 so each step is 10px.
+        send(Event::MouseButtonDown {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 62.,
+            y: 10.,
+        });
+        assert_eq!(slider.get_value(), 5.);
+        assert!(slider.selected());
+        send(Event::MouseMotion {
+            which: 0,
+            mousestate: MouseState::from_sdl_state(1),
+            x: 200.,
+            y: 10.,
+            moved_x: 0.,
+            moved_y: 0.,
+        });
+        assert_eq!(slider.get_value(), 10.);
+        send(Event::MouseButtonUp {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 200.,
+            y: 10.,
+        });
+        assert!(!slider.selected());
+        send(Event::KeyDown {
+            keycode: Some(Keycode::Left),
+            scancode: None,
+            keymod: Mod::NOMOD,
+            repeat: false,
+        });
+        assert_eq!(slider.get_value(), 9.);
+        assert_eq!(*changes, 3);
+    }
+
+    pub(crate) fn test_range_slider(canvas: &mut Canvas<Window>) {
+        let mut changes = 0_usize;
+        let changes = MutRef::new(&mut changes);
+        let mut slider = RangeSlider::new(
+            0.,
+            10.,
+            Box::new(|_, _, _| StateEnum::Enable),
+            Box::new(|_, _, _| Color::BLACK),
+            Box::new(|_, _, _| Color::WHITE),
+        )
+        .step(1.)
+        .range(2., 8.)
+        .changed(Box::new(|_, _, mut changes: MutRef<usize>, _| {
+            *changes += 1;
+            Ok(())
+        }));
+        let mut slider = MutRef::new(&mut slider);
+        let mut parent = ();
+        let parent = MutRef::new(&mut parent);
+        let canvas = &*canvas;
+        let send =
+            move |event| UserControl::event(slider, canvas, event, parent, changes).expect("");
+        let drag = |from, to| {
+            send(Event::MouseButtonDown {
+                which: 0,
+                mouse_btn: MouseButton::Left,
+                clicks: 1,
+                x: from,
+                y: 10.,
+            });
+            send(Event::MouseMotion {
+                which: 0,
+                mousestate: MouseState::from_sdl_state(1),
+                x: to,
+                y: 10.,
+                moved_x: 0.,
+                moved_y: 0.,
+            });
+            send(Event::MouseButtonUp {
+                which: 0,
+                mouse_btn: MouseButton::Left,
+                clicks: 1,
+                x: to,
+                y: 10.,
+            });
+        };
+        let key = |keycode| Event::KeyDown {
+            keycode: Some(keycode),
+            scancode: None,
+            keymod: Mod::NOMOD,
+            repeat: false,
+        };
+        send(Event::ElementResize {
+            width: 120.,
+            height: 20.,
+        });
+
+        //The track goes from 10 to 110 so each step is 10px, the thumbs can't pass each other.
+        drag(90., 20.);
+        assert_eq!((slider.low(), slider.high()), (2., 2.));
+        assert!(!slider.selected().1);
+        //On top of each other, the high thumb gets picked so it can move away.
+        drag(30., 70.);
+        assert_eq!((slider.low(), slider.high()), (2., 6.));
+        assert_eq!(*changes, 2);
+
+        //Clicking the track moves the closest thumb.
+        drag(100., 100.);
+        assert_eq!((slider.low(), slider.high()), (2., 9.));
+        drag(45., 45.);
+        assert_eq!((slider.low(), slider.high()), (4., 9.));
+        assert_eq!(*changes, 4);
+
+        //The keyboard and the wheel move the last thumb the user picked.
+        send(key(Keycode::Right));
+        assert_eq!(slider.low(), 5.);
+        send(key(Keycode::End));
+        assert_eq!((slider.low(), slider.high()), (9., 9.));
+        send(Event::MouseWheel {
+            which: 0,
+            scroll_x: 0.,
+            scroll_y: -1.,
+            direction: sdl2::mouse::MouseWheelDirection::Normal,
+            mouse_x: 50.,
+            mouse_y: 10.,
+        });
+        assert_eq!(slider.low(), 8.);
+        assert_eq!(*changes, 7);
+
+        slider.set_range(7.4, 2.6);
+        assert_eq!((slider.low(), slider.high()), (3., 7.));
+        assert_eq!(*changes, 7);
+    }
+}
@@ -0,0 +1,195 @@
+use std::{f32::consts::TAU, marker::PhantomData, time::Duration};
+
+use anyhow::{anyhow, Result};
+use sdl2::{
+    gfx::primitives::DrawRenderer, pixels::Color, rect::FRect, render::Canvas, video::Window,
+};
+
+use crate::{
+    event::Event,
+    functions::{FnColor, FnState, StateEnum},
+    refs::{MutRef, Ref},
+    user_control::UserControl,
+    zero,
+};
+
+///A ring of dots turning around, fading behind the leading one.
+pub struct Spinner<Parent: 'static, State: 'static> {
+    parent: PhantomData<Parent>,
+    statel: PhantomData<State>,
+    surface: FRect,
+    state: FnState<Self, Parent, State>,
+    color: FnColor<Self, Parent, State>,
+    dots: u8,
+    ///Turns per second.
+    speed: f32,
+    ///The angle of the leading dot, from 0 to 1.
+    phase: f32,
+}
+
+impl<Parent: 'static, State: 'static> Spinner<Parent, State> {
+    pub fn new(state: FnState<Self, Parent, State>, color: FnColor<Self, Parent, State>) -> Self {
+        Self {
+            parent: PhantomData,
+            statel: PhantomData,
+            surface: zero(),
+            state,
+            color,
+            dots: 10,
+            speed: 1.,
+            phase: 0.,
+        }
+    }
+
+    pub const fn surface(&self) -> FRect {
+        self.surface
+    }
+
+    pub fn dots(mut self, dots: u8) -> Self {
+        self.dots = dots.max(1);
+        self
+    }
+
+    pub const fn speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn state_mut(&mut self) -> &mut FnState<Self, Parent, State> {
+        &mut self.state
+    }
+}
+
+impl<Parent: 'static, State: 'static> UserControl<Parent, State> for Spinner<Parent, State> {
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        _: &Canvas<Window>,
+        event: Event,
+        _: MutRef<Parent>,
+        _: MutRef<State>,
+    ) -> Result<()> {
+        match event {
+            Event::ElementMove { x, y } => {
+                this.surface.set_x(x);
+                this.surface.set_y(y);
+            }
+            Event::ElementResize { width, height } => {
+                this.surface.set_width(width);
+                this.surface.set_height(height);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn update(
+        mut this: MutRef<Self>,
+        _: &Canvas<Window>,
+        elapsed: Duration,
+        _: MutRef<Parent>,
+        _: MutRef<State>,
+    ) -> Result<()> {
+        this.phase = (this.phase + elapsed.as_secs_f32() * this.speed).rem_euclid(1.);
+        Ok(())
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        if (this.state)(this, parent, state) == StateEnum::Hidden {
+            return Ok(());
+        }
+        let color = (this.color)(this, parent, state);
+        let size = this.surface.width().min(this.surface.height()) / 2.;
+        let dot = size / 6.;
+        let radius = size - dot;
+        let (cx, cy) = (
+            this.surface.x() + this.surface.width() / 2.,
+            this.surface.y() + this.surface.height() / 2.,
+        );
+        let dots = f32::from(this.dots);
+        //The leading dot snaps to its place so the ring doesn't look blurry.
+        let lead = (this.phase * dots).floor();
+        for i in 0..this.dots {
+            let i = f32::from(i);
+            let angle = (lead - i) / dots * TAU;
+            let alpha = f32::from(color.a) * (1. - i / dots);
+            canvas
+                .filled_circle(
+                    (cx + radius * angle.cos()) as i16,
+                    (cy + radius * angle.sin()) as i16,
+                    (dot * (1. - i / dots / 2.)) as i16,
+                    Color::RGBA(color.r, color.g, color.b, alpha as u8),
+                )
+                .map_err(|e| anyhow!(e))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod spinner_test {
+    use super::*;
+
+    pub(crate) fn test_spinner(canvas: &mut Canvas<Window>) {
+        let mut shown = true;
+        let mut shown = MutRef::new(&mut shown);
+        let mut parent = ();
+        let parent = MutRef::new(&mut parent);
+        let mut spinner = Spinner::new(
+            Box::new(|_, _, shown: Ref<bool>| {
+                if *shown {
+                    StateEnum::Showing
+                } else {
+                    StateEnum::Hidden
+                }
+            }),
+            Box::new(|_, _, _| Color::WHITE),
+        )
+        .dots(0)
+        .speed(2.);
+        assert_eq!(spinner.dots, 1);
+        let mut spinner = MutRef::new(&mut spinner);
+        UserControl::event(
+            spinner,
+            canvas,
+            Event::ElementResize {
+                width: 20.,
+                height: 20.,
+            },
+            parent,
+            shown,
+        )
+        .expect("");
+        let update = move |canvas: &Canvas<Window>, millis| {
+            UserControl::update(
+                spinner,
+                canvas,
+                Duration::from_millis(millis),
+                parent,
+                shown,
+            )
+            .expect("");
+        };
+
+        update(canvas, 250);
+        assert_eq!(spinner.phase, 0.5);
+        update(canvas, 750);
+        assert_eq!(spinner.phase, 0.);
+        UserControl::draw(spinner.into(), canvas, parent.into(), shown.into()).expect("");
+
+        //Turning the other way still keeps the phase between 0 and 1.
+        spinner.speed = -1.;
+        update(canvas, 250);
+        assert_eq!(spinner.phase, 0.75);
+        *shown = false;
+        UserControl::draw(spinner.into(), canvas, parent.into(), shown.into()).expect("");
+    }
+}
@@ -0,0 +1,383 @@
+use std::{marker::PhantomData, time::Duration};
+
+use anyhow::{anyhow, Result};
+use sdl2::{mouse::MouseButton, rect::FRect, render::Canvas, video::Window};
+
+use crate::{
+    event::Event,
+    functions::{FnAction, FnColor},
+    refs::{MutRef, Ref},
+    ui_element::{place, Orientation},
+    user_control::UserControl,
+    zero,
+};
+
+///Panes side by side with dividers the user drags to resize them.
+pub struct Splitter<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static> {
+    parent: PhantomData<Parent>,
+    statel: PhantomData<State>,
+    surface: FRect,
+    ///Horizontal puts the panes from left to right.
+    orientation: Orientation,
+    children: Vec<Child>,
+    ///The part of the space left by the dividers each pane takes, adding up to 1.
+    ratios: Vec<f32>,
+    ///In pixels, only ignored when a pane is collapsed.
+    min_sizes: Vec<f32>,
+    ///The ratio of the panes collapsed by a double click on the divider after them.
+    collapsed: Vec<Option<f32>>,
+    thickness: f32,
+    dragged: Option<usize>,
+    ///It gets called when the user moves a divider.
+    changed: Option<FnAction<Self, Parent, State>>,
+    divider_color: FnColor<Self, Parent, State>,
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    Splitter<Parent, State, Child>
+{
+    ///The panes start with the same size.
+    pub fn new(
+        orientation: Orientation,
+        children: Vec<Child>,
+        divider_color: FnColor<Self, Parent, State>,
+    ) -> Self {
+        let len = children.len();
+        Self {
+            parent: PhantomData,
+            statel: PhantomData,
+            surface: zero(),
+            orientation,
+            children,
+            ratios: vec![1. / len.max(1) as f32; len],
+            min_sizes: vec![0.; len],
+            collapsed: vec![None; len],
+            thickness: 6.,
+            dragged: None,
+            changed: None,
+            divider_color,
+        }
+    }
+
+    pub const fn surface(&self) -> FRect {
+        self.surface
+    }
+
+    pub const fn thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    ///The smallest size in pixels of each pane, the missing ones being 0.
+    pub fn min_sizes(mut self, min_sizes: &[f32]) -> Self {
+        for (min, size) in self.min_sizes.iter_mut().zip(min_sizes) {
+            *min = *size;
+        }
+        self
+    }
+
+    pub fn changed(mut self, changed: FnAction<Self, Parent, State>) -> Self {
+        self.changed = Some(changed);
+        self
+    }
+
+    pub fn children(&self) -> &[Child] {
+        &self.children
+    }
+
+    pub fn children_mut(&mut self) -> &mut [Child] {
+        &mut self.children
+    }
+
+    pub fn ratios(&self) -> &[f32] {
+        &self.ratios
+    }
+
+    ///Scaled so they add up to 1, the panes get resized on the next update.
+    pub fn set_ratios(&mut self, ratios: &[f32]) {
+        let total: f32 = ratios.iter().take(self.ratios.len()).sum();
+        if ratios.len() < self.ratios.len() || total <= 0. {
+            return;
+        }
+        for (ratio, new) in self.ratios.iter_mut().zip(ratios) {
+            *ratio = new.max(0.) / total;
+        }
+        self.collapsed.fill(None);
+    }
+
+    pub fn is_collapsed(&self, index: usize) -> bool {
+        self.collapsed.get(index).is_some_and(Option::is_some)
+    }
+
+    ///The start and the length of the rect along the orientation.
+    fn along(&self, rect: FRect) -> (f32, f32) {
+        match self.orientation {
+            Orientation::Horizontal => (rect.x(), rect.width()),
+            Orientation::Vertical => (rect.y(), rect.height()),
+        }
+    }
+
+    fn space(&self) -> f32 {
+        let dividers = self.children.len().saturating_sub(1) as f32 * self.thickness;
+        (self.along(self.surface).1 - dividers).max(0.)
+    }
+
+    ///The rect between from and from + len along the orientation.
+    fn part(&self, from: f32, len: f32) -> FRect {
+        match self.orientation {
+            Orientation::Horizontal => {
+                FRect::new(from, self.surface.y(), len, self.surface.height())
+            }
+            Orientation::Vertical => FRect::new(self.surface.x(), from, self.surface.width(), len),
+        }
+    }
+
+    fn panes(&self) -> Vec<FRect> {
+        let space = self.space();
+        let mut from = self.along(self.surface).0;
+        self.ratios
+            .iter()
+            .map(|ratio| {
+                let pane = self.part(from, ratio * space);
+                from += ratio * space + self.thickness;
+                pane
+            })
+            .collect()
+    }
+
+    fn divider(&self, pane: FRect) -> FRect {
+        let (from, len) = self.along(pane);
+        self.part(from + len, self.thickness)
+    }
+
+    fn divider_at(&self, event: &Event) -> Option<usize> {
+        let panes = self.panes();
+        (0..panes.len().saturating_sub(1)).find(|index| event.hover(self.divider(panes[*index])))
+    }
+
+    ///Moves the divider after the pane to the position, keeping the panes around it inside their min sizes.
+    fn drag(&mut self, index: usize, position: f32) {
+        let space = self.space();
+        if space <= 0. {
+            return;
+        }
+        let (from, _) = self.along(self.panes()[index]);
+        let total = (self.ratios[index] + self.ratios[index + 1]) * space;
+        let (min, max) = (self.min_sizes[index], total - self.min_sizes[index + 1]);
+        if min > max {
+            return;
+        }
+        let size = (position - from - self.thickness / 2.).clamp(min, max);
+        self.ratios[index] = size / space;
+        self.ratios[index + 1] = (total - size) / space;
+        self.collapsed[index] = None;
+    }
+
+    ///Collapses the pane into the next one, or gives it its size back.
+    fn toggle_collapse(&mut self, index: usize) {
+        if let Some(ratio) = self.collapsed[index].take() {
+            let ratio = ratio.min(self.ratios[index] + self.ratios[index + 1]);
+            self.ratios[index + 1] -= ratio - self.ratios[index];
+            self.ratios[index] = ratio;
+        } else {
+            self.collapsed[index] = Some(self.ratios[index]);
+            self.ratios[index + 1] += self.ratios[index];
+            self.ratios[index] = 0.;
+        }
+    }
+
+    fn call_changed(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let t = this;
+        if let Some(changed) = this.changed.as_mut() {
+            changed(t, parent, state, canvas)?;
+        }
+        Ok(())
+    }
+
+    ///Sends their pane to the children which aren't already there.
+    fn reform(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let panes = this.panes();
+        for (child, pane) in this.children.iter_mut().zip(panes) {
+            place(child.into(), pane, canvas, parent, state)?;
+        }
+        Ok(())
+    }
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    UserControl<Parent, State> for Splitter<Parent, State, Child>
+{
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match event {
+            Event::ElementMove { x, y } => {
+                this.surface.set_x(x);
+                this.surface.set_y(y);
+                return Self::reform(this, canvas, parent, state);
+            }
+            Event::ElementResize { width, height } => {
+                this.surface.set_width(width);
+                this.surface.set_height(height);
+                return Self::reform(this, canvas, parent, state);
+            }
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Left,
+                clicks,
+                ..
+            } => {
+                if let Some(index) = this.divider_at(&event) {
+                    if clicks >= 2 {
+                        this.dragged = None;
+                        this.toggle_collapse(index);
+                        Self::reform(this, canvas, parent, state)?;
+                        return Self::call_changed(this, canvas, parent, state);
+                    }
+                    this.dragged = Some(index);
+                    return Ok(());
+                }
+            }
+            Event::MouseMotion { x, y, .. } => {
+                if let Some(index) = this.dragged {
+                    let position = match this.orientation {
+                        Orientation::Horizontal => x,
+                        Orientation::Vertical => y,
+                    };
+                    this.drag(index, position);
+                    Self::reform(this, canvas, parent, state)?;
+                    return Self::call_changed(this, canvas, parent, state);
+                }
+            }
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                ..
+            } if this.dragged.is_some() => {
+                this.dragged = None;
+                return Ok(());
+            }
+            _ => {}
+        }
+        for child in this.children.iter_mut() {
+            UserControl::event(child.into(), canvas, event.clone(), parent, state)?;
+        }
+        Ok(())
+    }
+
+    fn update(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        elapsed: Duration,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        Self::reform(this, canvas, parent, state)?;
+        for child in this.children.iter_mut() {
+            UserControl::update(child.into(), canvas, elapsed, parent, state)?;
+        }
+        Ok(())
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        for child in &this.children {
+            UserControl::draw(child.into(), canvas, parent, state)?;
+        }
+        canvas.set_draw_color((this.divider_color)(this, parent, state));
+        let panes = this.panes();
+        for pane in panes.iter().take(panes.len().saturating_sub(1)) {
+            canvas
+                .fill_frect(this.divider(*pane))
+                .map_err(|e| anyhow!(e))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod splitter_test {
+    use sdl2::pixels::Color;
+
+    use crate::ui_element::stack_panel::stack_panel_test::block;
+
+    use super::*;
+
+    pub(crate) fn test_splitter_drag(canvas: &mut Canvas<Window>) {
+        let mut splitter = Splitter::new(
+            Orientation::Horizontal,
+            vec![block(0., 0.), block(0., 0.)],
+            Box::new(|_, _, _| Color::GRAY),
+        )
+        .min_sizes(&[0., 64.]);
+        let splitter = MutRef::new(&mut splitter);
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        let send = |event| UserControl::event(splitter, canvas, event, unit, unit).expect("");
+        let press = |x, clicks| Event::MouseButtonDown {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks,
+            x,
+            y: 5.,
+        };
+        let widths = || -> Vec<f32> {
+            splitter
+                .children()
+                .iter()
+                .map(|pane| pane.surface.width())
+                .collect()
+        };
+        send(Event::ElementResize {
+            width: 262.,
+            height: 10.,
+        });
+        assert_eq!(widths(), [128., 128.]);
+        assert_eq!(splitter.children()[1].surface.x(), 134.);
+
+        send(press(131., 1));
+        send(Event::MouseMotion {
+            which: 0,
+            mousestate: sdl2::mouse::MouseState::from_sdl_state(1),
+            x: 300.,
+            y: 5.,
+            moved_x: 169.,
+            moved_y: 0.,
+        });
+        send(Event::MouseButtonUp {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 300.,
+            y: 5.,
+        });
+        assert_eq!(widths(), [192., 64.]);
+        assert_eq!(splitter.ratios(), [0.75, 0.25]);
+
+        send(press(195., 2));
+        assert!(splitter.is_collapsed(0));
+        assert_eq!(widths(), [0., 256.]);
+        send(press(3., 2));
+        assert_eq!(widths(), [192., 64.]);
+    }
+}
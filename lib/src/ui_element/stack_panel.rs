@@ -0,0 +1,317 @@
+use std::{marker::PhantomData, time::Duration};
+
+use anyhow::Result;
+use sdl2::{rect::FRect, render::Canvas, video::Window};
+
+use crate::{
+    event::Event,
+    refs::{MutRef, Ref},
+    ui_element::{place, Alignment, Orientation},
+    user_control::UserControl,
+    zero,
+};
+
+///The size a child of a stack or a wrap panel asks for and where it goes across its line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StackItem {
+    pub width: f32,
+    pub height: f32,
+    ///A stretched child takes the whole line across the orientation.
+    pub alignment: Alignment,
+}
+
+impl StackItem {
+    pub const fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            alignment: Alignment::Start,
+        }
+    }
+
+    pub const fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    ///The length along the orientation and the length across it.
+    pub(crate) fn lens(self, orientation: Orientation) -> (f32, f32) {
+        let (_, len, _, cross_len) = orientation.split(FRect::new(0., 0., self.width, self.height));
+        (len, cross_len)
+    }
+}
+
+///Puts its children one after the other, each one taking the size of its item along the orientation.</br>
+///The alignment of each item places the child across the orientation.
+pub struct StackPanel<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+{
+    parent: PhantomData<Parent>,
+    statel: PhantomData<State>,
+    surface: FRect,
+    orientation: Orientation,
+    spacing: f32,
+    children: Vec<(Child, StackItem)>,
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    StackPanel<Parent, State, Child>
+{
+    pub fn new(orientation: Orientation, children: Vec<(Child, StackItem)>) -> Self {
+        Self {
+            parent: PhantomData,
+            statel: PhantomData,
+            surface: zero(),
+            orientation,
+            spacing: 0.,
+            children,
+        }
+    }
+
+    pub const fn surface(&self) -> FRect {
+        self.surface
+    }
+
+    pub const fn spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn children(&self) -> &[(Child, StackItem)] {
+        &self.children
+    }
+
+    pub fn children_mut(&mut self) -> &mut Vec<(Child, StackItem)> {
+        &mut self.children
+    }
+
+    ///The length the children take along the orientation, for a scroll view around the panel.
+    pub fn content_len(&self) -> f32 {
+        let lens: f32 = self
+            .children
+            .iter()
+            .map(|(_, item)| item.lens(self.orientation).0)
+            .sum();
+        lens + self.children.len().saturating_sub(1) as f32 * self.spacing
+    }
+
+    fn reform(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let (orientation, spacing) = (this.orientation, this.spacing);
+        let (mut main, _, cross, cross_len) = orientation.split(this.surface);
+        for (child, item) in this.children.iter_mut() {
+            let (len, child_cross_len) = item.lens(orientation);
+            let (child_cross, child_cross_len) =
+                item.alignment.place(cross, cross_len, child_cross_len);
+            place(
+                child.into(),
+                orientation.join(main, len, child_cross, child_cross_len),
+                canvas,
+                parent,
+                state,
+            )?;
+            main += len + spacing;
+        }
+        Ok(())
+    }
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    UserControl<Parent, State> for StackPanel<Parent, State, Child>
+{
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match event {
+            Event::ElementMove { x, y } => {
+                this.surface.set_x(x);
+                this.surface.set_y(y);
+                return Self::reform(this, canvas, parent, state);
+            }
+            Event::ElementResize { width, height } => {
+                this.surface.set_width(width);
+                this.surface.set_height(height);
+                return Self::reform(this, canvas, parent, state);
+            }
+            _ => {}
+        }
+        for (child, _) in this.children.iter_mut() {
+            UserControl::event(child.into(), canvas, event.clone(), parent, state)?;
+        }
+        Ok(())
+    }
+
+    fn update(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        elapsed: Duration,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        for (child, _) in this.children.iter_mut() {
+            UserControl::update(child.into(), canvas, elapsed, parent, state)?;
+        }
+        Self::reform(this, canvas, parent, state)
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        for (child, _) in &this.children {
+            UserControl::draw(child.into(), canvas, parent, state)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod stack_panel_test {
+    use super::*;
+
+    pub(crate) struct Block {
+        pub(crate) surface: FRect,
+        pub(crate) updates: usize,
+    }
+
+    impl UserControl<(), ()> for Block {
+        fn surface(this: Ref<Self>, _: Ref<()>, _: Ref<()>) -> FRect {
+            this.surface
+        }
+
+        fn event(
+            mut this: MutRef<Self>,
+            _: &Canvas<Window>,
+            event: Event,
+            _: MutRef<()>,
+            _: MutRef<()>,
+        ) -> Result<()> {
+            match event {
+                Event::ElementMove { x, y } => {
+                    this.surface.set_x(x);
+                    this.surface.set_y(y);
+                }
+                Event::ElementResize { width, height } => {
+                    this.surface.set_width(width);
+                    this.surface.set_height(height);
+                }
+                _ => {}
+            }
+            Ok(())
+        }
+
+        fn update(
+            mut this: MutRef<Self>,
+            _: &Canvas<Window>,
+            _: Duration,
+            _: MutRef<()>,
+            _: MutRef<()>,
+        ) -> Result<()> {
+            this.updates += 1;
+            Ok(())
+        }
+
+        fn draw(_: Ref<Self>, _: &mut Canvas<Window>, _: Ref<()>, _: Ref<()>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    pub(crate) fn block(width: f32, height: f32) -> Block {
+        Block {
+            surface: FRect::new(0., 0., width, height),
+            updates: 0,
+        }
+    }
+
+    pub(crate) fn test_stack_panel_layout(canvas: &mut Canvas<Window>) {
+        let mut stack = StackPanel::new(
+            Orientation::Vertical,
+            vec![
+                (block(0., 0.), StackItem::new(10., 10.)),
+                (
+                    block(0., 0.),
+                    StackItem::new(10., 20.).alignment(Alignment::Center),
+                ),
+                (
+                    block(0., 0.),
+                    StackItem::new(10., 5.).alignment(Alignment::End),
+                ),
+                (
+                    block(0., 0.),
+                    StackItem::new(10., 10.).alignment(Alignment::Stretch),
+                ),
+            ],
+        )
+        .spacing(2.);
+        let mut stack = MutRef::new(&mut stack);
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        UserControl::event(
+            stack,
+            canvas,
+            Event::ElementMove { x: 5., y: 5. },
+            unit,
+            unit,
+        )
+        .expect("");
+        UserControl::event(
+            stack,
+            canvas,
+            Event::ElementResize {
+                width: 40.,
+                height: 100.,
+            },
+            unit,
+            unit,
+        )
+        .expect("");
+
+        let surfaces: Vec<FRect> = stack
+            .children()
+            .iter()
+            .map(|(child, _)| child.surface)
+            .collect();
+        assert_eq!(
+            surfaces,
+            [
+                FRect::new(5., 5., 10., 10.),
+                FRect::new(20., 17., 10., 20.),
+                FRect::new(35., 39., 10., 5.),
+                FRect::new(5., 46., 40., 10.),
+            ]
+        );
+        assert_eq!(stack.content_len(), 51.);
+
+        //A shorter item moves the children after it, the stretched one keeps the height of its item.
+        stack.children_mut()[0].1 = StackItem::new(10., 4.);
+        UserControl::update(stack, canvas, Duration::ZERO, unit, unit).expect("");
+        let surfaces: Vec<FRect> = stack
+            .children()
+            .iter()
+            .map(|(child, _)| child.surface)
+            .collect();
+        assert_eq!(
+            surfaces,
+            [
+                FRect::new(5., 5., 10., 4.),
+                FRect::new(20., 11., 10., 20.),
+                FRect::new(35., 33., 10., 5.),
+                FRect::new(5., 40., 40., 10.),
+            ]
+        );
+        assert_eq!(stack.content_len(), 45.);
+    }
+}
@@ -0,0 +1,646 @@
+use std::{marker::PhantomData, time::Duration};
+
+use anyhow::{anyhow, Result};
+use sdl2::{
+    keyboard::{Keycode, Mod},
+    mouse::MouseButton,
+    rect::{FPoint, FRect},
+    render::Canvas,
+    video::Window,
+};
+
+use crate::{
+    event::Event,
+    functions::{FnAction, FnColor},
+    missing::ui_string::UIString,
+    refs::{MutRef, Ref},
+    ui_element::place,
+    user_control::UserControl,
+    zero,
+};
+
+const PADDING: f32 = 8.;
+///The side of the close button of the tabs.
+const CLOSE: f32 = 14.;
+
+///It gets called with the closed tab once it is removed.
+pub type FnTabClosed<Parent, State, Child> = Box<
+    dyn FnMut(
+        MutRef<TabControl<Parent, State, Child>>,
+        UIString,
+        Child,
+        MutRef<Parent>,
+        MutRef<State>,
+        &Canvas<Window>,
+    ) -> Result<()>,
+>;
+
+///Children behind a strip of tabs, only the selected one gets updated and drawn.
+pub struct TabControl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+{
+    parent: PhantomData<Parent>,
+    statel: PhantomData<State>,
+    surface: FRect,
+    tabs: Vec<(UIString, Child)>,
+    selected: usize,
+    header_height: f32,
+    closeable: bool,
+    reorderable: bool,
+    ///The tab following the mouse while it is held down.
+    dragged: Option<usize>,
+    hover: Option<usize>,
+    ///It gets called when the selected tab changes.
+    changed: Option<FnAction<Self, Parent, State>>,
+    closed: Option<FnTabClosed<Parent, State, Child>>,
+    back_color: FnColor<Self, Parent, State>,
+    front_color: FnColor<Self, Parent, State>,
+    select_color: FnColor<Self, Parent, State>,
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    TabControl<Parent, State, Child>
+{
+    pub fn new(
+        tabs: Vec<(UIString, Child)>,
+        back_color: FnColor<Self, Parent, State>,
+        front_color: FnColor<Self, Parent, State>,
+        select_color: FnColor<Self, Parent, State>,
+    ) -> Self {
+        Self {
+            parent: PhantomData,
+            statel: PhantomData,
+            surface: zero(),
+            tabs,
+            selected: 0,
+            header_height: 28.,
+            closeable: false,
+            reorderable: false,
+            dragged: None,
+            hover: None,
+            changed: None,
+            closed: None,
+            back_color,
+            front_color,
+            select_color,
+        }
+    }
+
+    pub const fn surface(&self) -> FRect {
+        self.surface
+    }
+
+    pub const fn header_height(mut self, header_height: f32) -> Self {
+        self.header_height = header_height;
+        self
+    }
+
+    ///Shows a close button on each tab.
+    pub const fn closeable(mut self, closeable: bool) -> Self {
+        self.closeable = closeable;
+        self
+    }
+
+    ///Lets the user drag the tabs to reorder them.
+    pub const fn reorderable(mut self, reorderable: bool) -> Self {
+        self.reorderable = reorderable;
+        self
+    }
+
+    pub fn changed(mut self, changed: FnAction<Self, Parent, State>) -> Self {
+        self.changed = Some(changed);
+        self
+    }
+
+    pub fn closed(mut self, closed: FnTabClosed<Parent, State, Child>) -> Self {
+        self.closed = Some(closed);
+        self
+    }
+
+    pub fn tabs(&self) -> &[(UIString, Child)] {
+        &self.tabs
+    }
+
+    pub const fn len(&self) -> usize {
+        self.tabs.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.tabs.is_empty()
+    }
+
+    pub fn child(&self, index: usize) -> Option<&Child> {
+        self.tabs.get(index).map(|(_, child)| child)
+    }
+
+    pub fn child_mut(&mut self, index: usize) -> Option<&mut Child> {
+        self.tabs.get_mut(index).map(|(_, child)| child)
+    }
+
+    pub const fn selected(&self) -> usize {
+        self.selected
+    }
+
+    ///The new tab gets placed on the next update.
+    pub fn push(&mut self, title: UIString, child: Child) {
+        self.tabs.push((title, child));
+    }
+
+    ///The new tab gets placed on the next update.
+    pub fn insert(&mut self, index: usize, title: UIString, child: Child) {
+        self.tabs.insert(index, (title, child));
+        if index <= self.selected && self.tabs.len() > 1 {
+            self.selected += 1;
+        }
+    }
+
+    ///Removes the tab without calling closed, the selection stays on the same tab when possible.
+    pub fn remove(&mut self, index: usize) -> Option<(UIString, Child)> {
+        if index >= self.tabs.len() {
+            return None;
+        }
+        let tab = self.tabs.remove(index);
+        if index < self.selected || self.selected >= self.tabs.len() {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        self.dragged = None;
+        self.hover = None;
+        Some(tab)
+    }
+
+    pub fn select(
+        mut this: MutRef<Self>,
+        index: usize,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        if index >= this.tabs.len() || index == this.selected {
+            return Ok(());
+        }
+        this.selected = index;
+        let t = this;
+        if let Some(changed) = this.changed.as_mut() {
+            changed(t, parent, state, canvas)?;
+        }
+        Ok(())
+    }
+
+    ///Removes the tab and gives it to closed.
+    pub fn close(
+        mut this: MutRef<Self>,
+        index: usize,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let selected = this.selected;
+        let Some((title, child)) = this.remove(index) else {
+            return Ok(());
+        };
+        let t = this;
+        if let Some(closed) = this.closed.as_mut() {
+            closed(t, title, child, parent, state, canvas)?;
+        }
+        if index == selected && !this.tabs.is_empty() {
+            let t = this;
+            if let Some(changed) = this.changed.as_mut() {
+                changed(t, parent, state, canvas)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn content(&self) -> FRect {
+        FRect::new(
+            self.surface.x(),
+            self.surface.y() + self.header_height,
+            self.surface.width(),
+            (self.surface.height() - self.header_height).max(0.),
+        )
+    }
+
+    fn tab(&self, index: usize) -> Result<FRect> {
+        let mut x = self.surface.x();
+        let mut width = 0.;
+        for (title, _) in &self.tabs[..=index] {
+            x += width;
+            width = if title.is_empty() {
+                0.
+            } else {
+                title.size()?.0
+            } + 2. * PADDING;
+            if self.closeable {
+                width += CLOSE + PADDING / 2.;
+            }
+        }
+        Ok(FRect::new(x, self.surface.y(), width, self.header_height))
+    }
+
+    fn close_button(&self, tab: FRect) -> FRect {
+        FRect::new(
+            tab.x() + tab.width() - PADDING - CLOSE,
+            tab.y() + (tab.height() - CLOSE) / 2.,
+            CLOSE,
+            CLOSE,
+        )
+    }
+
+    fn tab_at(&self, x: f32, y: f32) -> Result<Option<usize>> {
+        for index in 0..self.tabs.len() {
+            if self.tab(index)?.contains_point(FPoint::new(x, y)) {
+                return Ok(Some(index));
+            }
+        }
+        Ok(None)
+    }
+
+    ///Sends the content surface to the children which aren't already there.
+    fn reform(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let content = this.content();
+        for (_, child) in this.tabs.iter_mut() {
+            place(child.into(), content, canvas, parent, state)?;
+        }
+        Ok(())
+    }
+
+    fn header_event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: &Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match *event {
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => {
+                let Some(index) = this.tab_at(x, y)? else {
+                    return Ok(());
+                };
+                if this.closeable
+                    && this
+                        .close_button(this.tab(index)?)
+                        .contains_point(FPoint::new(x, y))
+                {
+                    return Self::close(this, index, canvas, parent, state);
+                }
+                Self::select(this, index, canvas, parent, state)?;
+                if this.reorderable {
+                    this.dragged = Some(index);
+                }
+            }
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Middle,
+                x,
+                y,
+                ..
+            } if this.closeable => {
+                if let Some(index) = this.tab_at(x, y)? {
+                    Self::close(this, index, canvas, parent, state)?;
+                }
+            }
+            Event::MouseMotion { x, y, .. } => this.hover = this.tab_at(x, y)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    ///Moves the dragged tab under the mouse.
+    fn drag(mut this: MutRef<Self>, x: f32) -> Result<()> {
+        let Some(dragged) = this.dragged else {
+            return Ok(());
+        };
+        let y = this.surface.y();
+        let last = this.tabs.len() - 1;
+        let target = match this.tab_at(x, y)? {
+            Some(target) => target,
+            None if x < this.surface.x() => 0,
+            None if x >= this.tab(last)?.x() => last,
+            None => return Ok(()),
+        };
+        if target != dragged {
+            let tab = this.tabs.remove(dragged);
+            this.tabs.insert(target, tab);
+            this.selected = target;
+            this.dragged = Some(target);
+        }
+        Ok(())
+    }
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    UserControl<Parent, State> for TabControl<Parent, State, Child>
+{
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match event {
+            Event::ElementMove { x, y } => {
+                this.surface.set_x(x);
+                this.surface.set_y(y);
+                return Self::reform(this, canvas, parent, state);
+            }
+            Event::ElementResize { width, height } => {
+                this.surface.set_width(width);
+                this.surface.set_height(height);
+                return Self::reform(this, canvas, parent, state);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Tab),
+                keymod,
+                ..
+            } if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) && !this.tabs.is_empty() => {
+                let len = this.tabs.len();
+                let next = if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+                    (this.selected + len - 1) % len
+                } else {
+                    (this.selected + 1) % len
+                };
+                return Self::select(this, next, canvas, parent, state);
+            }
+            Event::MouseMotion { x, .. } if this.dragged.is_some() => return Self::drag(this, x),
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                ..
+            } if this.dragged.is_some() => {
+                this.dragged = None;
+                return Ok(());
+            }
+            _ => {}
+        }
+        let header = FRect::new(
+            this.surface.x(),
+            this.surface.y(),
+            this.surface.width(),
+            this.header_height,
+        );
+        let pointer = matches!(
+            event,
+            Event::MouseMotion { .. }
+                | Event::MouseButtonDown { .. }
+                | Event::MouseButtonUp { .. }
+                | Event::MouseWheel { .. }
+        );
+        if pointer && event.hover(header) {
+            Self::header_event(this, canvas, &event, parent, state)?;
+            //The selected child still sees the mouse leaving it.
+            if !matches!(event, Event::MouseMotion { .. }) {
+                return Ok(());
+            }
+        } else if pointer {
+            this.hover = None;
+        }
+        let selected = this.selected;
+        if let Some((_, child)) = this.tabs.get_mut(selected) {
+            UserControl::event(child.into(), canvas, event, parent, state)?;
+        }
+        Ok(())
+    }
+
+    fn update(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        elapsed: Duration,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        Self::reform(this, canvas, parent, state)?;
+        let selected = this.selected;
+        if let Some((_, child)) = this.tabs.get_mut(selected) {
+            UserControl::update(child.into(), canvas, elapsed, parent, state)?;
+        }
+        Ok(())
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        let front_color = (this.front_color)(this, parent, state);
+        canvas.set_draw_color((this.back_color)(this, parent, state));
+        canvas
+            .fill_frect(FRect::new(
+                this.surface.x(),
+                this.surface.y(),
+                this.surface.width(),
+                this.header_height,
+            ))
+            .map_err(|e| anyhow!(e))?;
+        for (index, (title, _)) in this.tabs.iter().enumerate() {
+            let tab = this.tab(index)?;
+            if index == this.selected || this.hover == Some(index) {
+                canvas.set_draw_color((this.select_color)(this, parent, state));
+                canvas.fill_frect(tab).map_err(|e| anyhow!(e))?;
+            }
+            title.draw_left(
+                canvas,
+                FRect::new(
+                    tab.x() + PADDING,
+                    tab.y(),
+                    tab.width() - 2. * PADDING,
+                    tab.height(),
+                ),
+                front_color,
+            )?;
+            if this.closeable {
+                let close = this.close_button(tab);
+                canvas.set_draw_color(front_color);
+                let (left, top) = (close.x() + 3., close.y() + 3.);
+                let (right, bottom) = (close.x() + CLOSE - 3., close.y() + CLOSE - 3.);
+                canvas
+                    .draw_fline(FPoint::new(left, top), FPoint::new(right, bottom))
+                    .map_err(|e| anyhow!(e))?;
+                canvas
+                    .draw_fline(FPoint::new(left, bottom), FPoint::new(right, top))
+                    .map_err(|e| anyhow!(e))?;
+            }
+        }
+        if let Some((_, child)) = this.tabs.get(this.selected) {
+            UserControl::draw(child.into(), canvas, parent, state)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tab_control_test {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    use sdl2::{mouse::MouseState, pixels::Color};
+
+    use crate::ui_element::stack_panel::stack_panel_test::{block, Block};
+
+    use super::*;
+
+    pub(crate) fn test_tab_control_switch(canvas: &mut Canvas<Window>) {
+        let page = || (UIString::default(), block(0., 0.));
+        let mut tabs = TabControl::new(
+            vec![page(), page(), page()],
+            Box::new(|_, _, _| Color::WHITE),
+            Box::new(|_, _, _| Color::BLACK),
+            Box::new(|_, _, _| Color::GRAY),
+        )
+        .header_height(10.);
+        let mut tabs = MutRef::new(&mut tabs);
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        let send = |event| UserControl::event(tabs, canvas, event, unit, unit).expect("");
+        let update = || UserControl::update(tabs, canvas, Duration::ZERO, unit, unit).expect("");
+        let ctrl_tab = |keymod| Event::KeyDown {
+            keycode: Some(Keycode::Tab),
+            scancode: None,
+            keymod,
+            repeat: false,
+        };
+        send(Event::ElementResize {
+            width: 40.,
+            height: 30.,
+        });
+        assert_eq!(
+            tabs.child(2).expect("").surface,
+            FRect::new(0., 10., 40., 20.)
+        );
+
+        update();
+        send(ctrl_tab(Mod::LCTRLMOD));
+        update();
+        update();
+        let updates: Vec<usize> = tabs.tabs().iter().map(|(_, page)| page.updates).collect();
+        assert_eq!(updates, [1, 2, 0]);
+
+        send(ctrl_tab(Mod::LCTRLMOD | Mod::LSHIFTMOD));
+        send(ctrl_tab(Mod::RCTRLMOD | Mod::LSHIFTMOD));
+        assert_eq!(tabs.selected(), 2);
+        send(ctrl_tab(Mod::NOMOD));
+        assert_eq!(tabs.selected(), 2);
+
+        assert!(tabs.remove(0).is_some());
+        assert_eq!(tabs.selected(), 1);
+        assert!(tabs.remove(1).is_some());
+        assert_eq!(tabs.selected(), 0);
+    }
+
+    #[allow(clippy::too_many_lines)]
+    pub(crate) fn test_tab_control_close(canvas: &mut Canvas<Window>) {
+        //The updates tell the pages apart, none is updated here.
+        let page = |tag| {
+            let mut page = block(0., 0.);
+            page.updates = tag;
+            (UIString::default(), page)
+        };
+        let changes = Rc::new(Cell::new(0));
+        let closed = Rc::new(RefCell::new(Vec::new()));
+        let (counter, log) = (changes.clone(), closed.clone());
+        let mut tabs = TabControl::new(
+            vec![page(0), page(1), page(2), page(3)],
+            Box::new(|_, _, _| Color::WHITE),
+            Box::new(|_, _, _| Color::BLACK),
+            Box::new(|_, _, _| Color::GRAY),
+        )
+        .header_height(10.)
+        .closeable(true)
+        .reorderable(true)
+        .changed(Box::new(move |_, _, _, _| {
+            counter.set(counter.get() + 1);
+            Ok(())
+        }))
+        .closed(Box::new(move |_, _, page: Block, _, _, _| {
+            log.borrow_mut().push(page.updates);
+            Ok(())
+        }));
+        let mut tabs = MutRef::new(&mut tabs);
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        let canvas = &*canvas;
+        let send = move |event| UserControl::event(tabs, canvas, event, unit, unit).expect("");
+        let down = |mouse_btn, x| Event::MouseButtonDown {
+            which: 0,
+            mouse_btn,
+            clicks: 1,
+            x,
+            y: 5.,
+        };
+        let motion = |x| Event::MouseMotion {
+            which: 0,
+            mousestate: MouseState::from_sdl_state(1),
+            x,
+            y: 5.,
+            moved_x: 0.,
+            moved_y: 0.,
+        };
+        let up = Event::MouseButtonUp {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 0.,
+            y: 5.,
+        };
+        let tags =
+            move || -> Vec<usize> { tabs.tabs().iter().map(|(_, page)| page.updates).collect() };
+        send(Event::ElementResize {
+            width: 150.,
+            height: 30.,
+        });
+
+        //Without titles the tabs are 34px wide, their close button goes from 12 to 26.
+        assert_eq!(tabs.tab(1).expect(""), FRect::new(34., 0., 34., 10.));
+
+        //The dragged tab follows the mouse and stays selected.
+        send(down(MouseButton::Left, 39.));
+        assert_eq!(tabs.selected(), 1);
+        send(motion(110.));
+        assert_eq!(tags(), [0, 2, 3, 1]);
+        assert_eq!(tabs.selected(), 3);
+        send(up.clone());
+        send(motion(5.));
+        assert_eq!(tags(), [0, 2, 3, 1]);
+        assert_eq!(changes.get(), 1);
+
+        //The close button closes its tab, the selection stays on the same page.
+        send(down(MouseButton::Left, 17.));
+        assert_eq!(tags(), [2, 3, 1]);
+        assert_eq!(tabs.selected(), 2);
+        assert_eq!(changes.get(), 1);
+
+        //So does the middle button anywhere on the tab, closing the selected one selects another.
+        send(down(MouseButton::Middle, 73.));
+        assert_eq!(tags(), [2, 3]);
+        assert_eq!(tabs.selected(), 1);
+        assert_eq!(changes.get(), 2);
+        assert_eq!(*closed.borrow(), [0, 1]);
+
+        //Dragging to the left.
+        send(down(MouseButton::Left, 39.));
+        send(motion(5.));
+        send(up);
+        assert_eq!(tags(), [3, 2]);
+        assert_eq!(tabs.selected(), 0);
+        assert_eq!(changes.get(), 2);
+
+        //Removing a tab doesn't call closed.
+        assert!(tabs.remove(0).is_some());
+        assert_eq!(tags(), [2]);
+        assert_eq!(*closed.borrow(), [0, 1]);
+    }
+}
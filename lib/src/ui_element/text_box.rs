@@ -1,7 +1,7 @@
 use std::{marker::PhantomData, time::Duration};
 
 use crate::{
-    event::Event,
+    event::{mark_handled, Event},
     functions::{FnColor, FnState, StateEnum},
     missing::{
         clipboard::{get_clipboard_text, set_clipboard_text},
@@ -76,6 +76,11 @@ impl<Parent: 'static, State: 'static> TextBox<Parent, State> {
         self.selected = Some((index, to_index));
     }
 
+    ///Puts the caret at the end of the text so the user can type in it.
+    pub fn focus(&mut self) {
+        self.select(self.text.len(), None);
+    }
+
     pub fn unselect(&mut self) {
         self.selected = None;
     }
@@ -282,6 +287,7 @@ impl<Parent: 'static, State: 'static> UserControl<Parent, State> for TextBox<Par
                 },
             ) => {
                 if let Some((mut index, to_index)) = this.selected {
+                    mark_handled();
                     match keycode {
                         Keycode::Backspace => {
                             if let Some(to_index) = to_index {
@@ -0,0 +1,338 @@
+use std::{marker::PhantomData, time::Duration};
+
+use anyhow::{anyhow, Result};
+use sdl2::{
+    gfx::primitives::DrawRenderer, mouse::MouseButton, rect::FRect, render::Canvas, video::Window,
+};
+
+use crate::{
+    event::Event,
+    functions::{FnAction, FnColor, FnState, FnText, StateEnum},
+    refs::{MutRef, Ref},
+    user_control::UserControl,
+    zero,
+};
+
+///A switch the user turns on and off by clicking on it, with its label on the right.
+pub struct ToggleSwitch<Parent: 'static, State: 'static> {
+    parent: PhantomData<Parent>,
+    statel: PhantomData<State>,
+    surface: FRect,
+    on: bool,
+    ///Where the knob is drawn, from 0 when off to 1 when on.
+    knob: f32,
+    ///How many times per second the knob travels the whole track.
+    speed: f32,
+    ///It gets called when the user turns the switch on or off.
+    changed: Option<FnAction<Self, Parent, State>>,
+    text: Option<FnText<Self, Parent, State>>,
+    state: FnState<Self, Parent, State>,
+    off_color: FnColor<Self, Parent, State>,
+    on_color: FnColor<Self, Parent, State>,
+    knob_color: FnColor<Self, Parent, State>,
+    hover: bool,
+}
+
+impl<Parent: 'static, State: 'static> ToggleSwitch<Parent, State> {
+    pub fn new(
+        state: FnState<Self, Parent, State>,
+        off_color: FnColor<Self, Parent, State>,
+        on_color: FnColor<Self, Parent, State>,
+        knob_color: FnColor<Self, Parent, State>,
+    ) -> Self {
+        Self {
+            parent: PhantomData,
+            statel: PhantomData,
+            surface: zero(),
+            on: false,
+            knob: 0.,
+            speed: 8.,
+            changed: None,
+            text: None,
+            state,
+            off_color,
+            on_color,
+            knob_color,
+            hover: false,
+        }
+    }
+
+    pub const fn surface(&self) -> FRect {
+        self.surface
+    }
+
+    pub const fn on(mut self, on: bool) -> Self {
+        self.on = on;
+        self.knob = if on { 1. } else { 0. };
+        self
+    }
+
+    ///0 moves the knob instantly.
+    pub const fn speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn changed(mut self, changed: FnAction<Self, Parent, State>) -> Self {
+        self.changed = Some(changed);
+        self
+    }
+
+    pub fn changed_mut(&mut self) -> &mut Option<FnAction<Self, Parent, State>> {
+        &mut self.changed
+    }
+
+    pub fn text(mut self, text: FnText<Self, Parent, State>) -> Self {
+        self.text = Some(text);
+        self
+    }
+
+    pub fn text_mut(&mut self) -> &mut Option<FnText<Self, Parent, State>> {
+        &mut self.text
+    }
+
+    pub fn state_mut(&mut self) -> &mut FnState<Self, Parent, State> {
+        &mut self.state
+    }
+
+    pub const fn is_on(&self) -> bool {
+        self.on
+    }
+
+    ///Moves the knob straight to the new side, only the user's clicks animate it.
+    pub fn set_on(&mut self, on: bool) {
+        self.on = on;
+        self.knob = if on { 1. } else { 0. };
+    }
+
+    pub const fn hover(&self) -> bool {
+        self.hover
+    }
+
+    ///The track on the left of the surface, twice as wide as high.
+    fn track(&self) -> FRect {
+        let height = (self.surface.height() * 0.6).min(self.surface.width() / 2.);
+        FRect::new(
+            self.surface.x() + (self.surface.height() - height) / 2.,
+            self.surface.y() + (self.surface.height() - height) / 2.,
+            height * 2.,
+            height,
+        )
+    }
+}
+
+impl<Parent: 'static, State: 'static> UserControl<Parent, State> for ToggleSwitch<Parent, State> {
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        if let Event::ElementMove { x, y } = event {
+            this.surface.set_x(x);
+            this.surface.set_y(y);
+            return Ok(());
+        }
+        if let Event::ElementResize { width, height } = event {
+            this.surface.set_width(width);
+            this.surface.set_height(height);
+            return Ok(());
+        }
+        if (this.state)(this.into(), parent.into(), state.into()) != StateEnum::Enable {
+            return Ok(());
+        }
+        match (event.hover(this.surface), event) {
+            (
+                true,
+                Event::MouseButtonDown {
+                    mouse_btn: MouseButton::Left,
+                    ..
+                },
+            ) => {
+                this.on = !this.on;
+                let t = this;
+                if let Some(changed) = this.changed.as_mut() {
+                    changed(t, parent, state, canvas)?;
+                }
+            }
+            (true, Event::MouseMotion { .. }) => {
+                this.hover = true;
+            }
+            (false, _) => this.hover = false,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn update(
+        mut this: MutRef<Self>,
+        _: &Canvas<Window>,
+        elapsed: Duration,
+        _: MutRef<Parent>,
+        _: MutRef<State>,
+    ) -> Result<()> {
+        let target = if this.on { 1. } else { 0. };
+        let step = this.speed * elapsed.as_secs_f32();
+        //Without elapsed time, like in run_event, it jumps straight to the target.
+        this.knob = if this.speed <= 0. || elapsed.is_zero() || (target - this.knob).abs() <= step {
+            target
+        } else if target > this.knob {
+            this.knob + step
+        } else {
+            this.knob - step
+        };
+        Ok(())
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        if (this.state)(this, parent, state) == StateEnum::Hidden {
+            return Ok(());
+        }
+        let track = this.track();
+        let radius = track.height() / 2.;
+        let track_color = if this.on {
+            (this.on_color)(this, parent, state)
+        } else {
+            (this.off_color)(this, parent, state)
+        };
+        canvas
+            .rounded_box(
+                track.x() as i16,
+                track.y() as i16,
+                (track.x() + track.width()) as i16,
+                (track.y() + track.height()) as i16,
+                radius as i16,
+                track_color,
+            )
+            .map_err(|e| anyhow!(e))?;
+        let knob_x = track.x() + radius + this.knob * (track.width() - 2. * radius);
+        canvas
+            .filled_circle(
+                knob_x as i16,
+                (track.y() + radius) as i16,
+                (radius * 0.8) as i16,
+                (this.knob_color)(this, parent, state),
+            )
+            .map_err(|e| anyhow!(e))?;
+        if let Some(text) = this.text.as_ref() {
+            if let (Some(text), color) = text(this, parent, state)? {
+                let left = track.x() + track.width() + radius - this.surface.x();
+                text.draw_left(
+                    canvas,
+                    FRect::new(
+                        this.surface.x() + left,
+                        this.surface.y(),
+                        (this.surface.width() - left).max(0.),
+                        this.surface.height(),
+                    ),
+                    color,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod toggle_switch_test {
+    use sdl2::pixels::Color;
+
+    use super::*;
+
+    pub(crate) fn test_toggle_switch(canvas: &mut Canvas<Window>) {
+        let mut changes = 0_usize;
+        let changes = MutRef::new(&mut changes);
+        let mut enabled = true;
+        let mut enabled = MutRef::new(&mut enabled);
+        let mut toggle = ToggleSwitch::new(
+            Box::new(|_, enabled: Ref<bool>, _| {
+                if *enabled {
+                    StateEnum::Enable
+                } else {
+                    StateEnum::Showing
+                }
+            }),
+            Box::new(|_, _, _| Color::GRAY),
+            Box::new(|_, _, _| Color::GREEN),
+            Box::new(|_, _, _| Color::WHITE),
+        )
+        .changed(Box::new(|_, _, mut changes: MutRef<usize>, _| {
+            *changes += 1;
+            Ok(())
+        }));
+        let mut toggle = MutRef::new(&mut toggle);
+        let canvas = &*canvas;
+        let send =
+            move |event| UserControl::event(toggle, canvas, event, enabled, changes).expect("");
+        let update = move |elapsed| {
+            UserControl::update(toggle, canvas, elapsed, enabled, changes).expect("");
+        };
+        let click = |x| Event::MouseButtonDown {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x,
+            y: 10.,
+        };
+        send(Event::ElementResize {
+            width: 60.,
+            height: 20.,
+        });
+        assert_eq!(toggle.track(), FRect::new(4., 4., 24., 12.));
+
+        //The knob slides over to the new side.
+        send(click(50.));
+        assert!(toggle.is_on());
+        assert_eq!(*changes, 1);
+        assert_eq!(toggle.knob, 0.);
+        update(Duration::from_micros(62500));
+        assert_eq!(toggle.knob, 0.5);
+        update(Duration::from_millis(100));
+        assert_eq!(toggle.knob, 1.);
+
+        //Without elapsed time the knob jumps straight to its side.
+        send(click(10.));
+        assert!(!toggle.is_on());
+        update(Duration::ZERO);
+        assert_eq!(toggle.knob, 0.);
+        send(click(50.));
+        assert_eq!(*changes, 3);
+        toggle.set_on(false);
+        assert_eq!(toggle.knob, 0.);
+        toggle.set_on(true);
+        assert_eq!(toggle.knob, 1.);
+
+        send(click(70.));
+        assert!(toggle.is_on());
+        toggle.speed = 0.;
+        send(click(10.));
+        assert!(!toggle.is_on());
+        update(Duration::ZERO);
+        assert_eq!(toggle.knob, 0.);
+        assert_eq!(*changes, 4);
+
+        send(Event::MouseMotion {
+            which: 0,
+            mousestate: sdl2::mouse::MouseState::from_sdl_state(0),
+            x: 10.,
+            y: 10.,
+            moved_x: 0.,
+            moved_y: 0.,
+        });
+        assert!(toggle.hover());
+        *enabled = false;
+        send(click(10.));
+        assert!(!toggle.is_on());
+        assert_eq!(*changes, 4);
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    marker::PhantomData,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use sdl2::{pixels::Color, rect::FRect, render::Canvas, video::Window};
+
+use crate::{
+    event::Event,
+    functions::{FnColor, FnText},
+    overlay,
+    refs::{MutRef, Ref},
+    user_control::UserControl,
+};
+
+///Where the tooltip is drawn from the cursor.
+const OFFSET: (f32, f32) = (12., 18.);
+const PADDING: f32 = 4.;
+
+///Shows a text near the cursor once it stayed over the child for a while.
+pub struct Tooltip<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static> {
+    parent: PhantomData<Parent>,
+    statel: PhantomData<State>,
+    child: Child,
+    text: FnText<Child, Parent, State>,
+    back_color: FnColor<Self, Parent, State>,
+    border_color: FnColor<Self, Parent, State>,
+    ///How long the cursor must stay over the child before the tooltip shows.
+    delay: Duration,
+    ///When the cursor last moved over the child, timed with the clock so run_event shows it too.
+    hovered: Option<Instant>,
+    hover: bool,
+    ///Set by a click or a key so the tooltip waits for the cursor to leave and come back.
+    dismissed: bool,
+    cursor: (f32, f32),
+    shown: bool,
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    Tooltip<Parent, State, Child>
+{
+    pub fn new(child: Child, text: FnText<Child, Parent, State>) -> Self {
+        Self {
+            parent: PhantomData,
+            statel: PhantomData,
+            child,
+            text,
+            back_color: Box::new(|_, _, _| Color::RGB(255, 255, 225)),
+            border_color: Box::new(|_, _, _| Color::RGB(100, 100, 100)),
+            delay: Duration::from_millis(500),
+            hovered: None,
+            hover: false,
+            dismissed: false,
+            cursor: (0., 0.),
+            shown: false,
+        }
+    }
+
+    pub const fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn back_color(mut self, back_color: FnColor<Self, Parent, State>) -> Self {
+        self.back_color = back_color;
+        self
+    }
+
+    pub fn border_color(mut self, border_color: FnColor<Self, Parent, State>) -> Self {
+        self.border_color = border_color;
+        self
+    }
+
+    pub fn text_mut(&mut self) -> &mut FnText<Child, Parent, State> {
+        &mut self.text
+    }
+
+    pub const fn child(&self) -> &Child {
+        &self.child
+    }
+
+    pub fn child_mut(&mut self) -> &mut Child {
+        &mut self.child
+    }
+
+    pub const fn is_shown(&self) -> bool {
+        self.shown
+    }
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    UserControl<Parent, State> for Tooltip<Parent, State, Child>
+{
+    fn surface(this: Ref<Self>, parent: Ref<Parent>, state: Ref<State>) -> FRect {
+        UserControl::surface((&this.child).into(), parent, state)
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        UserControl::event(
+            (&mut this.child).into(),
+            canvas,
+            event.clone(),
+            parent,
+            state,
+        )?;
+        let surface = UserControl::surface((&this.child).into(), parent.into(), state.into());
+        match event {
+            Event::MouseMotion { x, y, .. } if event.hover(surface) => {
+                this.hover = true;
+                this.cursor = (x, y);
+                //The tooltip only shows once the cursor stops moving.
+                if !this.shown {
+                    this.hovered = Some(Instant::now());
+                }
+            }
+            Event::MouseMotion { .. } => {
+                this.hover = false;
+                this.dismissed = false;
+                this.shown = false;
+            }
+            Event::MouseButtonDown { .. } | Event::MouseWheel { .. } | Event::KeyDown { .. }
+                if this.hover =>
+            {
+                this.dismissed = true;
+                this.shown = false;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn update(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        elapsed: Duration,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        UserControl::update((&mut this.child).into(), canvas, elapsed, parent, state)?;
+        if this.hover && !this.dismissed {
+            let delay = this.delay;
+            this.shown = this
+                .hovered
+                .is_some_and(|hovered| hovered.elapsed() >= delay);
+        }
+        Ok(())
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        UserControl::draw((&this.child).into(), canvas, parent, state)?;
+        if !this.shown {
+            return Ok(());
+        }
+        let (Some(text), color) = (this.text)((&this.child).into(), parent, state)? else {
+            return Ok(());
+        };
+        let (width, height) = text.size()?;
+        let (width, height) = (width + 2. * PADDING, height + 2. * PADDING);
+        let (window_width, window_height) = canvas.output_size().map_err(|e| anyhow!(e))?;
+        let (window_width, window_height) = (window_width as f32, window_height as f32);
+        let (x, y) = this.cursor;
+        //Kept inside the window, going above the cursor when there is no room under it.
+        let left = (x + OFFSET.0).min(window_width - width).max(0.);
+        let top = if y + OFFSET.1 + height > window_height {
+            y - height
+        } else {
+            y + OFFSET.1
+        }
+        .max(0.);
+        let surface = FRect::new(left, top, width, height);
+        let back_color = (this.back_color)(this, parent, state);
+        let border_color = (this.border_color)(this, parent, state);
+        overlay::draw_above(Box::new(move |canvas| {
+            canvas.set_draw_color(back_color);
+            canvas.fill_frect(surface).map_err(|e| anyhow!(e))?;
+            canvas.set_draw_color(border_color);
+            canvas.draw_frect(surface).map_err(|e| anyhow!(e))?;
+            text.draw_left(
+                canvas,
+                FRect::new(
+                    left + PADDING,
+                    top + PADDING,
+                    width - 2. * PADDING,
+                    height - 2. * PADDING,
+                ),
+                color,
+            )
+        }));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tooltip_test {
+    use sdl2::mouse::{MouseButton, MouseState};
+
+    use crate::{functions::StateEnum, ui_element::ui_rect::UIRect};
+
+    use super::*;
+
+    pub(crate) fn test_tooltip_delay(canvas: &mut Canvas<Window>) {
+        let mut tooltip = Tooltip::new(
+            UIRect::new(
+                Box::new(|_, _, _| StateEnum::Enable),
+                Box::new(|_, _, _| Color::WHITE),
+            ),
+            Box::new(|_, _, _| Ok((None, Color::BLACK))),
+        )
+        .delay(Duration::from_millis(500));
+        let tooltip = MutRef::new(&mut tooltip);
+        let mut parent = ();
+        let parent = MutRef::new(&mut parent);
+        let mut state = ();
+        let state = MutRef::new(&mut state);
+        let canvas = &*canvas;
+        let send =
+            move |event| UserControl::event(tooltip, canvas, event, parent, state).expect("");
+        //Moves the start of the hover back instead of sleeping.
+        let wait = move |millis| {
+            let mut tooltip = tooltip;
+            tooltip.hovered = tooltip
+                .hovered
+                .and_then(|hovered| hovered.checked_sub(Duration::from_millis(millis)));
+            UserControl::update(tooltip, canvas, Duration::ZERO, parent, state).expect("");
+        };
+        let motion = |x, y| Event::MouseMotion {
+            which: 0,
+            mousestate: MouseState::from_sdl_state(0),
+            x,
+            y,
+            moved_x: 0.,
+            moved_y: 0.,
+        };
+        send(Event::ElementResize {
+            width: 20.,
+            height: 20.,
+        });
+
+        send(motion(5., 5.));
+        wait(300);
+        assert!(!tooltip.is_shown());
+        wait(300);
+        assert!(tooltip.is_shown());
+
+        send(Event::MouseButtonDown {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 5.,
+            y: 5.,
+        });
+        assert!(!tooltip.is_shown());
+        send(motion(6., 6.));
+        wait(600);
+        assert!(!tooltip.is_shown());
+
+        send(motion(30., 30.));
+        send(motion(6., 6.));
+        wait(600);
+        assert!(tooltip.is_shown());
+        send(motion(30., 30.));
+        assert!(!tooltip.is_shown());
+    }
+}
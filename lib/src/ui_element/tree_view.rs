@@ -0,0 +1,991 @@
+use std::{marker::PhantomData, time::Duration};
+
+use anyhow::{anyhow, Result};
+use sdl2::{
+    gfx::primitives::DrawRenderer,
+    keyboard::Keycode,
+    mouse::MouseButton,
+    pixels::Color,
+    rect::{FPoint, FRect},
+    render::Canvas,
+    ttf::Font,
+    video::Window,
+};
+
+use crate::{
+    event::{mark_handled, Event},
+    functions::{FnAction, FnColor},
+    missing::{rect::clipped, ui_string::UIString},
+    refs::{MutRef, Ref},
+    ui_element::scroll_view::{ScrollBarPlacement, ScrollBarStyle},
+    user_control::UserControl,
+    zero,
+};
+
+pub type FnLabel<Item> = Box<dyn Fn(&Item) -> String>;
+///Loads the children of an item the first time it gets expanded.
+pub type FnChildren<Item> = Box<dyn Fn(&Item) -> Result<Vec<Item>>>;
+///Gets called after the user dragged the node from the first path to the second one.
+pub type FnMoved<Element, Parent, State> = Box<
+    dyn FnMut(MutRef<Element>, &[usize], &[usize], MutRef<Parent>, MutRef<State>) -> Result<()>,
+>;
+
+///How far the mouse has to move before a click becomes a drag.
+const DRAG_THRESHOLD: f32 = 5.;
+
+pub struct TreeNode<Item: 'static> {
+    item: Item,
+    ///None until the children get loaded.
+    children: Option<Vec<TreeNode<Item>>>,
+    expanded: bool,
+}
+
+impl<Item: 'static> TreeNode<Item> {
+    ///A node whose children get loaded when it is expanded.
+    pub const fn new(item: Item) -> Self {
+        Self {
+            item,
+            children: None,
+            expanded: false,
+        }
+    }
+
+    pub const fn with_children(item: Item, children: Vec<Self>) -> Self {
+        Self {
+            item,
+            children: Some(children),
+            expanded: false,
+        }
+    }
+
+    pub const fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self
+    }
+
+    pub const fn item(&self) -> &Item {
+        &self.item
+    }
+
+    pub fn item_mut(&mut self) -> &mut Item {
+        &mut self.item
+    }
+
+    pub fn children(&self) -> Option<&[Self]> {
+        self.children.as_deref()
+    }
+
+    pub fn children_mut(&mut self) -> &mut Option<Vec<Self>> {
+        &mut self.children
+    }
+
+    pub const fn is_expanded(&self) -> bool {
+        self.expanded
+    }
+}
+
+fn find<'a, Item>(nodes: &'a [TreeNode<Item>], path: &[usize]) -> Option<&'a TreeNode<Item>> {
+    let (first, rest) = path.split_first()?;
+    let node = nodes.get(*first)?;
+    if rest.is_empty() {
+        Some(node)
+    } else {
+        find(node.children.as_deref()?, rest)
+    }
+}
+
+fn find_mut<'a, Item>(
+    nodes: &'a mut [TreeNode<Item>],
+    path: &[usize],
+) -> Option<&'a mut TreeNode<Item>> {
+    let (first, rest) = path.split_first()?;
+    let node = nodes.get_mut(*first)?;
+    if rest.is_empty() {
+        Some(node)
+    } else {
+        find_mut(node.children.as_deref_mut()?, rest)
+    }
+}
+
+///The loaded children of the node at the path, the roots for an empty path.
+fn siblings_mut<'a, Item>(
+    roots: &'a mut Vec<TreeNode<Item>>,
+    parent: &[usize],
+) -> Option<&'a mut Vec<TreeNode<Item>>> {
+    if parent.is_empty() {
+        Some(roots)
+    } else {
+        find_mut(roots, parent)?.children.as_mut()
+    }
+}
+
+fn flatten<Item>(nodes: &[TreeNode<Item>], path: &mut Vec<usize>, rows: &mut Vec<Vec<usize>>) {
+    for (index, node) in nodes.iter().enumerate() {
+        path.push(index);
+        rows.push(path.clone());
+        if let (true, Some(children)) = (node.expanded, node.children.as_deref()) {
+            flatten(children, path, rows);
+        }
+        path.pop();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DropPlace {
+    Before,
+    Into,
+    After,
+}
+
+///Shows a hierarchy of items with expandable nodes, the children get loaded lazily.
+pub struct TreeView<Parent: 'static, State: 'static, Item: 'static> {
+    parent: PhantomData<Parent>,
+    state: PhantomData<State>,
+    surface: FRect,
+    font: &'static Font<'static, 'static>,
+    roots: Vec<TreeNode<Item>>,
+    label: FnLabel<Item>,
+    loader: Option<FnChildren<Item>>,
+    ///The path of each visible node in the order they are shown.
+    rows: Vec<Vec<usize>>,
+    dirty: bool,
+    row_height: f32,
+    indent: f32,
+    scroll: f32,
+    wheel_step: f32,
+    style: ScrollBarStyle,
+    front_color: FnColor<Self, Parent, State>,
+    back_color: FnColor<Self, Parent, State>,
+    select_color: FnColor<Self, Parent, State>,
+    scroll_color: FnColor<Self, Parent, State>,
+    selected: Option<Vec<usize>>,
+    ///It gets called when the user selects another node.
+    selection_changed: Option<FnAction<Self, Parent, State>>,
+    moved: Option<FnMoved<Self, Parent, State>>,
+    ///The pressed row and where the press started.
+    pressed: Option<(usize, f32)>,
+    dragging: bool,
+    drop: Option<(usize, DropPlace)>,
+    thumb_selected: bool,
+    focused: bool,
+}
+
+impl<Parent: 'static, State: 'static, Item: 'static> TreeView<Parent, State, Item> {
+    pub fn new(
+        font: &'static Font<'static, 'static>,
+        roots: Vec<TreeNode<Item>>,
+        label: FnLabel<Item>,
+        front_color: FnColor<Self, Parent, State>,
+        back_color: FnColor<Self, Parent, State>,
+    ) -> Self {
+        Self {
+            parent: PhantomData,
+            state: PhantomData,
+            surface: zero(),
+            font,
+            roots,
+            label,
+            loader: None,
+            rows: Vec::new(),
+            dirty: true,
+            row_height: 25.,
+            indent: 20.,
+            scroll: 0.,
+            wheel_step: 40.,
+            style: ScrollBarStyle::default(),
+            front_color,
+            back_color,
+            select_color: Box::new(|_, _, _| Color::RGBA(70, 120, 200, 160)),
+            scroll_color: Box::new(|_, _, _| Color::RGB(120, 120, 120)),
+            selected: None,
+            selection_changed: None,
+            moved: None,
+            pressed: None,
+            dragging: false,
+            drop: None,
+            thumb_selected: false,
+            focused: false,
+        }
+    }
+
+    pub fn loader(mut self, loader: FnChildren<Item>) -> Self {
+        self.loader = Some(loader);
+        self
+    }
+
+    pub fn select_color(mut self, select_color: FnColor<Self, Parent, State>) -> Self {
+        self.select_color = select_color;
+        self
+    }
+
+    pub fn scroll_color(mut self, scroll_color: FnColor<Self, Parent, State>) -> Self {
+        self.scroll_color = scroll_color;
+        self
+    }
+
+    pub const fn style(mut self, style: ScrollBarStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub const fn row_height(mut self, row_height: f32) -> Self {
+        self.row_height = row_height;
+        self
+    }
+
+    pub const fn indent(mut self, indent: f32) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    pub fn selection_changed(mut self, selection_changed: FnAction<Self, Parent, State>) -> Self {
+        self.selection_changed = Some(selection_changed);
+        self
+    }
+
+    ///Lets the user reorder the nodes by dragging them.
+    pub fn moved(mut self, moved: FnMoved<Self, Parent, State>) -> Self {
+        self.moved = Some(moved);
+        self
+    }
+
+    pub fn roots(&self) -> &[TreeNode<Item>] {
+        &self.roots
+    }
+
+    ///Gives access to the nodes and rebuilds the visible rows on the next update.
+    pub fn roots_mut(&mut self) -> &mut Vec<TreeNode<Item>> {
+        self.dirty = true;
+        &mut self.roots
+    }
+
+    pub fn node(&self, path: &[usize]) -> Option<&TreeNode<Item>> {
+        find(&self.roots, path)
+    }
+
+    pub fn node_mut(&mut self, path: &[usize]) -> Option<&mut TreeNode<Item>> {
+        self.dirty = true;
+        find_mut(&mut self.roots, path)
+    }
+
+    ///The path of the visible nodes in the order they are shown.
+    pub fn rows(&self) -> &[Vec<usize>] {
+        &self.rows
+    }
+
+    pub fn selected(&self) -> Option<&[usize]> {
+        self.selected.as_deref()
+    }
+
+    pub fn set_selected(&mut self, selected: Option<Vec<usize>>) {
+        self.selected = selected.filter(|path| find(&self.roots, path).is_some());
+    }
+
+    ///Expands the node, loading its children if they never were.
+    pub fn expand(&mut self, path: &[usize]) -> Result<()> {
+        let loader = self.loader.as_ref();
+        let Some(node) = find_mut(&mut self.roots, path) else {
+            return Ok(());
+        };
+        if node.children.is_none() {
+            let children = match loader {
+                Some(loader) => loader(&node.item)?,
+                None => Vec::new(),
+            };
+            node.children = Some(children.into_iter().map(TreeNode::new).collect());
+        }
+        node.expanded = true;
+        self.dirty = true;
+        Ok(())
+    }
+
+    pub fn collapse(&mut self, path: &[usize]) {
+        if let Some(node) = find_mut(&mut self.roots, path) {
+            node.expanded = false;
+            self.dirty = true;
+        }
+    }
+
+    pub fn toggle(&mut self, path: &[usize]) -> Result<()> {
+        if self.node(path).is_some_and(TreeNode::is_expanded) {
+            self.collapse(path);
+            Ok(())
+        } else {
+            self.expand(path)
+        }
+    }
+
+    ///If the node shows an expand toggle, unloaded nodes have one when there is a loader.
+    fn has_children(&self, node: &TreeNode<Item>) -> bool {
+        node.children
+            .as_ref()
+            .map_or(self.loader.is_some(), |children| !children.is_empty())
+    }
+
+    fn layout(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        self.rows.clear();
+        flatten(&self.roots, &mut Vec::new(), &mut self.rows);
+        if let Some(selected) = self.selected.as_ref() {
+            if !self.rows.contains(selected) {
+                self.selected = None;
+            }
+        }
+        self.dirty = false;
+        let scroll = self.scroll;
+        self.set_scroll(scroll);
+    }
+
+    ///Moves the node under the parent at the index, returns where it ended up.
+    fn move_node(&mut self, from: &[usize], parent: &[usize], index: usize) -> Option<Vec<usize>> {
+        let (from_index, from_parent) = from.split_last()?;
+        if parent.starts_with(from) || siblings_mut(&mut self.roots, parent).is_none() {
+            return None;
+        }
+        let siblings = siblings_mut(&mut self.roots, from_parent)?;
+        if *from_index >= siblings.len() {
+            return None;
+        }
+        let node = siblings.remove(*from_index);
+        let mut parent = parent.to_vec();
+        let mut index = index;
+        let depth = from_parent.len();
+        if parent.len() > depth && parent.starts_with(from_parent) && parent[depth] > *from_index {
+            parent[depth] -= 1;
+        } else if parent == from_parent && index > *from_index {
+            index -= 1;
+        }
+        let siblings = siblings_mut(&mut self.roots, &parent)?;
+        let index = index.min(siblings.len());
+        siblings.insert(index, node);
+        parent.push(index);
+        self.dirty = true;
+        Some(parent)
+    }
+
+    fn drop_node(
+        mut this: MutRef<Self>,
+        from: usize,
+        (to, place): (usize, DropPlace),
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        //The rows can change while dragging, the drop is aborted when either row is gone.
+        let (Some(from), Some(target)) = (this.rows.get(from).cloned(), this.rows.get(to).cloned())
+        else {
+            return Ok(());
+        };
+        let Some((index, target_parent)) = target.split_last() else {
+            return Ok(());
+        };
+        let moved = match place {
+            DropPlace::Before => this.move_node(&from, target_parent, *index),
+            DropPlace::After => this.move_node(&from, target_parent, index + 1),
+            DropPlace::Into => {
+                if target.starts_with(&from) {
+                    return Ok(());
+                }
+                this.expand(&target)?;
+                this.move_node(&from, &target, usize::MAX)
+            }
+        };
+        let Some(to) = moved else {
+            return Ok(());
+        };
+        this.selected = Some(to.clone());
+        this.layout();
+        let t = this;
+        if let Some(moved) = this.moved.as_mut() {
+            moved(t, &from, &to, parent, state)?;
+        }
+        Ok(())
+    }
+
+    fn total_height(&self) -> f32 {
+        self.rows.len() as f32 * self.row_height
+    }
+
+    fn max_scroll(&self) -> f32 {
+        (self.total_height() - self.surface.height()).max(0.)
+    }
+
+    pub const fn scroll(&self) -> f32 {
+        self.scroll
+    }
+
+    pub fn set_scroll(&mut self, scroll: f32) {
+        self.scroll = scroll.clamp(0., self.max_scroll());
+    }
+
+    ///Scrolls as little as possible to make the row visible.
+    pub fn scroll_to(&mut self, row: usize) {
+        let top = row as f32 * self.row_height;
+        if top < self.scroll {
+            self.set_scroll(top);
+        } else if top + self.row_height > self.scroll + self.surface.height() {
+            self.set_scroll(top + self.row_height - self.surface.height());
+        }
+    }
+
+    fn bar(&self) -> bool {
+        self.total_height() > self.surface.height()
+    }
+
+    fn view(&self) -> FRect {
+        if self.style.placement == ScrollBarPlacement::Gutter && self.bar() {
+            FRect::new(
+                self.surface.x(),
+                self.surface.y(),
+                self.surface.width() - self.style.thickness,
+                self.surface.height(),
+            )
+        } else {
+            self.surface
+        }
+    }
+
+    fn track(&self) -> FRect {
+        FRect::new(
+            self.surface.x() + self.surface.width() - self.style.thickness,
+            self.surface.y(),
+            self.style.thickness,
+            self.surface.height(),
+        )
+    }
+
+    fn thumb(&self) -> FRect {
+        let track = self.track();
+        let h = (track.height() * track.height() / self.total_height())
+            .max(self.style.min_thumb_size)
+            .min(track.height());
+        let max_scroll = self.max_scroll();
+        let ratio = if max_scroll > 0. {
+            self.scroll / max_scroll
+        } else {
+            0.
+        };
+        FRect::new(
+            track.x(),
+            track.y() + ratio * (track.height() - h),
+            track.width(),
+            h,
+        )
+    }
+
+    fn row_rect(&self, row: usize) -> FRect {
+        let view = self.view();
+        FRect::new(
+            view.x(),
+            view.y() + row as f32 * self.row_height - self.scroll,
+            view.width(),
+            self.row_height,
+        )
+    }
+
+    ///The row at the window position y.
+    fn row_at(&self, y: f32) -> Option<usize> {
+        let y = y - self.surface.y() + self.scroll;
+        if y < 0. {
+            return None;
+        }
+        Some((y / self.row_height) as usize).filter(|row| *row < self.rows.len())
+    }
+
+    ///Where the expand toggle of the row is.
+    fn toggle_rect(&self, row: usize) -> FRect {
+        let rect = self.row_rect(row);
+        let depth = self.rows[row].len() - 1;
+        FRect::new(
+            rect.x() + depth as f32 * self.indent,
+            rect.y(),
+            self.indent,
+            rect.height(),
+        )
+    }
+
+    fn drop_at(&self, y: f32) -> Option<(usize, DropPlace)> {
+        let row = self.row_at(y)?;
+        let rect = self.row_rect(row);
+        let place = if y < rect.y() + rect.height() / 4. {
+            DropPlace::Before
+        } else if y > rect.y() + rect.height() * 3. / 4. {
+            DropPlace::After
+        } else {
+            DropPlace::Into
+        };
+        Some((row, place))
+    }
+
+    fn select_row(
+        mut this: MutRef<Self>,
+        row: usize,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let Some(path) = this.rows.get(row).cloned() else {
+            return Ok(());
+        };
+        this.scroll_to(row);
+        if this.selected.as_ref() != Some(&path) {
+            this.selected = Some(path);
+            let t = this;
+            if let Some(selection_changed) = this.selection_changed.as_mut() {
+                selection_changed(t, parent, state, canvas)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///Up and down move the selection, right expands then enters and left collapses then leaves.
+    fn key_event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        keycode: Keycode,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let len = this.rows.len();
+        if len == 0 {
+            return Ok(());
+        }
+        let selected = this.selected.clone();
+        let row = selected
+            .as_ref()
+            .and_then(|path| this.rows.iter().position(|row| row == path));
+        let row = match (keycode, row, selected) {
+            (Keycode::Up, Some(row), _) => row.saturating_sub(1),
+            (Keycode::Down, Some(row), _) => (row + 1).min(len - 1),
+            (Keycode::Up | Keycode::Down | Keycode::Home, _, _) => 0,
+            (Keycode::End, _, _) => len - 1,
+            (Keycode::Right, Some(row), Some(path)) => {
+                let node = this.node(&path).ok_or(anyhow!("Checked"))?;
+                if node.expanded || !this.has_children(node) {
+                    let child = [path.as_slice(), &[0]].concat();
+                    this.rows
+                        .iter()
+                        .position(|row| *row == child)
+                        .unwrap_or(row)
+                } else {
+                    this.expand(&path)?;
+                    this.layout();
+                    row
+                }
+            }
+            (Keycode::Left, Some(row), Some(path)) => {
+                if this.node(&path).is_some_and(TreeNode::is_expanded) {
+                    this.collapse(&path);
+                    this.layout();
+                    row
+                } else {
+                    let up = &path[..path.len() - 1];
+                    this.rows.iter().position(|row| row == up).unwrap_or(row)
+                }
+            }
+            (Keycode::Return | Keycode::Space, Some(row), Some(path)) => {
+                this.toggle(&path)?;
+                this.layout();
+                row
+            }
+            _ => return Ok(()),
+        };
+        mark_handled();
+        Self::select_row(this, row, canvas, parent, state)
+    }
+
+    fn mouse_event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: &Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match *event {
+            Event::MouseMotion {
+                mousestate, x, y, ..
+            } if mousestate.left() => {
+                if this.thumb_selected {
+                    let track = this.track();
+                    let thumb = this.thumb();
+                    let ratio = ((y - track.y() - thumb.height() / 2.)
+                        / (track.height() - thumb.height()))
+                    .clamp(0., 1.);
+                    let max_scroll = this.max_scroll();
+                    this.set_scroll(ratio * max_scroll);
+                } else if let (Some((_, start)), true) = (this.pressed, this.moved.is_some()) {
+                    this.dragging |= (y - start).abs() > DRAG_THRESHOLD;
+                    if this.dragging {
+                        this.drop = this
+                            .surface
+                            .contains_point(FPoint::new(x, y))
+                            .then(|| this.drop_at(y))
+                            .flatten();
+                    }
+                }
+            }
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Left,
+                clicks,
+                x,
+                y,
+                ..
+            } => {
+                let point = FPoint::new(x, y);
+                this.focused = this.surface.contains_point(point);
+                if !this.focused {
+                    return Ok(());
+                }
+                if this.bar() && this.thumb().contains_point(point) {
+                    this.thumb_selected = true;
+                } else if let Some(row) = this.row_at(y) {
+                    let path = this.rows[row].clone();
+                    if clicks == 2 || this.toggle_rect(row).contains_point(point) {
+                        this.toggle(&path)?;
+                        this.layout();
+                    } else {
+                        this.pressed = Some((row, y));
+                    }
+                    Self::select_row(this, row, canvas, parent, state)?;
+                }
+            }
+            Event::MouseButtonDown { x, y, .. } => {
+                this.focused = this.surface.contains_point(FPoint::new(x, y));
+            }
+            Event::MouseButtonUp { .. } => {
+                if let (Some((from, _)), Some(drop)) = (this.pressed, this.drop) {
+                    Self::drop_node(this, from, drop, parent, state)?;
+                }
+                this.pressed = None;
+                this.dragging = false;
+                this.drop = None;
+                this.thumb_selected = false;
+            }
+            Event::MouseWheel {
+                scroll_y,
+                mouse_x,
+                mouse_y,
+                ..
+            } if this.surface.contains_point(FPoint::new(mouse_x, mouse_y)) => {
+                let scroll = this.scroll - scroll_y * this.wheel_step;
+                this.set_scroll(scroll);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn draw_toggle(&self, canvas: &Canvas<Window>, row: usize, color: Color) -> Result<()> {
+        let rect = self.toggle_rect(row);
+        let (cx, cy) = (rect.x() + rect.width() / 2., rect.y() + rect.height() / 2.);
+        let size = rect.width().min(rect.height()) / 4.;
+        let expanded = find(&self.roots, &self.rows[row]).is_some_and(TreeNode::is_expanded);
+        let ((x1, y1), (x2, y2), (x3, y3)) = if expanded {
+            (
+                (cx - size, cy - size / 2.),
+                (cx + size, cy - size / 2.),
+                (cx, cy + size),
+            )
+        } else {
+            (
+                (cx - size / 2., cy - size),
+                (cx - size / 2., cy + size),
+                (cx + size, cy),
+            )
+        };
+        canvas
+            .filled_trigon(
+                x1 as i16, y1 as i16, x2 as i16, y2 as i16, x3 as i16, y3 as i16, color,
+            )
+            .map_err(|e| anyhow!(e))
+    }
+
+    fn draw_rows(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        let front_color = (this.front_color)(this, parent, state);
+        let select_color = (this.select_color)(this, parent, state);
+        let first = (this.scroll / this.row_height) as usize;
+        let last = (((this.scroll + this.surface.height()) / this.row_height).ceil() as usize)
+            .min(this.rows.len());
+        for row in first..last {
+            let path = &this.rows[row];
+            let node = find(&this.roots, path).ok_or(anyhow!("Checked"))?;
+            let rect = this.row_rect(row);
+            if this.selected.as_ref() == Some(path) {
+                canvas.set_draw_color(select_color);
+                canvas.fill_frect(rect).map_err(|e| anyhow!(e))?;
+            }
+            if this.has_children(node) {
+                this.draw_toggle(canvas, row, front_color)?;
+            }
+            let left = path.len() as f32 * this.indent;
+            if let Some(text) = UIString::new(this.font, (this.label)(&node.item))? {
+                text.draw_left(
+                    canvas,
+                    FRect::new(
+                        rect.x() + left,
+                        rect.y(),
+                        (rect.width() - left).max(0.),
+                        rect.height(),
+                    ),
+                    front_color,
+                )?;
+            }
+        }
+        let Some((row, place)) = this.drop else {
+            return Ok(());
+        };
+        let rect = this.row_rect(row);
+        let left = rect.x() + (this.rows[row].len() - 1) as f32 * this.indent;
+        canvas.set_draw_color(front_color);
+        match place {
+            DropPlace::Before => canvas.draw_fline(
+                FPoint::new(left, rect.y()),
+                FPoint::new(rect.x() + rect.width(), rect.y()),
+            ),
+            DropPlace::After => canvas.draw_fline(
+                FPoint::new(left, rect.y() + rect.height()),
+                FPoint::new(rect.x() + rect.width(), rect.y() + rect.height()),
+            ),
+            DropPlace::Into => canvas.draw_frect(rect),
+        }
+        .map_err(|e| anyhow!(e))
+    }
+}
+
+impl<Parent: 'static, State: 'static, Item: 'static> UserControl<Parent, State>
+    for TreeView<Parent, State, Item>
+{
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        this.layout();
+        match event {
+            Event::ElementMove { x, y } => {
+                this.surface.set_x(x);
+                this.surface.set_y(y);
+                Ok(())
+            }
+            Event::ElementResize { width, height } => {
+                this.surface.set_width(width);
+                this.surface.set_height(height);
+                let scroll = this.scroll;
+                this.set_scroll(scroll);
+                Ok(())
+            }
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } if this.focused => Self::key_event(this, canvas, keycode, parent, state),
+            event => Self::mouse_event(this, canvas, &event, parent, state),
+        }
+    }
+
+    fn update(
+        mut this: MutRef<Self>,
+        _: &Canvas<Window>,
+        _: Duration,
+        _: MutRef<Parent>,
+        _: MutRef<State>,
+    ) -> Result<()> {
+        this.layout();
+        Ok(())
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        canvas.set_draw_color((this.back_color)(this, parent, state));
+        canvas.fill_frect(this.surface).map_err(|e| anyhow!(e))?;
+        clipped(canvas, this.view(), |canvas| {
+            Self::draw_rows(this, canvas, parent, state)
+        })?;
+        if !this.bar() || (this.style.auto_hide && !this.thumb_selected) {
+            return Ok(());
+        }
+        if let Some(track_color) = this.style.track_color {
+            canvas.set_draw_color(track_color);
+            canvas.fill_frect(this.track()).map_err(|e| anyhow!(e))?;
+        }
+        let color = (this.scroll_color)(this, parent, state);
+        canvas.set_draw_color(if this.thumb_selected {
+            this.style.thumb_pressed_color.unwrap_or(color)
+        } else {
+            color
+        });
+        canvas.fill_frect(this.thumb()).map_err(|e| anyhow!(e))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tree_view_test {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    use sdl2::keyboard::Mod;
+
+    use super::*;
+
+    type Moves = Rc<RefCell<Vec<(Vec<usize>, Vec<usize>)>>>;
+
+    fn tree_view(loads: Rc<Cell<usize>>, moves: Moves) -> TreeView<(), (), &'static str> {
+        TreeView::new(
+            UIString::no_font(),
+            vec![
+                TreeNode::new("a"),
+                TreeNode::with_children("b", vec![TreeNode::new("b0"), TreeNode::new("b1")]),
+                TreeNode::with_children("c", Vec::new()),
+            ],
+            Box::new(|item| (*item).to_owned()),
+            Box::new(|_, _, _| Color::WHITE),
+            Box::new(|_, _, _| Color::BLACK),
+        )
+        .loader(Box::new(move |item| {
+            loads.set(loads.get() + 1);
+            Ok(match *item {
+                "a" => vec!["a0", "a1"],
+                _ => Vec::new(),
+            })
+        }))
+        .moved(Box::new(move |_, from, to, _, _| {
+            moves.borrow_mut().push((from.to_vec(), to.to_vec()));
+            Ok(())
+        }))
+        .row_height(10.)
+    }
+
+    fn labels(tree: &TreeView<(), (), &'static str>) -> Vec<&'static str> {
+        tree.rows()
+            .iter()
+            .filter_map(|path| tree.node(path).map(|node| *node.item()))
+            .collect()
+    }
+
+    #[allow(clippy::too_many_lines)]
+    pub(crate) fn test_tree_view(canvas: &mut Canvas<Window>) {
+        let loads = Rc::new(Cell::new(0));
+        let moves = Moves::default();
+        let mut tree = tree_view(loads.clone(), moves.clone());
+        let mut tree = MutRef::new(&mut tree);
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        let canvas = &*canvas;
+        let send = move |event| UserControl::event(tree, canvas, event, unit, unit).expect("");
+        let update = move || {
+            UserControl::update(tree, canvas, Duration::ZERO, unit, unit).expect("");
+        };
+        let key = |keycode| Event::KeyDown {
+            keycode: Some(keycode),
+            scancode: None,
+            keymod: Mod::NOMOD,
+            repeat: false,
+        };
+        send(Event::ElementResize {
+            width: 100.,
+            height: 100.,
+        });
+        assert_eq!(labels(&tree), ["a", "b", "c"]);
+
+        //The children are loaded on the first expand only.
+        assert!(tree.has_children(&tree.roots()[0]));
+        assert!(!tree.has_children(&tree.roots()[2]));
+        assert_eq!(loads.get(), 0);
+        tree.expand(&[0]).expect("");
+        tree.collapse(&[0]);
+        tree.expand(&[0]).expect("");
+        assert_eq!(loads.get(), 1);
+        tree.expand(&[1]).expect("");
+        assert_eq!(loads.get(), 1);
+        update();
+        assert_eq!(labels(&tree), ["a", "a0", "a1", "b", "b0", "b1", "c"]);
+        assert_eq!(tree.rows()[4], [1, 0]);
+
+        //Hiding the selected node drops the selection.
+        tree.set_selected(Some(vec![0, 1]));
+        tree.collapse(&[0]);
+        update();
+        assert_eq!(labels(&tree), ["a", "b", "b0", "b1", "c"]);
+        assert_eq!(tree.selected(), None);
+
+        send(Event::MouseButtonDown {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 50.,
+            y: 5.,
+        });
+        send(Event::MouseButtonUp {
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 50.,
+            y: 5.,
+        });
+        assert_eq!(tree.selected(), Some([0].as_slice()));
+        for (keycode, selected) in [
+            (Keycode::Down, [1].as_slice()),
+            (Keycode::Right, &[1, 0]),
+            (Keycode::Left, &[1]),
+            (Keycode::Left, &[1]),
+            (Keycode::Up, &[0]),
+            (Keycode::Right, &[0]),
+            (Keycode::Right, &[0, 0]),
+            (Keycode::End, &[2]),
+            (Keycode::Home, &[0]),
+        ] {
+            send(key(keycode));
+            assert_eq!(tree.selected(), Some(selected));
+        }
+        assert_eq!(labels(&tree), ["a", "a0", "a1", "b", "c"]);
+        send(key(Keycode::Return));
+        assert_eq!(labels(&tree), ["a", "b", "c"]);
+
+        //Dropping before, after and into the rows.
+        tree.expand(&[0]).expect("");
+        tree.collapse(&[1]);
+        update();
+        assert_eq!(labels(&tree), ["a", "a0", "a1", "b", "c"]);
+        TreeView::drop_node(tree, 4, (1, DropPlace::Before), unit, unit).expect("");
+        assert_eq!(labels(&tree), ["a", "c", "a0", "a1", "b"]);
+        TreeView::drop_node(tree, 0, (4, DropPlace::After), unit, unit).expect("");
+        assert_eq!(labels(&tree), ["b", "a", "c", "a0", "a1"]);
+        assert_eq!(tree.selected(), Some([1].as_slice()));
+        TreeView::drop_node(tree, 4, (2, DropPlace::Into), unit, unit).expect("");
+        assert_eq!(labels(&tree), ["b", "a", "c", "a1", "a0"]);
+        assert_eq!(tree.node(&[1, 0]).map(TreeNode::is_expanded), Some(true));
+        assert_eq!(tree.node(&[1, 0, 0]).map(|node| *node.item()), Some("a1"));
+        //A node can't go into its own children.
+        TreeView::drop_node(tree, 1, (3, DropPlace::Into), unit, unit).expect("");
+        assert_eq!(labels(&tree), ["b", "a", "c", "a1", "a0"]);
+        //Rows that are gone abort the drop.
+        TreeView::drop_node(tree, 9, (0, DropPlace::Before), unit, unit).expect("");
+        TreeView::drop_node(tree, 0, (9, DropPlace::After), unit, unit).expect("");
+        assert_eq!(labels(&tree), ["b", "a", "c", "a1", "a0"]);
+        assert_eq!(
+            *moves.borrow(),
+            [
+                (vec![2], vec![0, 0]),
+                (vec![0], vec![1]),
+                (vec![1, 2], vec![1, 0, 0]),
+            ]
+        );
+    }
+}
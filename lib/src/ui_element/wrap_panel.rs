@@ -0,0 +1,265 @@
+use std::{marker::PhantomData, ops::Range, time::Duration};
+
+use anyhow::Result;
+use sdl2::{rect::FRect, render::Canvas, video::Window};
+
+use crate::{
+    event::Event,
+    refs::{MutRef, Ref},
+    ui_element::{place, stack_panel::StackItem, Alignment, Orientation},
+    user_control::UserControl,
+    zero,
+};
+
+///Puts its children one after the other along the orientation, starting a new line when there is no room left.</br>
+///The children take the size of their item, its alignment places them across the line.
+pub struct WrapPanel<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static> {
+    parent: PhantomData<Parent>,
+    statel: PhantomData<State>,
+    surface: FRect,
+    orientation: Orientation,
+    ///Between the children of a line.
+    spacing: f32,
+    ///Between the lines.
+    line_spacing: f32,
+    children: Vec<(Child, StackItem)>,
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    WrapPanel<Parent, State, Child>
+{
+    pub fn new(orientation: Orientation, children: Vec<(Child, StackItem)>) -> Self {
+        Self {
+            parent: PhantomData,
+            statel: PhantomData,
+            surface: zero(),
+            orientation,
+            spacing: 0.,
+            line_spacing: 0.,
+            children,
+        }
+    }
+
+    pub const fn surface(&self) -> FRect {
+        self.surface
+    }
+
+    pub const fn spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    pub const fn line_spacing(mut self, line_spacing: f32) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+
+    pub fn children(&self) -> &[(Child, StackItem)] {
+        &self.children
+    }
+
+    pub fn children_mut(&mut self) -> &mut Vec<(Child, StackItem)> {
+        &mut self.children
+    }
+
+    ///The children of each line with its thickness.</br>
+    ///The stretched children only count when the line has nothing else, since they take the size of the line.
+    fn lines(&self) -> Vec<(Range<usize>, f32)> {
+        let (_, space, _, _) = self.orientation.split(self.surface);
+        let mut lines = Vec::new();
+        let (mut start, mut used, mut thickness, mut stretched) = (0, 0., None, 0_f32);
+        for (index, (_, item)) in self.children.iter().enumerate() {
+            let (len, cross_len) = item.lens(self.orientation);
+            if index > start && used + self.spacing + len > space {
+                lines.push((start..index, thickness.unwrap_or(stretched)));
+                (start, used, thickness, stretched) = (index, 0., None, 0.);
+            }
+            used += if index > start { self.spacing } else { 0. } + len;
+            if item.alignment == Alignment::Stretch {
+                stretched = stretched.max(cross_len);
+            } else {
+                thickness = Some(thickness.unwrap_or(0_f32).max(cross_len));
+            }
+        }
+        if start < self.children.len() {
+            lines.push((start..self.children.len(), thickness.unwrap_or(stretched)));
+        }
+        lines
+    }
+
+    ///The length the lines take across the orientation, for a scroll view around the panel.
+    pub fn content_len(&self) -> f32 {
+        let lines = self.lines();
+        let thickness: f32 = lines.iter().map(|(_, thickness)| thickness).sum();
+        thickness + lines.len().saturating_sub(1) as f32 * self.line_spacing
+    }
+
+    fn reform(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        let lines = this.lines();
+        let (orientation, spacing, line_spacing) =
+            (this.orientation, this.spacing, this.line_spacing);
+        let (start, _, mut cross, _) = orientation.split(this.surface);
+        for (range, thickness) in lines {
+            let mut main = start;
+            for (child, item) in &mut this.children[range] {
+                let (len, cross_len) = item.lens(orientation);
+                let (child_cross, cross_len) = item.alignment.place(cross, thickness, cross_len);
+                place(
+                    child.into(),
+                    orientation.join(main, len, child_cross, cross_len),
+                    canvas,
+                    parent,
+                    state,
+                )?;
+                main += len + spacing;
+            }
+            cross += thickness + line_spacing;
+        }
+        Ok(())
+    }
+}
+
+impl<Parent: 'static, State: 'static, Child: UserControl<Parent, State> + 'static>
+    UserControl<Parent, State> for WrapPanel<Parent, State, Child>
+{
+    fn surface(this: Ref<Self>, _: Ref<Parent>, _: Ref<State>) -> FRect {
+        this.surface
+    }
+
+    fn event(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        event: Event,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        match event {
+            Event::ElementMove { x, y } => {
+                this.surface.set_x(x);
+                this.surface.set_y(y);
+                return Self::reform(this, canvas, parent, state);
+            }
+            Event::ElementResize { width, height } => {
+                this.surface.set_width(width);
+                this.surface.set_height(height);
+                return Self::reform(this, canvas, parent, state);
+            }
+            _ => {}
+        }
+        for (child, _) in this.children.iter_mut() {
+            UserControl::event(child.into(), canvas, event.clone(), parent, state)?;
+        }
+        Ok(())
+    }
+
+    fn update(
+        mut this: MutRef<Self>,
+        canvas: &Canvas<Window>,
+        elapsed: Duration,
+        parent: MutRef<Parent>,
+        state: MutRef<State>,
+    ) -> Result<()> {
+        for (child, _) in this.children.iter_mut() {
+            UserControl::update(child.into(), canvas, elapsed, parent, state)?;
+        }
+        Self::reform(this, canvas, parent, state)
+    }
+
+    fn draw(
+        this: Ref<Self>,
+        canvas: &mut Canvas<Window>,
+        parent: Ref<Parent>,
+        state: Ref<State>,
+    ) -> Result<()> {
+        for (child, _) in &this.children {
+            UserControl::draw(child.into(), canvas, parent, state)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod wrap_panel_test {
+    use crate::ui_element::stack_panel::stack_panel_test::block;
+
+    use super::*;
+
+    pub(crate) fn test_wrap_panel_flow(canvas: &mut Canvas<Window>) {
+        let mut wrap = WrapPanel::new(
+            Orientation::Horizontal,
+            vec![
+                (block(0., 0.), StackItem::new(20., 10.)),
+                (block(0., 0.), StackItem::new(20., 20.)),
+                (
+                    block(0., 0.),
+                    StackItem::new(20., 10.).alignment(Alignment::End),
+                ),
+                (block(0., 0.), StackItem::new(30., 10.)),
+                (
+                    block(0., 0.),
+                    StackItem::new(10., 5.).alignment(Alignment::Stretch),
+                ),
+                (
+                    block(0., 0.),
+                    StackItem::new(60., 8.).alignment(Alignment::Stretch),
+                ),
+            ],
+        )
+        .spacing(5.)
+        .line_spacing(2.);
+        let mut wrap = MutRef::new(&mut wrap);
+        let mut unit = ();
+        let unit = MutRef::new(&mut unit);
+        UserControl::event(
+            wrap,
+            canvas,
+            Event::ElementResize {
+                width: 70.,
+                height: 100.,
+            },
+            unit,
+            unit,
+        )
+        .expect("");
+
+        let surfaces: Vec<FRect> = wrap
+            .children()
+            .iter()
+            .map(|(child, _)| child.surface)
+            .collect();
+        assert_eq!(
+            surfaces,
+            [
+                FRect::new(0., 0., 20., 10.),
+                FRect::new(25., 0., 20., 20.),
+                FRect::new(50., 10., 20., 10.),
+                FRect::new(0., 22., 30., 10.),
+                FRect::new(35., 22., 10., 10.),
+                FRect::new(0., 34., 60., 8.),
+            ]
+        );
+        assert_eq!(wrap.content_len(), 42.);
+
+        //A thinner item makes its line thinner, the stretched child of the line follows it.
+        wrap.children_mut()[3].1 = StackItem::new(30., 4.);
+        UserControl::update(wrap, canvas, Duration::ZERO, unit, unit).expect("");
+        let surfaces: Vec<FRect> = wrap.children()[3..]
+            .iter()
+            .map(|(child, _)| child.surface)
+            .collect();
+        assert_eq!(
+            surfaces,
+            [
+                FRect::new(0., 22., 30., 4.),
+                FRect::new(35., 22., 10., 4.),
+                FRect::new(0., 28., 60., 8.),
+            ]
+        );
+        assert_eq!(wrap.content_len(), 36.);
+    }
+}